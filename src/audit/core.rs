@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+
+use crate::common::constant::AUDIT_LOG_TREE_NAME;
+use crate::raft::db::{
+    route::TableRoute,
+    table::{TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult},
+};
+
+use super::model::{AuditLogDo, AuditLogDto, AuditLogQueryParam};
+
+///
+/// 审计日志管理,日志通过raft table写入,各节点数据一致
+#[bean(inject)]
+#[derive(Default)]
+pub struct AuditLogManager {
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+}
+
+impl AuditLogManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn save_log(
+        raft_table_route: Option<Arc<TableRoute>>,
+        log_do: AuditLogDo,
+    ) -> anyhow::Result<()> {
+        let req = TableManagerReq::Set {
+            table_name: AUDIT_LOG_TREE_NAME.clone(),
+            key: log_do.id.as_bytes().to_owned(),
+            value: log_do.to_bytes(),
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(())
+    }
+
+    async fn query_page_list(
+        table_manager: Option<Addr<TableManager>>,
+        param: AuditLogQueryParam,
+    ) -> anyhow::Result<(usize, Vec<AuditLogDto>)> {
+        let mut total = 0;
+        let mut log_list = Vec::new();
+        if let Some(table_manager) = &table_manager {
+            let query_req = TableManagerQueryReq::QueryPageList {
+                table_name: AUDIT_LOG_TREE_NAME.clone(),
+                like_key: None,
+                offset: None,
+                limit: None,
+                is_rev: true,
+            };
+            if let TableManagerResult::PageListResult(_, list) =
+                table_manager.send(query_req).await??
+            {
+                for (_, v) in list {
+                    let log_do = AuditLogDo::from_bytes(&v)?;
+                    if !param.is_match(&log_do) {
+                        continue;
+                    }
+                    if total >= param.offset && log_list.len() < param.limit {
+                        log_list.push(log_do.into());
+                    }
+                    total += 1;
+                }
+            }
+        }
+        Ok((total, log_list))
+    }
+}
+
+impl Inject for AuditLogManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+    }
+}
+
+impl Actor for AuditLogManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("AuditLogManager started")
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<AuditLogResult>")]
+pub enum AuditLogReq {
+    Add(AuditLogDo),
+    QueryPageList(AuditLogQueryParam),
+}
+
+pub enum AuditLogResult {
+    None,
+    PageResult(usize, Vec<AuditLogDto>),
+}
+
+impl Handler<AuditLogReq> for AuditLogManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<AuditLogResult>>;
+
+    fn handle(&mut self, msg: AuditLogReq, _ctx: &mut Self::Context) -> Self::Result {
+        let raft_table_route = self.raft_table_route.clone();
+        let table_manager = self.table_manager.clone();
+        let fut = async move {
+            match msg {
+                AuditLogReq::Add(log_do) => {
+                    if let Err(err) = Self::save_log(raft_table_route, log_do).await {
+                        log::warn!("save audit log error,{}", err);
+                    }
+                    Ok(AuditLogResult::None)
+                }
+                AuditLogReq::QueryPageList(param) => {
+                    let (total, list) = Self::query_page_list(table_manager, param).await?;
+                    Ok(AuditLogResult::PageResult(total, list))
+                }
+            }
+        }
+        .into_actor(self)
+        .map(|res, _act, _ctx| res);
+        Box::pin(fut)
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::privilege::NamespaceGroupParam;
+use crate::now_millis_i64;
+
+const ONE_DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+const ID_TIME_LEN: usize = 13;
+const MASK_VALUE: &str = "******";
+///
+/// 未登录被拒绝的请求只在本地日志输出,每个间隔最多一条
+const UNAUTHORIZED_LOG_INTERVAL_MILLIS: i64 = 10_000;
+
+static UNAUTHORIZED_LOG_TIME: AtomicI64 = AtomicI64::new(0);
+static UNAUTHORIZED_SUPPRESSED: AtomicU64 = AtomicU64::new(0);
+
+lazy_static::lazy_static! {
+    /// 不记录审计日志的写请求:配置监听、心跳、集群内部请求及POST方式的查询
+    static ref IGNORE_AUDIT_PATH: Regex = Regex::new(
+        r"(?i)^(/nacos/v1/raft/.*|/nacos/v1/cs/configs/listener|/nacos/v1/ns/instance/beat|/rnacos/api/console/v2/metrics/timeline)$"
+    )
+    .unwrap();
+    /// 密码、token及配置内容(可能是cipher-配置的明文)不记录原值
+    static ref SENSITIVE_PARAM_NAMES: Vec<&'static str> = vec![
+        "password", "accesstoken", "token", "secretkey", "newpassword", "oldpassword",
+        "content", "betaips", "encrypteddatakey",
+    ];
+}
+
+pub const AUDIT_SOURCE_CONSOLE: &str = "console";
+pub const AUDIT_SOURCE_OPENAPI: &str = "openapi";
+pub const AUDIT_SOURCE_GRPC: &str = "grpc";
+
+///
+/// 已登录但没有权限被拒绝的请求记录的状态
+pub const AUDIT_STATUS_FORBIDDEN: u32 = 403;
+
+///
+/// 审计日志,记录写操作的操作人、操作内容、时间与来源
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct AuditLogDo {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(int64, tag = "2")]
+    pub op_time: i64,
+    #[prost(string, tag = "3")]
+    pub op_user: String,
+    #[prost(string, tag = "4")]
+    pub client_ip: String,
+    #[prost(string, tag = "5")]
+    pub source: String,
+    #[prost(string, tag = "6")]
+    pub method: String,
+    #[prost(string, tag = "7")]
+    pub path: String,
+    #[prost(string, tag = "8")]
+    pub query: String,
+    #[prost(string, tag = "9")]
+    pub namespace: String,
+    #[prost(string, tag = "10")]
+    pub group: String,
+    #[prost(string, tag = "11")]
+    pub resource: String,
+    #[prost(uint32, tag = "12")]
+    pub status: u32,
+}
+
+impl AuditLogDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogDto {
+    pub id: String,
+    pub op_time: i64,
+    pub op_user: String,
+    pub client_ip: String,
+    pub source: String,
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub namespace: String,
+    pub group: String,
+    pub resource: String,
+    pub status: u32,
+}
+
+impl From<AuditLogDo> for AuditLogDto {
+    fn from(value: AuditLogDo) -> Self {
+        Self {
+            id: value.id,
+            op_time: value.op_time,
+            op_user: value.op_user,
+            client_ip: value.client_ip,
+            source: value.source,
+            method: value.method,
+            path: value.path,
+            query: value.query,
+            namespace: value.namespace,
+            group: value.group,
+            resource: value.resource,
+            status: value.status,
+        }
+    }
+}
+
+///
+/// 从请求参数中提取的审计对象,兼容配置、服务与用户接口的参数命名
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRequestParam {
+    pub tenant: Option<String>,
+    pub namespace_id: Option<String>,
+    pub namespace: Option<String>,
+    pub group: Option<String>,
+    pub group_name: Option<String>,
+    pub data_id: Option<String>,
+    pub service_name: Option<String>,
+    pub username: Option<String>,
+}
+
+impl AuditRequestParam {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            tenant: self.tenant.or(other.tenant),
+            namespace_id: self.namespace_id.or(other.namespace_id),
+            namespace: self.namespace.or(other.namespace),
+            group: self.group.or(other.group),
+            group_name: self.group_name.or(other.group_name),
+            data_id: self.data_id.or(other.data_id),
+            service_name: self.service_name.or(other.service_name),
+            username: self.username.or(other.username),
+        }
+    }
+
+    ///
+    /// 资源优先取配置id,其次服务名、用户名
+    pub fn get_resource(&self) -> &str {
+        self.data_id
+            .as_ref()
+            .or(self.service_name.as_ref())
+            .or(self.username.as_ref())
+            .map(|e| e.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn to_namespace_group_param(&self) -> NamespaceGroupParam {
+        NamespaceGroupParam {
+            tenant: self.tenant.clone(),
+            namespace_id: self.namespace_id.clone(),
+            namespace: self.namespace.clone(),
+            group: self.group.clone(),
+            group_name: self.group_name.clone(),
+            service_name: self.service_name.clone(),
+        }
+    }
+}
+
+///
+/// 审计日志保留规则,值为0表示不限制
+/// 过期时间以最近一条日志的时间为基准,保证各节点结果一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditLogRetention {
+    pub max_count: usize,
+    pub max_age_millis: i64,
+}
+
+impl AuditLogRetention {
+    pub fn new(max_count: usize, max_days: u64) -> Self {
+        Self {
+            max_count,
+            max_age_millis: max_days as i64 * ONE_DAY_MILLIS,
+        }
+    }
+
+    ///
+    /// 移除超出保留规则的日志,返回移除的条数;最近一条始终保留
+    pub fn remove_expired(&self, data: &mut BTreeMap<Vec<u8>, Vec<u8>>) -> usize {
+        let latest_time = data
+            .keys()
+            .next_back()
+            .map(|k| AuditLogUtils::get_id_time(k))
+            .unwrap_or_default();
+        let mut count = 0;
+        while data.len() > 1 {
+            let key = data.keys().next().cloned().unwrap_or_default();
+            let over_count = self.max_count > 0 && data.len() > self.max_count;
+            let over_age = self.max_age_millis > 0
+                && AuditLogUtils::get_id_time(&key) < latest_time - self.max_age_millis;
+            if !over_count && !over_age {
+                break;
+            }
+            data.remove(&key);
+            count += 1;
+        }
+        count
+    }
+}
+
+impl Default for AuditLogRetention {
+    fn default() -> Self {
+        Self::new(100000, 30)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogQueryParam {
+    pub op_user: Option<String>,
+    pub source: Option<String>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub resource: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl AuditLogQueryParam {
+    pub fn is_match(&self, log_do: &AuditLogDo) -> bool {
+        if let Some(op_user) = &self.op_user {
+            if !log_do.op_user.contains(op_user.as_str()) {
+                return false;
+            }
+        }
+        if let Some(source) = &self.source {
+            if source.as_str() != log_do.source {
+                return false;
+            }
+        }
+        if let Some(method) = &self.method {
+            if !method.eq_ignore_ascii_case(&log_do.method) {
+                return false;
+            }
+        }
+        if let Some(path) = &self.path {
+            if !log_do.path.contains(path.as_str()) {
+                return false;
+            }
+        }
+        if let Some(resource) = &self.resource {
+            if !log_do.resource.contains(resource.as_str()) {
+                return false;
+            }
+        }
+        if let Some(start_time) = self.start_time {
+            if log_do.op_time < start_time {
+                return false;
+            }
+        }
+        if let Some(end_time) = self.end_time {
+            if log_do.op_time > end_time {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct AuditLogUtils;
+
+impl AuditLogUtils {
+    ///
+    /// 日志id: 13位毫秒时间 + 随机后缀,按记录时间有序
+    pub fn new_id(op_time: i64) -> String {
+        let suffix = uuid::Uuid::new_v4().to_string().replace('-', "");
+        format!("{:013}{}", op_time, &suffix[..6])
+    }
+
+    pub fn get_id_time(id: &[u8]) -> i64 {
+        id.get(..ID_TIME_LEN)
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// 只记录写请求
+    pub fn is_audit_request(method: &str, path: &str) -> bool {
+        !matches!(method, "GET" | "HEAD" | "OPTIONS") && !IGNORE_AUDIT_PATH.is_match(path)
+    }
+
+    pub fn get_source(path: &str) -> &'static str {
+        if path.starts_with("/rnacos/") {
+            AUDIT_SOURCE_CONSOLE
+        } else {
+            AUDIT_SOURCE_OPENAPI
+        }
+    }
+
+    ///
+    /// query中的密码、token、配置内容等敏感参数不记录原值
+    pub fn mask_query(query: &str) -> String {
+        if query.is_empty() {
+            return String::new();
+        }
+        match serde_urlencoded::from_str::<Vec<(String, String)>>(query) {
+            Ok(list) => {
+                let list: Vec<(String, String)> = list
+                    .into_iter()
+                    .map(|(k, v)| {
+                        if SENSITIVE_PARAM_NAMES.contains(&k.to_lowercase().as_str()) {
+                            (k, MASK_VALUE.to_owned())
+                        } else {
+                            (k, v)
+                        }
+                    })
+                    .collect();
+                serde_urlencoded::to_string(list).unwrap_or_default()
+            }
+            Err(_) => String::new(),
+        }
+    }
+
+    ///
+    /// 未登录或会话无效被拒绝的请求不写入审计日志(避免匿名请求通过raft刷写),只在本地限频输出
+    pub fn log_unauthorized(client_ip: &str, method: &str, path: &str) {
+        let now = now_millis_i64();
+        let last = UNAUTHORIZED_LOG_TIME.load(Ordering::Relaxed);
+        if now - last < UNAUTHORIZED_LOG_INTERVAL_MILLIS
+            || UNAUTHORIZED_LOG_TIME
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            UNAUTHORIZED_SUPPRESSED.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let suppressed = UNAUTHORIZED_SUPPRESSED.swap(0, Ordering::Relaxed);
+        log::warn!(
+            "reject unauthorized request,client_ip:{},method:{},path:{},suppressed:{}",
+            client_ip,
+            method,
+            path,
+            suppressed
+        );
+    }
+
+    pub fn build_log(
+        op_user: Option<Arc<String>>,
+        client_ip: Arc<String>,
+        method: &str,
+        path: &str,
+        query: &str,
+        param: &AuditRequestParam,
+    ) -> AuditLogDo {
+        let op_time = now_millis_i64();
+        let namespace_param = param.to_namespace_group_param();
+        //登录请求没有会话,使用登录的用户名
+        let op_user = match op_user {
+            Some(v) => v.as_ref().to_owned(),
+            None if path.ends_with("/login") => param.username.clone().unwrap_or_default(),
+            None => String::new(),
+        };
+        AuditLogDo {
+            id: Self::new_id(op_time),
+            op_time,
+            op_user,
+            client_ip: client_ip.as_ref().to_owned(),
+            source: Self::get_source(path).to_owned(),
+            method: method.to_owned(),
+            path: path.to_owned(),
+            query: Self::mask_query(query),
+            namespace: namespace_param.get_namespace().to_owned(),
+            group: namespace_param.get_group().unwrap_or_default().to_owned(),
+            resource: param.get_resource().to_owned(),
+            status: 0,
+        }
+    }
+
+    ///
+    /// gRPC写请求的审计日志,path记录请求类型
+    pub fn build_grpc_log(
+        op_user: Option<Arc<String>>,
+        client_ip: Arc<String>,
+        request_type: &str,
+        param: &AuditRequestParam,
+    ) -> AuditLogDo {
+        let mut log_do = Self::build_log(op_user, client_ip, "GRPC", request_type, "", param);
+        log_do.source = AUDIT_SOURCE_GRPC.to_owned();
+        log_do
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_log_retention() {
+        let mut data = BTreeMap::new();
+        for i in 0..5i64 {
+            let id = AuditLogUtils::new_id(i * ONE_DAY_MILLIS);
+            assert_eq!(
+                AuditLogUtils::get_id_time(id.as_bytes()),
+                i * ONE_DAY_MILLIS
+            );
+            data.insert(id.into_bytes(), vec![]);
+        }
+        assert_eq!(AuditLogRetention::new(0, 3).remove_expired(&mut data), 1);
+        assert_eq!(AuditLogRetention::new(2, 0).remove_expired(&mut data), 2);
+        assert_eq!(AuditLogRetention::new(0, 0).remove_expired(&mut data), 0);
+        assert_eq!(data.len(), 2);
+        assert!(AuditLogUtils::is_audit_request(
+            "POST",
+            "/nacos/v1/cs/configs"
+        ));
+        assert!(!AuditLogUtils::is_audit_request(
+            "GET",
+            "/nacos/v1/cs/configs"
+        ));
+        assert!(!AuditLogUtils::is_audit_request(
+            "PUT",
+            "/nacos/v1/ns/instance/beat"
+        ));
+        assert_eq!(
+            AuditLogUtils::mask_query("dataId=a&password=123&accessToken=abc"),
+            "dataId=a&password=******&accessToken=******"
+        );
+        assert_eq!(
+            AuditLogUtils::mask_query("dataId=cipher-a&content=secret&betaIps=1.1.1.1"),
+            "dataId=cipher-a&content=******&betaIps=******"
+        );
+    }
+
+    #[test]
+    fn audit_grpc_log() {
+        let param: AuditRequestParam = serde_json::from_str(
+            r#"{"namespace":"dev","groupName":"g1","serviceName":"s1","instance":{"ip":"1.1.1.1"}}"#,
+        )
+        .unwrap();
+        let log_do = AuditLogUtils::build_grpc_log(
+            Some(Arc::new("u1".to_owned())),
+            Arc::new("127.0.0.1".to_owned()),
+            "InstanceRequest",
+            &param,
+        );
+        assert_eq!(log_do.source, AUDIT_SOURCE_GRPC);
+        assert_eq!(log_do.path, "InstanceRequest");
+        assert_eq!(log_do.op_user, "u1");
+        assert_eq!(log_do.namespace, "dev");
+        assert_eq!(log_do.group, "g1");
+        assert_eq!(log_do.resource, "s1");
+    }
+}
@@ -1,10 +1,15 @@
+use crate::audit::core::AuditLogManager;
 use crate::common::AppSysConfig;
+use crate::config::config_approval::ConfigApprovalManager;
 use crate::config::core::ConfigActor;
 use crate::grpc::bistream_manage::BiStreamManage;
 use crate::metrics::core::MetricsManager;
 use crate::naming::cluster::node_manage::{InnerNodeManage, NodeManage};
 use crate::naming::cluster::route::NamingRoute;
+use crate::naming::consul_index::ConsulIndexManage;
 use crate::naming::core::NamingActor;
+use crate::naming::eureka_delta::EurekaDeltaManage;
+use crate::naming::xds::core::XdsManage;
 use crate::raft::cache::route::CacheRoute;
 use crate::raft::cache::CacheManager;
 use crate::raft::cluster::route::ConfigRoute;
@@ -13,6 +18,7 @@ use crate::raft::db::table::TableManager;
 use crate::raft::filestore::core::FileStore;
 use crate::raft::network::factory::RaftClusterRequestSender;
 use crate::raft::NacosRaft;
+use crate::user::access_key::AccessKeyManager;
 use crate::user::UserManager;
 use actix::Addr;
 use bean_factory::FactoryData;
@@ -23,6 +29,9 @@ pub struct AppShareData {
     pub config_addr: Addr<ConfigActor>,
     pub naming_addr: Addr<NamingActor>,
     pub bi_stream_manage: Addr<BiStreamManage>,
+    pub xds_manage: Addr<XdsManage>,
+    pub consul_index_manage: Addr<ConsulIndexManage>,
+    pub eureka_delta_manage: Addr<EurekaDeltaManage>,
     pub raft: Arc<NacosRaft>,
     pub raft_store: Arc<FileStore>,
     pub sys_config: Arc<AppSysConfig>,
@@ -36,6 +45,9 @@ pub struct AppShareData {
     pub raft_cache_route: Arc<CacheRoute>,
     pub factory_data: FactoryData,
     pub user_manager: Addr<UserManager>,
+    pub access_key_manager: Addr<AccessKeyManager>,
+    pub config_approval_manager: Addr<ConfigApprovalManager>,
+    pub audit_log_manager: Addr<AuditLogManager>,
     pub cache_manager: Addr<CacheManager>,
     pub timezone_offset: Arc<FixedOffset>,
     pub metrics_manager: Addr<MetricsManager>,
@@ -13,12 +13,23 @@ pub const HTTP_METHOD_ALL: &str = EMPTY_STR;
 pub const SEQ_KEY_CONFIG: &str = "SEQ_CONFIG";
 
 pub const AUTHORIZATION_HEADER: &str = "Authorization";
+pub const CONSUL_TOKEN_HEADER: &str = "X-Consul-Token";
+pub const SPRING_CONFIG_TOKEN_HEADER: &str = "X-Config-Token";
 pub const ACCESS_TOKEN_HEADER: &str = "accessToken";
+pub const SPAS_ACCESS_KEY_HEADER: &str = "Spas-AccessKey";
+pub const SPAS_SIGNATURE_HEADER: &str = "Spas-Signature";
+pub const SIGN_TIMESTAMP_HEADER: &str = "Timestamp";
 
 lazy_static::lazy_static! {
     pub static ref CONFIG_TREE_NAME: Arc<String> =  Arc::new("T_CONFIG".to_string());
+    pub static ref CONFIG_HISTORY_TREE_NAME: Arc<String> =  Arc::new("T_CONFIG_HISTORY".to_string());
+    pub static ref CONFIG_SCHEMA_TREE_NAME: Arc<String> =  Arc::new("T_CONFIG_SCHEMA".to_string());
+    pub static ref CONFIG_APPROVAL_TREE_NAME: Arc<String> =  Arc::new("T_CONFIG_APPROVAL".to_string());
     pub static ref SEQUENCE_TREE_NAME: Arc<String> =  Arc::new("T_SEQUENCE".to_string());
     pub static ref USER_TREE_NAME: Arc<String> =  Arc::new("T_USER".to_string());
+    pub static ref AUDIT_LOG_TREE_NAME: Arc<String> =  Arc::new("T_AUDIT_LOG".to_string());
+    pub static ref ACCESS_KEY_TREE_NAME: Arc<String> =  Arc::new("T_ACCESS_KEY".to_string());
     pub static ref CACHE_TREE_NAME: Arc<String> =  Arc::new("T_CACHE".to_string());
+    pub static ref NAMING_INSTANCE_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_INSTANCE".to_string());
     pub static ref EMPTY_ARC_STRING: Arc<String> = Arc::new("".to_string());
 }
@@ -16,11 +16,8 @@ pub fn encode_base64(data: &[u8]) -> String {
 /// key,iv长度需要是16的倍数
 pub fn encrypt_aes128(key: &str, iv: &str, plain: &[u8]) -> anyhow::Result<Vec<u8>> {
     let pt_len = plain.len();
-    let buf_len = if pt_len % 48 == 0 {
-        pt_len
-    } else {
-        (48 - pt_len % 48) + pt_len
-    };
+    //pkcs7填充至少增加一个块
+    let buf_len = (pt_len / 16 + 1) * 16;
     let mut buf = vec![0u8; buf_len];
     (buf[..pt_len]).copy_from_slice(plain);
     match Aes128CbcEnc::new(key.as_bytes().into(), iv.as_bytes().into())
@@ -75,6 +75,28 @@ pub struct AppSysConfig {
     pub metrics_collect_interval_second: u64,
     pub metrics_log_interval_second: u64,
     pub console_captcha_enable: bool,
+    pub config_cipher_keys: String,
+    pub config_history_max_count: usize,
+    pub config_history_max_days: u64,
+    pub config_history_namespace_retention: String,
+    pub audit_log_max_count: usize,
+    pub audit_log_max_days: u64,
+    pub dns_port: u16,
+    pub dns_domain: String,
+    pub dns_ttl: u32,
+    pub consul_api_enable: bool,
+    pub consul_namespace: Arc<String>,
+    pub consul_group: Arc<String>,
+    pub eureka_api_enable: bool,
+    pub eureka_namespace: Arc<String>,
+    pub eureka_group: Arc<String>,
+    pub xds_enable: bool,
+    pub xds_namespace: Arc<String>,
+    pub xds_group: Arc<String>,
+    pub spring_config_api_enable: bool,
+    pub spring_config_namespace: Arc<String>,
+    pub spring_config_group: Arc<String>,
+    pub spring_config_profile_to_namespace: bool,
 }
 
 impl AppSysConfig {
@@ -168,6 +190,88 @@ impl AppSysConfig {
             .unwrap_or("true".to_owned())
             .parse()
             .unwrap_or(true);
+        let config_cipher_keys = std::env::var("RNACOS_CONFIG_CIPHER_KEYS").unwrap_or_default();
+        let config_history_max_count = std::env::var("RNACOS_CONFIG_HISTORY_MAX_COUNT")
+            .unwrap_or("100".to_owned())
+            .parse()
+            .unwrap_or(100);
+        let config_history_max_days = std::env::var("RNACOS_CONFIG_HISTORY_MAX_DAYS")
+            .unwrap_or("0".to_owned())
+            .parse()
+            .unwrap_or(0);
+        let config_history_namespace_retention =
+            std::env::var("RNACOS_CONFIG_HISTORY_NAMESPACE_RETENTION").unwrap_or_default();
+        let audit_log_max_count = std::env::var("RNACOS_AUDIT_LOG_MAX_COUNT")
+            .unwrap_or("100000".to_owned())
+            .parse()
+            .unwrap_or(100000);
+        let audit_log_max_days = std::env::var("RNACOS_AUDIT_LOG_MAX_DAYS")
+            .unwrap_or("30".to_owned())
+            .parse()
+            .unwrap_or(30);
+        let dns_port = std::env::var("RNACOS_DNS_PORT")
+            .unwrap_or("0".to_owned())
+            .parse()
+            .unwrap_or(0);
+        let dns_domain = StringUtils::map_not_empty(std::env::var("RNACOS_DNS_DOMAIN").ok())
+            .unwrap_or("rnacos.local".to_owned());
+        let dns_ttl = std::env::var("RNACOS_DNS_TTL")
+            .unwrap_or("5".to_owned())
+            .parse()
+            .unwrap_or(5);
+        let consul_api_enable = std::env::var("RNACOS_ENABLE_CONSUL_API")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let consul_namespace = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_CONSUL_NAMESPACE").ok())
+                .unwrap_or("public".to_owned()),
+        );
+        let consul_group = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_CONSUL_GROUP").ok())
+                .unwrap_or("DEFAULT_GROUP".to_owned()),
+        );
+        let eureka_api_enable = std::env::var("RNACOS_ENABLE_EUREKA_API")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let eureka_namespace = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_EUREKA_NAMESPACE").ok())
+                .unwrap_or("public".to_owned()),
+        );
+        let eureka_group = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_EUREKA_GROUP").ok())
+                .unwrap_or("DEFAULT_GROUP".to_owned()),
+        );
+        let xds_enable = std::env::var("RNACOS_ENABLE_XDS")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let xds_namespace = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_XDS_NAMESPACE").ok())
+                .unwrap_or("public".to_owned()),
+        );
+        let xds_group = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_XDS_GROUP").ok())
+                .unwrap_or("DEFAULT_GROUP".to_owned()),
+        );
+        let spring_config_api_enable = std::env::var("RNACOS_ENABLE_SPRING_CONFIG_API")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let spring_config_namespace = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_SPRING_CONFIG_NAMESPACE").ok())
+                .unwrap_or("public".to_owned()),
+        );
+        let spring_config_group = Arc::new(
+            StringUtils::map_not_empty(std::env::var("RNACOS_SPRING_CONFIG_GROUP").ok())
+                .unwrap_or("DEFAULT_GROUP".to_owned()),
+        );
+        //profile映射方式:group或namespace
+        let spring_config_profile_to_namespace =
+            std::env::var("RNACOS_SPRING_CONFIG_PROFILE_MAPPING")
+                .map(|v| v.eq_ignore_ascii_case("namespace"))
+                .unwrap_or(false);
         if metrics_collect_interval_second < 1 {
             metrics_collect_interval_second = 1;
         }
@@ -208,6 +312,28 @@ impl AppSysConfig {
             metrics_collect_interval_second,
             metrics_log_interval_second,
             console_captcha_enable,
+            config_cipher_keys,
+            config_history_max_count,
+            config_history_max_days,
+            config_history_namespace_retention,
+            audit_log_max_count,
+            audit_log_max_days,
+            dns_port,
+            dns_domain,
+            dns_ttl,
+            consul_api_enable,
+            consul_namespace,
+            consul_group,
+            eureka_api_enable,
+            eureka_namespace,
+            eureka_group,
+            xds_enable,
+            xds_namespace,
+            xds_group,
+            spring_config_api_enable,
+            spring_config_namespace,
+            spring_config_group,
+            spring_config_profile_to_namespace,
         }
     }
 
@@ -222,6 +348,10 @@ impl AppSysConfig {
     pub fn get_http_console_addr(&self) -> String {
         format!("0.0.0.0:{}", &self.http_console_port)
     }
+
+    pub fn get_dns_addr(&self) -> String {
+        format!("0.0.0.0:{}", &self.dns_port)
+    }
 }
 
 /**
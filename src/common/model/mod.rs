@@ -2,6 +2,10 @@ use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
+use self::privilege::PrivilegeGroup;
+
+pub mod privilege;
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct ApiResultOld<T>
 where
@@ -89,6 +93,11 @@ pub struct UserSession {
     pub nickname: Option<String>,
     pub roles: Vec<Arc<String>>,
     pub extend_infos: HashMap<String, String>,
+    #[serde(default)]
+    pub namespace_privilege: Option<Arc<PrivilegeGroup>>,
+    /// 是否可以读取cipher-配置的明文
+    #[serde(default)]
+    pub config_decrypt: bool,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -96,4 +105,9 @@ pub struct TokenSession {
     pub username: Arc<String>,
     pub roles: Vec<Arc<String>>,
     pub extend_infos: HashMap<String, String>,
+    #[serde(default)]
+    pub namespace_privilege: Option<Arc<PrivilegeGroup>>,
+    /// 是否可以读取cipher-配置的明文
+    #[serde(default)]
+    pub config_decrypt: bool,
 }
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ConfigUtils;
+use crate::naming::DEFAULT_GROUP;
+
+///
+/// 命名空间权限项
+/// groups为空时表示命名空间下所有分组;分组支持`*`通配符
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespacePrivilege {
+    pub namespace_id: Arc<String>,
+    #[serde(default)]
+    pub groups: Vec<Arc<String>>,
+    #[serde(default)]
+    pub writable: bool,
+}
+
+impl NamespacePrivilege {
+    pub fn match_namespace(&self, namespace_id: &str) -> bool {
+        Self::normalize_namespace(&self.namespace_id) == Self::normalize_namespace(namespace_id)
+    }
+
+    pub fn match_group(&self, group: &str) -> bool {
+        self.groups.is_empty()
+            || self
+                .groups
+                .iter()
+                .any(|pattern| wildcard_match(pattern, group))
+    }
+
+    fn normalize_namespace(namespace_id: &str) -> String {
+        ConfigUtils::default_tenant(namespace_id.to_owned())
+    }
+}
+
+///
+/// 用户的命名空间权限;未启用时不限制
+/// config_decrypt与enabled无关,只有授权后才能读取cipher-配置的明文
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegeGroup {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub items: Vec<NamespacePrivilege>,
+    #[serde(default)]
+    pub config_decrypt: bool,
+}
+
+impl PrivilegeGroup {
+    ///
+    /// 校验是否有命名空间(及分组)的读写权限
+    /// group为空时只要命名空间下存在授权即可访问,只用于列表、搜索等跨分组的请求
+    pub fn check(&self, namespace_id: &str, group: Option<&str>, write: bool) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        self.items.iter().any(|item| {
+            item.match_namespace(namespace_id)
+                && (!write || item.writable)
+                && group.map(|g| item.match_group(g)).unwrap_or(true)
+        })
+    }
+}
+
+///
+/// 从请求参数中提取的命名空间与分组,兼容配置与服务接口的参数命名
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceGroupParam {
+    pub tenant: Option<String>,
+    pub namespace_id: Option<String>,
+    pub namespace: Option<String>,
+    pub group: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+}
+
+impl NamespaceGroupParam {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            tenant: self.tenant.or(other.tenant),
+            namespace_id: self.namespace_id.or(other.namespace_id),
+            namespace: self.namespace.or(other.namespace),
+            group: self.group.or(other.group),
+            group_name: self.group_name.or(other.group_name),
+            service_name: self.service_name.or(other.service_name),
+        }
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        self.tenant
+            .as_ref()
+            .or(self.namespace_id.as_ref())
+            .or(self.namespace.as_ref())
+            .map(|e| e.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn get_group(&self) -> Option<&str> {
+        if let Some(group) = self.group.as_ref().or(self.group_name.as_ref()) {
+            if !group.is_empty() {
+                return Some(group.as_str());
+            }
+        }
+        //服务名可能带有分组前缀: group@@service
+        self.service_name
+            .as_ref()
+            .and_then(|e| e.split_once("@@"))
+            .map(|(group, _)| group)
+    }
+
+    ///
+    /// 未指定分组时与接口处理保持一致,按默认分组校验
+    pub fn get_group_or_default(&self) -> &str {
+        self.get_group().unwrap_or(DEFAULT_GROUP)
+    }
+
+    pub fn check(&self, privilege: &PrivilegeGroup, write: bool) -> bool {
+        privilege.check(
+            self.get_namespace(),
+            Some(self.get_group_or_default()),
+            write,
+        )
+    }
+
+    ///
+    /// 列表、搜索类请求未指定分组时,匹配命名空间下任一授权分组
+    pub fn check_list(&self, privilege: &PrivilegeGroup, write: bool) -> bool {
+        privilege.check(self.get_namespace(), self.get_group(), write)
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == value;
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !value.starts_with(first) || value.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    if !value.ends_with(last) {
+        return false;
+    }
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privilege_check() {
+        let privilege = PrivilegeGroup {
+            enabled: true,
+            config_decrypt: false,
+            items: vec![
+                NamespacePrivilege {
+                    namespace_id: Arc::new("public".to_owned()),
+                    groups: vec![],
+                    writable: false,
+                },
+                NamespacePrivilege {
+                    namespace_id: Arc::new("dev".to_owned()),
+                    groups: vec![Arc::new("team_a*".to_owned())],
+                    writable: true,
+                },
+            ],
+        };
+        assert!(privilege.check("", Some("DEFAULT_GROUP"), false));
+        assert!(!privilege.check("", Some("DEFAULT_GROUP"), true));
+        assert!(privilege.check("dev", Some("team_a_order"), true));
+        assert!(!privilege.check("dev", Some("team_b"), false));
+        assert!(privilege.check("dev", None, false));
+        assert!(!privilege.check("test", None, false));
+        assert!(PrivilegeGroup::default().check("test", None, true));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "abc"));
+        assert!(wildcard_match("a*c", "abc"));
+        assert!(wildcard_match("a*b*c", "a_b_c"));
+        assert!(!wildcard_match("a*c", "ab"));
+        assert!(!wildcard_match("abc", "abcd"));
+    }
+
+    #[test]
+    fn test_param_group() {
+        let param = NamespaceGroupParam {
+            service_name: Some("g1@@svc".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(param.get_group(), Some("g1"));
+        assert_eq!(param.get_namespace(), "");
+    }
+
+    #[test]
+    fn test_param_without_group() {
+        let privilege = PrivilegeGroup {
+            enabled: true,
+            config_decrypt: false,
+            items: vec![NamespacePrivilege {
+                namespace_id: Arc::new("dev".to_owned()),
+                groups: vec![Arc::new("team_a*".to_owned())],
+                writable: true,
+            }],
+        };
+        let param = NamespaceGroupParam {
+            tenant: Some("dev".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(param.get_group_or_default(), DEFAULT_GROUP);
+        assert!(!param.check(&privilege, false));
+        assert!(!param.check(&privilege, true));
+        assert!(param.check_list(&privilege, false));
+
+        let param = NamespaceGroupParam {
+            tenant: Some("dev".to_owned()),
+            service_name: Some("team_a_order@@svc".to_owned()),
+            ..Default::default()
+        };
+        assert!(param.check(&privilege, true));
+    }
+}
@@ -1,8 +1,39 @@
-use actix_web::web;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use actix_web::dev::{self, ServiceRequest};
+use actix_web::http::Method;
+use actix_web::{web, HttpMessage, HttpRequest};
+use regex::Regex;
 use tokio_stream::StreamExt;
 
+use crate::audit::model::{AuditLogDo, AuditLogUtils, AuditRequestParam};
+use crate::common::model::privilege::{NamespaceGroupParam, PrivilegeGroup};
+use crate::common::model::{TokenSession, UserSession};
+
 const MAX_SIZE: usize = 10485760;
 
+lazy_static::lazy_static! {
+    /// 需要校验命名空间权限的配置与服务接口
+    pub static ref PRIVILEGE_CHECK_PATH: Regex = Regex::new(
+        r"(?i)^/(rnacos/api/console(/v2)?/(config|configs|cs|instance|instances|service|ns)|nacos/v[12]/(cs|ns))(/|$)"
+    )
+    .unwrap();
+    pub static ref IGNORE_PRIVILEGE_CHECK_PATH: Vec<&'static str> = vec![
+        "/nacos/v1/cs/configs/listener"
+    ];
+    /// 跨分组的列表、搜索、导入导出接口,未指定分组时按命名空间下任一授权分组校验
+    pub static ref PRIVILEGE_LIST_PATH: Regex = Regex::new(
+        r"(?i)^/(rnacos/api/console/(configs|ns/services|config/(download|import)|v2/(config/(list|download|import|approval/list)|service/list))|nacos/v[12]/(cs/(config/search|history/configs)|ns/(service/list|catalog/services)))$"
+    )
+    .unwrap();
+    /// 带search参数时为配置搜索
+    pub static ref CONFIG_SEARCH_PATH: Regex = Regex::new(
+        r"(?i)^/(rnacos/api/console|nacos/v1)/cs/configs$"
+    )
+    .unwrap();
+}
+
 pub async fn get_req_body(mut payload: web::Payload) -> anyhow::Result<Vec<u8>> {
     let mut body = web::BytesMut::new();
     while let Some(chunk) = payload.next().await {
@@ -15,3 +46,152 @@ pub async fn get_req_body(mut payload: web::Payload) -> anyhow::Result<Vec<u8>>
     }
     Ok(body.to_vec())
 }
+
+///
+/// 获取请求方的客户端ip(支持X-Forwarded-For等代理头)
+pub fn get_client_ip(req: &HttpRequest) -> Arc<String> {
+    let conn_info = req.connection_info();
+    let addr = conn_info.realip_remote_addr().unwrap_or_default();
+    let ip = match addr.parse::<SocketAddr>() {
+        Ok(v) => v.ip().to_string(),
+        Err(_) => addr.to_owned(),
+    };
+    Arc::new(ip)
+}
+
+///
+/// 是否可以读取cipher-配置的明文;未开启鉴权(没有会话)时不限制
+pub fn can_decrypt_config(req: &HttpRequest) -> bool {
+    let extensions = req.extensions();
+    if let Some(session) = extensions.get::<Arc<TokenSession>>() {
+        return session.config_decrypt;
+    }
+    if let Some(session) = extensions.get::<Arc<UserSession>>() {
+        return session.config_decrypt;
+    }
+    true
+}
+
+///
+/// 会话的命名空间权限;列表类查询按此过滤结果
+pub fn get_session_privilege(req: &HttpRequest) -> Option<Arc<PrivilegeGroup>> {
+    let extensions = req.extensions();
+    if let Some(session) = extensions.get::<Arc<TokenSession>>() {
+        return session.namespace_privilege.clone();
+    }
+    if let Some(session) = extensions.get::<Arc<UserSession>>() {
+        return session.namespace_privilege.clone();
+    }
+    None
+}
+
+pub fn bytes_to_payload(buf: web::Bytes) -> dev::Payload {
+    let (_, mut pl) = actix_http::h1::Payload::create(true);
+    pl.unread_data(buf);
+    dev::Payload::from(pl)
+}
+
+pub fn is_privilege_check_path(path: &str) -> bool {
+    PRIVILEGE_CHECK_PATH.is_match(path) && !IGNORE_PRIVILEGE_CHECK_PATH.contains(&path)
+}
+
+fn is_privilege_list_request(request: &ServiceRequest) -> bool {
+    let path = request.path();
+    if PRIVILEGE_LIST_PATH.is_match(path) {
+        return true;
+    }
+    CONFIG_SEARCH_PATH.is_match(path)
+        && request.method() == Method::GET
+        && serde_urlencoded::from_str::<Vec<(String, String)>>(request.query_string())
+            .map(|list| list.iter().any(|(k, _)| k == "search"))
+            .unwrap_or(false)
+}
+
+///
+/// 读取非GET、非multipart的请求体,读取后重新放回请求中
+pub async fn peek_request_body(request: &mut ServiceRequest) -> Option<web::Bytes> {
+    let is_multipart = request
+        .headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap_or_default().starts_with("multipart/"))
+        .unwrap_or(false);
+    if request.method() == Method::GET || is_multipart {
+        return None;
+    }
+    if let Ok(p) = request.extract::<web::Payload>().await {
+        if let Ok(v) = p.to_bytes().await {
+            request.set_payload(bytes_to_payload(v.clone()));
+            return Some(v);
+        }
+    }
+    None
+}
+
+///
+/// 从query、非multipart的请求体及tenant请求头中提取命名空间与分组参数
+pub async fn get_namespace_group_param(request: &mut ServiceRequest) -> NamespaceGroupParam {
+    let mut param = serde_urlencoded::from_str::<NamespaceGroupParam>(request.query_string())
+        .unwrap_or_default();
+    if let Some(v) = peek_request_body(request).await {
+        let body_param = serde_json::from_slice::<NamespaceGroupParam>(v.as_ref())
+            .or_else(|_| serde_urlencoded::from_bytes::<NamespaceGroupParam>(v.as_ref()))
+            .unwrap_or_default();
+        param = param.merge(body_param);
+    }
+    //配置导入通过请求头传递命名空间
+    if param.tenant.is_none() {
+        param.tenant = request
+            .headers()
+            .get("tenant")
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).to_string());
+    }
+    param
+}
+
+///
+/// 从query及请求体中提取审计日志需要的操作对象参数
+pub async fn get_audit_request_param(request: &mut ServiceRequest) -> AuditRequestParam {
+    let param =
+        serde_urlencoded::from_str::<AuditRequestParam>(request.query_string()).unwrap_or_default();
+    if let Some(v) = peek_request_body(request).await {
+        let body_param = serde_json::from_slice::<AuditRequestParam>(v.as_ref())
+            .or_else(|_| serde_urlencoded::from_bytes::<AuditRequestParam>(v.as_ref()))
+            .unwrap_or_default();
+        return param.merge(body_param);
+    }
+    param
+}
+
+///
+/// 构建写请求的审计日志,响应状态在请求处理完成后补充
+pub async fn build_audit_log(
+    request: &mut ServiceRequest,
+    op_user: Option<Arc<String>>,
+) -> AuditLogDo {
+    let param = get_audit_request_param(request).await;
+    let client_ip = get_client_ip(request.request());
+    AuditLogUtils::build_log(
+        op_user,
+        client_ip,
+        request.method().as_str(),
+        request.path(),
+        request.query_string(),
+        &param,
+    )
+}
+
+///
+/// 校验请求参数中的命名空间与分组是否在用户授权范围内
+/// GET请求视为读操作,其它视为写操作
+pub async fn check_request_privilege(
+    request: &mut ServiceRequest,
+    privilege: &PrivilegeGroup,
+) -> bool {
+    let write = request.method() != Method::GET;
+    let param = get_namespace_group_param(request).await;
+    if is_privilege_list_request(request) {
+        param.check_list(privilege, write)
+    } else {
+        param.check(privilege, write)
+    }
+}
@@ -0,0 +1,516 @@
+use std::sync::Arc;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use serde::{Deserialize, Serialize};
+
+use crate::common::constant::CONFIG_APPROVAL_TREE_NAME;
+use crate::common::model::privilege::PrivilegeGroup;
+use crate::config::config_crypto::ConfigCryptoManager;
+use crate::config::core::{ConfigActor, ConfigCmd, ConfigKey, ConfigResult};
+use crate::config::model::ConfigCasConflictError;
+use crate::config::utils::diff_util::{self, DiffLine};
+use crate::now_millis_i64;
+use crate::raft::cluster::model::SetConfigReq;
+use crate::raft::cluster::route::ConfigRoute;
+use crate::raft::db::{
+    route::TableRoute,
+    table::{TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ConfigApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ConfigApprovalStatus {
+    pub fn from_value(v: u32) -> Self {
+        match v {
+            1 => Self::Approved,
+            2 => Self::Rejected,
+            _ => Self::Pending,
+        }
+    }
+
+    pub fn get_value(&self) -> u32 {
+        match self {
+            Self::Pending => 0,
+            Self::Approved => 1,
+            Self::Rejected => 2,
+        }
+    }
+}
+
+///
+/// 待审批的配置变更
+/// base_md5为提交时的配置md5,审批通过时作为CAS条件发布
+/// cipher-配置的content与正式配置一样加密存储
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct ConfigApprovalDo {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub data_id: String,
+    #[prost(string, tag = "3")]
+    pub group: String,
+    #[prost(string, tag = "4")]
+    pub tenant: String,
+    #[prost(string, tag = "5")]
+    pub content: String,
+    #[prost(string, optional, tag = "6")]
+    pub config_type: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub desc: Option<String>,
+    #[prost(string, tag = "8")]
+    pub base_md5: String,
+    #[prost(string, tag = "9")]
+    pub author: String,
+    #[prost(int64, tag = "10")]
+    pub submit_time: i64,
+    #[prost(uint32, tag = "11")]
+    pub status: u32,
+    #[prost(string, tag = "12")]
+    pub reviewer: String,
+    #[prost(int64, tag = "13")]
+    pub review_time: i64,
+    #[prost(string, tag = "14")]
+    pub review_comment: String,
+}
+
+impl ConfigApprovalDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+
+    pub fn get_status(&self) -> ConfigApprovalStatus {
+        ConfigApprovalStatus::from_value(self.status)
+    }
+
+    pub fn to_key(&self) -> ConfigKey {
+        ConfigKey::new(&self.data_id, &self.group, &self.tenant)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigApprovalDto {
+    pub id: Option<Arc<String>>,
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+    pub content: Option<Arc<String>>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+    pub base_md5: Option<Arc<String>>,
+    pub author: Option<Arc<String>>,
+    pub submit_time: Option<i64>,
+    pub status: Option<ConfigApprovalStatus>,
+    pub reviewer: Option<Arc<String>>,
+    pub review_time: Option<i64>,
+    pub review_comment: Option<String>,
+    /// 与当前配置的差异,只在查询详情时返回
+    pub diff: Option<Vec<DiffLine>>,
+}
+
+impl From<ConfigApprovalDo> for ConfigApprovalDto {
+    fn from(value: ConfigApprovalDo) -> Self {
+        let status = value.get_status();
+        Self {
+            id: Some(Arc::new(value.id)),
+            data_id: Arc::new(value.data_id),
+            group: Arc::new(value.group),
+            tenant: Arc::new(value.tenant),
+            content: Some(Arc::new(value.content)),
+            config_type: value.config_type.map(Arc::new),
+            desc: value.desc.map(Arc::new),
+            base_md5: Some(Arc::new(value.base_md5)),
+            author: Some(Arc::new(value.author)),
+            submit_time: Some(value.submit_time),
+            status: Some(status),
+            reviewer: Some(Arc::new(value.reviewer)).filter(|e| !e.is_empty()),
+            review_time: Some(value.review_time).filter(|e| *e > 0),
+            review_comment: Some(value.review_comment).filter(|e| !e.is_empty()),
+            diff: None,
+        }
+    }
+}
+
+///
+/// 变更id: 13位毫秒时间 + 随机后缀,按提交时间有序
+fn new_approval_id() -> String {
+    let suffix = uuid::Uuid::new_v4().to_string().replace('-', "");
+    format!("{:013}{}", now_millis_i64(), &suffix[..6])
+}
+
+#[bean(inject)]
+#[derive(Default)]
+pub struct ConfigApprovalManager {
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+    config_route: Option<Arc<ConfigRoute>>,
+    config_addr: Option<Addr<ConfigActor>>,
+    crypto: Arc<ConfigCryptoManager>,
+}
+
+impl ConfigApprovalManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get_approval(
+        raft_table_route: &Option<Arc<TableRoute>>,
+        id: Arc<String>,
+    ) -> anyhow::Result<ConfigApprovalDo> {
+        if let Some(raft_table_route) = raft_table_route {
+            let query_req = TableManagerQueryReq::GetByArcKey {
+                table_name: CONFIG_APPROVAL_TREE_NAME.clone(),
+                key: id.clone(),
+            };
+            match raft_table_route.get_leader_data(query_req).await? {
+                TableManagerResult::Value(v) => ConfigApprovalDo::from_bytes(&v),
+                _ => Err(anyhow::anyhow!("not found config change {}", &id)),
+            }
+        } else {
+            Err(anyhow::anyhow!("raft_table_route is none "))
+        }
+    }
+
+    async fn save_approval(
+        raft_table_route: &Option<Arc<TableRoute>>,
+        approval_do: &ConfigApprovalDo,
+    ) -> anyhow::Result<()> {
+        let req = TableManagerReq::Set {
+            table_name: CONFIG_APPROVAL_TREE_NAME.clone(),
+            key: approval_do.id.as_bytes().to_owned(),
+            value: approval_do.to_bytes(),
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// 获取当前配置内容,配置不存在时返回None
+    async fn get_current_config(
+        config_addr: &Option<Addr<ConfigActor>>,
+        key: ConfigKey,
+        decrypt: bool,
+    ) -> anyhow::Result<Option<(Arc<String>, Arc<String>)>> {
+        if let Some(config_addr) = config_addr {
+            if let ConfigResult::Data { value, md5, .. } =
+                config_addr.send(ConfigCmd::GET(key, decrypt)).await??
+            {
+                return Ok(Some((value, md5)));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn review(
+        raft_table_route: Option<Arc<TableRoute>>,
+        config_route: Option<Arc<ConfigRoute>>,
+        crypto: Arc<ConfigCryptoManager>,
+        param: ConfigApprovalReviewParam,
+    ) -> anyhow::Result<ConfigApprovalDo> {
+        let mut approval_do = Self::get_approval(&raft_table_route, param.id.clone()).await?;
+        if approval_do.get_status() != ConfigApprovalStatus::Pending {
+            return Err(anyhow::anyhow!(
+                "the config change {} has been reviewed",
+                &param.id
+            ));
+        }
+        let reviewer = param.reviewer.unwrap_or_default();
+        if reviewer.is_empty()
+            || approval_do.author.is_empty()
+            || reviewer.as_str() == approval_do.author
+        {
+            return Err(anyhow::anyhow!(
+                "the config change must be reviewed by another user"
+            ));
+        }
+        if param.approve {
+            let config_route = config_route.ok_or(anyhow::anyhow!("config_route is none"))?;
+            let key = approval_do.to_key();
+            let content = crypto.decrypt(&key, &Arc::new(approval_do.content.clone()))?;
+            let mut req = SetConfigReq::new(key, content);
+            req.config_type = approval_do.config_type.clone().map(Arc::new);
+            req.desc = approval_do.desc.clone().map(Arc::new);
+            if !approval_do.author.is_empty() {
+                req.op_user = Some(Arc::new(approval_do.author.clone()));
+            }
+            req.cas_md5 = Some(Arc::new(approval_do.base_md5.clone()));
+            if let Err(err) = config_route.set_config(req).await {
+                if ConfigCasConflictError::is_conflict(&err) {
+                    return Err(anyhow::anyhow!(
+                        "the config has been changed after the change {} was submitted",
+                        &param.id
+                    ));
+                }
+                return Err(err);
+            }
+        }
+        approval_do.status = if param.approve {
+            ConfigApprovalStatus::Approved.get_value()
+        } else {
+            ConfigApprovalStatus::Rejected.get_value()
+        };
+        approval_do.reviewer = reviewer.as_ref().to_owned();
+        approval_do.review_time = now_millis_i64();
+        approval_do.review_comment = param.comment.unwrap_or_default();
+        Self::save_approval(&raft_table_route, &approval_do).await?;
+        Ok(approval_do)
+    }
+}
+
+impl Inject for ConfigApprovalManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+        self.config_route = factory_data.get_bean();
+        self.config_addr = factory_data.get_actor();
+        if let Some(crypto) = factory_data.get_bean() {
+            self.crypto = crypto;
+        }
+    }
+}
+
+impl Actor for ConfigApprovalManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("ConfigApprovalManager started")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigApprovalQueryParam {
+    pub tenant: Option<Arc<String>>,
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub status: Option<ConfigApprovalStatus>,
+    /// 会话的命名空间权限,只返回有读权限的分组
+    pub privilege: Option<Arc<PrivilegeGroup>>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl ConfigApprovalQueryParam {
+    fn is_match(&self, approval_do: &ConfigApprovalDo) -> bool {
+        if let Some(tenant) = &self.tenant {
+            if tenant.as_str() != approval_do.tenant {
+                return false;
+            }
+        }
+        if let Some(data_id) = &self.data_id {
+            if !approval_do.data_id.contains(data_id.as_str()) {
+                return false;
+            }
+        }
+        if let Some(group) = &self.group {
+            if !approval_do.group.contains(group.as_str()) {
+                return false;
+            }
+        }
+        if let Some(status) = &self.status {
+            if *status != approval_do.get_status() {
+                return false;
+            }
+        }
+        if let Some(privilege) = &self.privilege {
+            if !privilege.check(&approval_do.tenant, Some(&approval_do.group), false) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigApprovalReviewParam {
+    pub id: Arc<String>,
+    pub reviewer: Option<Arc<String>>,
+    pub approve: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<ConfigApprovalResult>")]
+pub enum ConfigApprovalReq {
+    Submit(ConfigApprovalDto),
+    /// 第二个参数为是否解密cipher-配置的变更内容
+    Get(Arc<String>, bool),
+    Review(ConfigApprovalReviewParam),
+    QueryPageList(ConfigApprovalQueryParam),
+}
+
+pub enum ConfigApprovalResult {
+    Info(ConfigApprovalDto),
+    PageResult(usize, Vec<ConfigApprovalDto>),
+}
+
+impl Handler<ConfigApprovalReq> for ConfigApprovalManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<ConfigApprovalResult>>;
+
+    fn handle(&mut self, msg: ConfigApprovalReq, _ctx: &mut Self::Context) -> Self::Result {
+        let raft_table_route = self.raft_table_route.clone();
+        let table_manager = self.table_manager.clone();
+        let config_route = self.config_route.clone();
+        let config_addr = self.config_addr.clone();
+        let crypto = self.crypto.clone();
+        let fut = async move {
+            match msg {
+                ConfigApprovalReq::Submit(param) => {
+                    let key = ConfigKey::new_by_arc(
+                        param.data_id.clone(),
+                        param.group.clone(),
+                        param.tenant.clone(),
+                    );
+                    key.is_valid()?;
+                    let content = param.content.unwrap_or_default();
+                    let encrypted_content = crypto.encrypt(&key, content.clone())?;
+                    let base_md5 = Self::get_current_config(&config_addr, key, false)
+                        .await?
+                        .map(|(_, md5)| md5.as_ref().to_owned())
+                        .unwrap_or_default();
+                    let approval_do = ConfigApprovalDo {
+                        id: new_approval_id(),
+                        data_id: param.data_id.as_ref().to_owned(),
+                        group: param.group.as_ref().to_owned(),
+                        tenant: param.tenant.as_ref().to_owned(),
+                        content: encrypted_content.as_ref().to_owned(),
+                        config_type: param.config_type.map(|e| e.as_ref().to_owned()),
+                        desc: param.desc.map(|e| e.as_ref().to_owned()),
+                        base_md5,
+                        author: param
+                            .author
+                            .map(|e| e.as_ref().to_owned())
+                            .unwrap_or_default(),
+                        submit_time: now_millis_i64(),
+                        status: ConfigApprovalStatus::Pending.get_value(),
+                        reviewer: "".to_owned(),
+                        review_time: 0,
+                        review_comment: "".to_owned(),
+                    };
+                    Self::save_approval(&raft_table_route, &approval_do).await?;
+                    let mut dto: ConfigApprovalDto = approval_do.into();
+                    dto.content = Some(content);
+                    Ok(ConfigApprovalResult::Info(dto))
+                }
+                ConfigApprovalReq::Get(id, decrypt) => {
+                    let approval_do = Self::get_approval(&raft_table_route, id).await?;
+                    let is_pending = approval_do.get_status() == ConfigApprovalStatus::Pending;
+                    let key = approval_do.to_key();
+                    let mut dto: ConfigApprovalDto = approval_do.into();
+                    if decrypt {
+                        if let Some(content) = &dto.content {
+                            dto.content = Some(crypto.decrypt(&key, content)?);
+                        }
+                    }
+                    if is_pending {
+                        let current = Self::get_current_config(&config_addr, key, decrypt)
+                            .await?
+                            .map(|(value, _)| value)
+                            .unwrap_or_default();
+                        let content = dto.content.clone().unwrap_or_default();
+                        dto.diff = Some(diff_util::line_diff(&current, &content));
+                    }
+                    Ok(ConfigApprovalResult::Info(dto))
+                }
+                ConfigApprovalReq::Review(param) => {
+                    let approval_do =
+                        Self::review(raft_table_route, config_route, crypto, param).await?;
+                    Ok(ConfigApprovalResult::Info(approval_do.into()))
+                }
+                ConfigApprovalReq::QueryPageList(param) => {
+                    if let Some(table_manager) = &table_manager {
+                        let query_req = TableManagerQueryReq::QueryPageList {
+                            table_name: CONFIG_APPROVAL_TREE_NAME.clone(),
+                            like_key: None,
+                            offset: None,
+                            limit: None,
+                            is_rev: true,
+                        };
+                        if let TableManagerResult::PageListResult(_, list) =
+                            table_manager.send(query_req).await??
+                        {
+                            let mut total = 0;
+                            let mut approval_list = Vec::new();
+                            for (_, v) in list {
+                                let approval_do = ConfigApprovalDo::from_bytes(&v)?;
+                                if !param.is_match(&approval_do) {
+                                    continue;
+                                }
+                                if total >= param.offset && approval_list.len() < param.limit {
+                                    let mut dto: ConfigApprovalDto = approval_do.into();
+                                    //列表不返回内容
+                                    dto.content = None;
+                                    approval_list.push(dto);
+                                }
+                                total += 1;
+                            }
+                            return Ok(ConfigApprovalResult::PageResult(total, approval_list));
+                        }
+                    }
+                    Ok(ConfigApprovalResult::PageResult(0, vec![]))
+                }
+            }
+        }
+        .into_actor(self)
+        .map(|res, _act, _ctx| res);
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_approval_do_convert() {
+        let approval_do = ConfigApprovalDo {
+            id: new_approval_id(),
+            data_id: "app.yaml".to_owned(),
+            group: "DEFAULT_GROUP".to_owned(),
+            tenant: "prod".to_owned(),
+            content: "a: 1".to_owned(),
+            config_type: Some("yaml".to_owned()),
+            author: "dev".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(approval_do.id.len(), 19);
+        let approval_do = ConfigApprovalDo::from_bytes(&approval_do.to_bytes()).unwrap();
+        assert_eq!(approval_do.get_status(), ConfigApprovalStatus::Pending);
+        let param = ConfigApprovalQueryParam {
+            tenant: Some(Arc::new("prod".to_owned())),
+            status: Some(ConfigApprovalStatus::Pending),
+            ..Default::default()
+        };
+        assert!(param.is_match(&approval_do));
+        let param = ConfigApprovalQueryParam {
+            status: Some(ConfigApprovalStatus::Approved),
+            ..Default::default()
+        };
+        assert!(!param.is_match(&approval_do));
+        let dto: ConfigApprovalDto = approval_do.into();
+        assert_eq!(dto.reviewer, None);
+        assert_eq!(dto.config_type.unwrap().as_str(), "yaml");
+    }
+}
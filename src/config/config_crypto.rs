@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::common::crypto_utils::{decode_base64, decrypt_aes128, encode_base64, encrypt_aes128};
+use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
+
+///
+/// 需要加密存储的配置data_id前缀
+pub const CIPHER_DATA_ID_PREFIX: &str = "cipher-";
+
+///
+/// 已加密内容的标记前缀
+pub const ENCRYPTED_CONTENT_PREFIX: &str = "rnacos-enc:";
+
+const CIPHER_KEY_LEN: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct ConfigCipherKey {
+    pub key_id: Arc<String>,
+    key: String,
+}
+
+///
+/// 配置内容加解密管理
+/// 每个命名空间可配置多个密钥,第一个为当前加密使用的密钥,其余只用于解密(密钥轮换)
+#[derive(Debug, Clone, Default)]
+pub struct ConfigCryptoManager {
+    namespace_keys: HashMap<String, Vec<ConfigCipherKey>>,
+}
+
+impl ConfigCryptoManager {
+    ///
+    /// 配置格式: namespace:key_id:key,多个用逗号分隔;key长度需要为16
+    pub fn new_by_config(config: &str) -> anyhow::Result<Self> {
+        let mut namespace_keys: HashMap<String, Vec<ConfigCipherKey>> = HashMap::new();
+        for item in config
+            .split(',')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            let mut parts = item.splitn(3, ':');
+            let (namespace, key_id, key) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(namespace), Some(key_id), Some(key)) if !key_id.is_empty() => {
+                    (namespace, key_id, key)
+                }
+                _ => return Err(anyhow::anyhow!("config cipher key format error: {}", item)),
+            };
+            if key.len() != CIPHER_KEY_LEN {
+                return Err(anyhow::anyhow!(
+                    "config cipher key length must be {}, key_id: {}",
+                    CIPHER_KEY_LEN,
+                    key_id
+                ));
+            }
+            let namespace = ConfigUtils::default_tenant(namespace.to_owned());
+            let keys = namespace_keys.entry(namespace).or_default();
+            if keys.iter().any(|e| e.key_id.as_str() == key_id) {
+                return Err(anyhow::anyhow!(
+                    "config cipher key_id is repeated: {}",
+                    key_id
+                ));
+            }
+            keys.push(ConfigCipherKey {
+                key_id: Arc::new(key_id.to_owned()),
+                key: key.to_owned(),
+            });
+        }
+        Ok(Self { namespace_keys })
+    }
+
+    pub fn is_cipher_key(key: &ConfigKey) -> bool {
+        key.data_id.starts_with(CIPHER_DATA_ID_PREFIX)
+    }
+
+    pub fn is_encrypted(content: &str) -> bool {
+        content.starts_with(ENCRYPTED_CONTENT_PREFIX)
+    }
+
+    fn get_active_key(&self, key: &ConfigKey) -> Option<&ConfigCipherKey> {
+        if !Self::is_cipher_key(key) {
+            return None;
+        }
+        self.namespace_keys
+            .get(key.tenant.as_str())
+            .and_then(|keys| keys.first())
+    }
+
+    ///
+    /// 是否需要加密存储
+    pub fn need_encrypt(&self, key: &ConfigKey) -> bool {
+        self.get_active_key(key).is_some()
+    }
+
+    ///
+    /// 密文是否由当前密钥加密
+    pub fn is_active_encrypted(&self, key: &ConfigKey, content: &str) -> bool {
+        match (self.get_active_key(key), Self::split_content(content)) {
+            (Some(active_key), Some((key_id, _, _))) => active_key.key_id.as_str() == key_id,
+            _ => false,
+        }
+    }
+
+    ///
+    /// 使用命名空间当前密钥加密;不需要加密时返回原内容
+    pub fn encrypt(&self, key: &ConfigKey, content: Arc<String>) -> anyhow::Result<Arc<String>> {
+        let cipher_key = if let Some(v) = self.get_active_key(key) {
+            v
+        } else {
+            return Ok(content);
+        };
+        let iv = uuid::Uuid::new_v4().simple().to_string()[..CIPHER_KEY_LEN].to_owned();
+        let data = encrypt_aes128(&cipher_key.key, &iv, content.as_bytes())?;
+        Ok(Arc::new(format!(
+            "{}{}:{}:{}",
+            ENCRYPTED_CONTENT_PREFIX,
+            &cipher_key.key_id,
+            &iv,
+            encode_base64(&data)
+        )))
+    }
+
+    ///
+    /// 解密内容;非cipher-配置或非加密内容直接返回
+    pub fn decrypt(&self, key: &ConfigKey, content: &Arc<String>) -> anyhow::Result<Arc<String>> {
+        if !Self::is_cipher_key(key) {
+            return Ok(content.clone());
+        }
+        let (key_id, iv, data) = if let Some(v) = Self::split_content(content) {
+            v
+        } else {
+            return Ok(content.clone());
+        };
+        let cipher_key = self
+            .namespace_keys
+            .get(key.tenant.as_str())
+            .and_then(|keys| keys.iter().find(|e| e.key_id.as_str() == key_id))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "config cipher key is not exist, key_id: {}, config: {}",
+                    key_id,
+                    key.build_key()
+                )
+            })?;
+        let data = decrypt_aes128(&cipher_key.key, iv, &decode_base64(data)?)?;
+        Ok(Arc::new(String::from_utf8(data)?))
+    }
+
+    ///
+    /// 解密失败时保留密文
+    pub fn decrypt_or_raw(&self, key: &ConfigKey, content: &Arc<String>) -> Arc<String> {
+        match self.decrypt(key, content) {
+            Ok(v) => v,
+            Err(err) => {
+                log::warn!("decrypt config error,{}", err);
+                content.clone()
+            }
+        }
+    }
+
+    fn split_content(content: &str) -> Option<(&str, &str, &str)> {
+        let content = content.strip_prefix(ENCRYPTED_CONTENT_PREFIX)?;
+        let mut parts = content.splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(key_id), Some(iv), Some(data)) => Some((key_id, iv, data)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_crypto_rotate() {
+        let old_manager = ConfigCryptoManager::new_by_config("public:k1:0123456789abcdef").unwrap();
+        let manager = ConfigCryptoManager::new_by_config(
+            "public:k2:abcdef0123456789,public:k1:0123456789abcdef",
+        )
+        .unwrap();
+        let key = ConfigKey::new("cipher-db.yaml", "DEFAULT_GROUP", "");
+        let plain = Arc::new("password=123456".to_owned());
+        let old_content = old_manager.encrypt(&key, plain.clone()).unwrap();
+        assert!(ConfigCryptoManager::is_encrypted(&old_content));
+        assert!(!manager.is_active_encrypted(&key, &old_content));
+        assert_eq!(manager.decrypt(&key, &old_content).unwrap(), plain);
+        let content = manager.encrypt(&key, plain.clone()).unwrap();
+        assert!(manager.is_active_encrypted(&key, &content));
+        assert_eq!(manager.decrypt(&key, &content).unwrap(), plain);
+        assert!(old_manager.decrypt(&key, &content).is_err());
+
+        let plain_key = ConfigKey::new("db.yaml", "DEFAULT_GROUP", "");
+        assert_eq!(manager.encrypt(&plain_key, plain.clone()).unwrap(), plain);
+    }
+
+    #[test]
+    fn config_crypto_skip_plain_key() {
+        let manager = ConfigCryptoManager::new_by_config("public:k1:0123456789abcdef").unwrap();
+        let key = ConfigKey::new("cipher-db.yaml", "DEFAULT_GROUP", "");
+        let content = manager
+            .encrypt(&key, Arc::new("password=123456".to_owned()))
+            .unwrap();
+        let plain_key = ConfigKey::new("db.yaml", "DEFAULT_GROUP", "");
+        assert_eq!(manager.decrypt(&plain_key, &content).unwrap(), content);
+        assert_eq!(manager.decrypt_or_raw(&plain_key, &content), content);
+    }
+}
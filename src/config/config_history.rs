@@ -0,0 +1,209 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::common::byte_utils::{bin_to_id, id_to_bin};
+use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
+
+const HISTORY_KEY_SPLIT: u8 = 0x01;
+const ONE_DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+///
+/// 配置历史记录保留规则,值为0表示不限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryRetentionRule {
+    pub max_count: usize,
+    pub max_age_millis: i64,
+}
+
+impl HistoryRetentionRule {
+    pub fn new(max_count: usize, max_days: u64) -> Self {
+        Self {
+            max_count,
+            max_age_millis: max_days as i64 * ONE_DAY_MILLIS,
+        }
+    }
+}
+
+impl Default for HistoryRetentionRule {
+    fn default() -> Self {
+        Self::new(100, 0)
+    }
+}
+
+///
+/// 配置历史记录保留策略,支持按命名空间单独设置
+/// 集群各节点需保持一致
+#[derive(Debug, Clone, Default)]
+pub struct ConfigHistoryRetention {
+    default_rule: HistoryRetentionRule,
+    namespace_rules: HashMap<String, HistoryRetentionRule>,
+}
+
+impl ConfigHistoryRetention {
+    ///
+    /// 命名空间规则格式: namespace:max_count:max_days,多个用逗号分隔
+    pub fn new_by_config(
+        max_count: usize,
+        max_days: u64,
+        namespace_config: &str,
+    ) -> anyhow::Result<Self> {
+        let mut namespace_rules = HashMap::new();
+        for item in namespace_config
+            .split(',')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            let parts: Vec<&str> = item.split(':').collect();
+            if parts.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "config history retention format error: {}",
+                    item
+                ));
+            }
+            let count: usize = parts[1].trim().parse().map_err(|_| {
+                anyhow::anyhow!("config history retention max_count error: {}", item)
+            })?;
+            let days: u64 = parts[2].trim().parse().map_err(|_| {
+                anyhow::anyhow!("config history retention max_days error: {}", item)
+            })?;
+            namespace_rules.insert(
+                ConfigUtils::default_tenant(parts[0].trim().to_owned()),
+                HistoryRetentionRule::new(count, days),
+            );
+        }
+        Ok(Self {
+            default_rule: HistoryRetentionRule::new(max_count, max_days),
+            namespace_rules,
+        })
+    }
+
+    pub fn get_rule(&self, tenant: &str) -> &HistoryRetentionRule {
+        self.namespace_rules
+            .get(tenant)
+            .unwrap_or(&self.default_rule)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryIndexItem {
+    pub id: u64,
+    pub op_time: i64,
+}
+
+///
+/// 配置历史记录索引,只记录id与变更时间;历史内容存放在raft table中
+#[derive(Debug, Default)]
+pub struct ConfigHistoryIndex {
+    data: HashMap<ConfigKey, VecDeque<HistoryIndexItem>>,
+}
+
+impl ConfigHistoryIndex {
+    pub fn insert(&mut self, key: &ConfigKey, id: u64, op_time: i64) {
+        let list = self.data.entry(key.clone()).or_default();
+        let item = HistoryIndexItem { id, op_time };
+        match list.back() {
+            Some(last) if last.id >= id => {
+                if let Err(i) = list.binary_search_by_key(&id, |e| e.id) {
+                    list.insert(i, item);
+                }
+            }
+            _ => list.push_back(item),
+        }
+    }
+
+    ///
+    /// 按保留规则移除过期的历史记录,返回被移除的id;最近一条始终保留
+    /// 过期时间以最近一条记录的变更时间为基准,保证各节点结果一致
+    pub fn remove_expired(&mut self, key: &ConfigKey, rule: &HistoryRetentionRule) -> Vec<u64> {
+        let mut ret = vec![];
+        if let Some(list) = self.data.get_mut(key) {
+            let latest_time = list.back().map(|e| e.op_time).unwrap_or_default();
+            while list.len() > 1 {
+                let front = list.front().unwrap();
+                let over_count = rule.max_count > 0 && list.len() > rule.max_count;
+                let over_age =
+                    rule.max_age_millis > 0 && front.op_time < latest_time - rule.max_age_millis;
+                if !over_count && !over_age {
+                    break;
+                }
+                ret.push(front.id);
+                list.pop_front();
+            }
+        }
+        ret
+    }
+
+    pub fn remove(&mut self, key: &ConfigKey) -> Vec<u64> {
+        self.data
+            .remove(key)
+            .map(|list| list.into_iter().map(|e| e.id).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn count(&self, key: &ConfigKey) -> usize {
+        self.data.get(key).map(|e| e.len()).unwrap_or_default()
+    }
+}
+
+pub struct ConfigHistoryUtils;
+
+impl ConfigHistoryUtils {
+    ///
+    /// 同一配置的历史记录key前缀
+    pub fn build_prefix(key: &ConfigKey) -> Vec<u8> {
+        let mut v = key.build_key().into_bytes();
+        v.push(HISTORY_KEY_SPLIT);
+        v
+    }
+
+    ///
+    /// 历史记录key: 配置key + 分隔符 + 大端序id,保证同一配置的历史按id有序
+    pub fn build_key(key: &ConfigKey, id: u64) -> Vec<u8> {
+        let mut v = Self::build_prefix(key);
+        v.extend_from_slice(&id_to_bin(id));
+        v
+    }
+
+    pub fn parse_key(data: &[u8]) -> Option<(ConfigKey, u64)> {
+        if data.len() < 9 || data[data.len() - 9] != HISTORY_KEY_SPLIT {
+            return None;
+        }
+        let (key, id) = data.split_at(data.len() - 8);
+        let key = std::str::from_utf8(&key[..key.len() - 1]).ok()?;
+        Some((ConfigKey::from(key), bin_to_id(id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_history_retention() {
+        let retention = ConfigHistoryRetention::new_by_config(3, 0, "dev:0:1").unwrap();
+        let key = ConfigKey::new("app.yaml", "DEFAULT_GROUP", "");
+        let mut index = ConfigHistoryIndex::default();
+        for id in 1..=5 {
+            index.insert(&key, id, id as i64);
+        }
+        assert_eq!(
+            index.remove_expired(&key, retention.get_rule("")),
+            vec![1, 2]
+        );
+        assert_eq!(index.count(&key), 3);
+
+        let dev_key = ConfigKey::new("app.yaml", "DEFAULT_GROUP", "dev");
+        index.insert(&dev_key, 2, ONE_DAY_MILLIS);
+        index.insert(&dev_key, 1, 0);
+        index.insert(&dev_key, 3, ONE_DAY_MILLIS * 2);
+        assert_eq!(
+            index.remove_expired(&dev_key, retention.get_rule("dev")),
+            vec![1]
+        );
+        assert_eq!(index.remove(&dev_key), vec![2, 3]);
+
+        let data = ConfigHistoryUtils::build_key(&dev_key, 10);
+        assert!(data.starts_with(&ConfigHistoryUtils::build_prefix(&dev_key)));
+        assert_eq!(ConfigHistoryUtils::parse_key(&data), Some((dev_key, 10)));
+    }
+}
@@ -3,8 +3,10 @@ use std::{
     sync::Arc,
 };
 
+use crate::common::model::privilege::PrivilegeGroup;
 use crate::common::string_utils::StringUtils;
 use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
 
 #[derive(Debug, Clone, Default)]
 pub struct ConfigQueryParam {
@@ -13,7 +15,19 @@ pub struct ConfigQueryParam {
     pub data_id: Option<Arc<String>>,
     pub like_group: Option<String>,
     pub like_data_id: Option<String>,
+    pub app_name: Option<String>,
+    pub like_app_name: Option<String>,
+    /// 逗号分隔的标签,需要全部匹配
+    pub config_tags: Option<String>,
+    pub config_type: Option<String>,
+    /// 配置内容搜索关键字
+    pub content_keyword: Option<String>,
+    pub content_regex: bool,
     pub query_context: bool,
+    /// 是否解密cipher-配置内容,未授权时返回密文
+    pub decrypt: bool,
+    /// 会话的命名空间权限,只返回有读权限的分组
+    pub privilege: Option<Arc<PrivilegeGroup>>,
     pub offset: usize,
     pub limit: usize,
 }
@@ -28,6 +42,13 @@ impl ConfigQueryParam {
             true
         }
     }
+    pub fn match_privilege(&self, tenant: &str, g: &str) -> bool {
+        if let Some(privilege) = &self.privilege {
+            privilege.check(tenant, Some(g), false)
+        } else {
+            true
+        }
+    }
     pub fn match_data_id(&self, s: &Arc<String>) -> bool {
         if let Some(data_id) = &self.data_id {
             data_id.is_empty() || StringUtils::eq(s, data_id)
@@ -37,6 +58,45 @@ impl ConfigQueryParam {
             true
         }
     }
+
+    ///
+    /// 是否需要按配置内容中的属性过滤
+    pub fn has_value_filter(&self) -> bool {
+        !StringUtils::is_option_empty(&self.app_name)
+            || !StringUtils::is_option_empty(&self.like_app_name)
+            || !StringUtils::is_option_empty(&self.config_tags)
+            || !StringUtils::is_option_empty(&self.config_type)
+    }
+
+    pub fn match_config_type(&self, config_type: &Option<Arc<String>>) -> bool {
+        if let Some(t) = &self.config_type {
+            let s = config_type.as_ref().map(|e| e.as_str()).unwrap_or_default();
+            t.is_empty() || StringUtils::eq(s, t)
+        } else {
+            true
+        }
+    }
+
+    pub fn match_app_name(&self, app_name: &Option<Arc<String>>) -> bool {
+        let s = app_name.as_ref().map(|e| e.as_str()).unwrap_or_default();
+        if let Some(name) = &self.app_name {
+            name.is_empty() || StringUtils::eq(s, name)
+        } else if let Some(like_name) = &self.like_app_name {
+            like_name.is_empty() || StringUtils::like(s, like_name).is_some()
+        } else {
+            true
+        }
+    }
+
+    pub fn match_tags(&self, config_tags: &Option<Arc<String>>) -> bool {
+        if let Some(tags) = &self.config_tags {
+            let config_tags = config_tags.as_ref().map(|e| e.as_str()).unwrap_or_default();
+            ConfigUtils::split_tags(tags)
+                .all(|tag| ConfigUtils::split_tags(config_tags).any(|e| e == tag))
+        } else {
+            true
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -80,21 +140,28 @@ impl ConfigIndex {
         (b, self.group_data.len())
     }
 
-    pub(crate) fn query_config_page(
+    pub(crate) fn query_config_page<F>(
         &self,
         tenant: &Arc<String>,
         limit: usize,
         param: &ConfigQueryParam,
-    ) -> (usize, Vec<ConfigKey>) {
+        filter: &F,
+    ) -> (usize, Vec<ConfigKey>)
+    where
+        F: Fn(&ConfigKey) -> bool,
+    {
         let mut rlist = vec![];
         let end_index = param.offset + limit;
         let mut index = 0;
         for (g, set) in &self.group_data {
-            if param.match_group(g) {
+            if param.match_group(g) && param.match_privilege(tenant, g) {
                 for s in set {
                     if param.match_data_id(s) {
+                        let key = ConfigKey::new_by_arc(s.clone(), g.clone(), tenant.clone());
+                        if !filter(&key) {
+                            continue;
+                        }
                         if index >= param.offset && index < end_index {
-                            let key = ConfigKey::new_by_arc(s.clone(), g.clone(), tenant.clone());
                             rlist.push(key);
                         }
                         index += 1;
@@ -177,17 +244,30 @@ impl TenantIndex {
     }
 
     pub fn query_config_page(&self, param: &ConfigQueryParam) -> (usize, Vec<ConfigKey>) {
+        self.query_config_page_by_filter(param, |_| true)
+    }
+
+    ///
+    /// 分页查询,filter用于按索引外的属性过滤
+    pub fn query_config_page_by_filter<F>(
+        &self,
+        param: &ConfigQueryParam,
+        filter: F,
+    ) -> (usize, Vec<ConfigKey>)
+    where
+        F: Fn(&ConfigKey) -> bool,
+    {
         let mut rlist = vec![];
         let mut size = 0;
         let mut limit = param.limit;
         if let Some(tenant) = &param.tenant {
             if let Some(index) = self.tenant_group.get(tenant) {
-                return index.query_config_page(tenant, limit, param);
+                return index.query_config_page(tenant, limit, param, &filter);
             }
         } else {
             for (tenant, service_index) in &self.tenant_group {
                 let (sub_size, mut sub_list) =
-                    service_index.query_config_page(tenant, limit, param);
+                    service_index.query_config_page(tenant, limit, param, &filter);
                 size += sub_size;
                 limit -= sub_list.len();
                 rlist.append(&mut sub_list);
@@ -276,3 +356,66 @@ fn query_service() {
     assert!(size == 0);
     assert!(list.is_empty());
 }
+
+#[test]
+fn query_config_by_tags() {
+    let mut index = TenantIndex::new();
+    let key1 = ConfigKey::new("1", "1", "1");
+    let key2 = ConfigKey::new("2", "1", "1");
+    index.insert_config(key1.clone());
+    index.insert_config(key2.clone());
+    let tags = Some(Arc::new(ConfigUtils::normalize_tags(" b,a,, b")));
+    assert_eq!(tags.as_ref().unwrap().as_str(), "a,b");
+
+    let param = ConfigQueryParam {
+        config_tags: Some("b".to_owned()),
+        limit: 0xffff_ffff,
+        ..ConfigQueryParam::default()
+    };
+    assert!(param.has_value_filter());
+    assert!(param.match_tags(&tags));
+    assert!(!param.match_tags(&None));
+    let (size, list) = index.query_config_page_by_filter(&param, |key| {
+        param.match_tags(if key == &key1 { &tags } else { &None })
+    });
+    assert_eq!(size, 1);
+    assert_eq!(list[0], key1);
+}
+
+#[test]
+fn query_config_by_group_privilege() {
+    use crate::common::model::privilege::NamespacePrivilege;
+    let mut index = TenantIndex::new();
+    let key1 = ConfigKey::new("1", "team_a", "dev");
+    let key2 = ConfigKey::new("2", "team_a", "dev");
+    let key3 = ConfigKey::new("1", "team_b", "dev");
+    index.insert_config(key1.clone());
+    index.insert_config(key2.clone());
+    index.insert_config(key3.clone());
+
+    let privilege = PrivilegeGroup {
+        enabled: true,
+        config_decrypt: false,
+        items: vec![NamespacePrivilege {
+            namespace_id: Arc::new("dev".to_owned()),
+            groups: vec![Arc::new("team_a".to_owned())],
+            writable: false,
+        }],
+    };
+    let mut param = ConfigQueryParam {
+        tenant: Some(Arc::new("dev".to_owned())),
+        privilege: Some(Arc::new(privilege)),
+        limit: 0xffff_ffff,
+        ..ConfigQueryParam::default()
+    };
+    let (size, list) = index.query_config_page(&param);
+    assert_eq!(size, 2);
+    assert!(list.iter().all(|e| e.group.as_str() == "team_a"));
+
+    param.tenant = None;
+    param.offset = 1;
+    param.limit = 10;
+    let (size, list) = index.query_config_page(&param);
+    assert_eq!(size, 2);
+    assert_eq!(list, vec![key2]);
+}
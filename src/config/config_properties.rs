@@ -0,0 +1,311 @@
+use serde_json::{Map, Value};
+
+use crate::config::config_type::ConfigType;
+use crate::config::config_validate::ConfigContentValidator;
+
+///
+/// 还原嵌套结构时支持的最大数组下标,超出时属性名按普通名称处理,避免分配超大数组
+const MAX_ARRAY_INDEX: usize = 10000;
+
+///
+/// 把配置内容展开为spring风格的属性列表(`a.b[0].c`),用于按优先级合并多个配置
+pub struct ConfigPropertiesParser;
+
+impl ConfigPropertiesParser {
+    ///
+    /// 配置类型为空或text时按data_id后缀推断类型;不支持的类型返回None
+    pub fn get_config_type(config_type: Option<&str>, data_id: &str) -> Option<ConfigType> {
+        let config_type = match config_type {
+            Some(v) if !v.is_empty() && v != "text" => ConfigType::new_by_value(v),
+            _ => {
+                let suffix = data_id.rsplit_once('.').map(|(_, v)| v).unwrap_or_default();
+                match suffix {
+                    "yml" => ConfigType::Yaml,
+                    v => ConfigType::new_by_value(v),
+                }
+            }
+        };
+        match config_type {
+            ConfigType::Json | ConfigType::Yaml | ConfigType::Toml | ConfigType::Properties => {
+                Some(config_type)
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// 解析配置内容为展开后的属性;yaml多文档时后面的文档覆盖前面的同名属性
+    pub fn parse(config_type: &ConfigType, content: &str) -> anyhow::Result<Map<String, Value>> {
+        let mut properties = Map::new();
+        if config_type == &ConfigType::Properties {
+            for (key, value) in Self::parse_properties(content) {
+                properties.insert(key, Value::String(value));
+            }
+            return Ok(properties);
+        }
+        for value in ConfigContentValidator::to_json_values(config_type, content)? {
+            let mut items = vec![];
+            Self::flatten("", &value, &mut items);
+            properties.extend(items);
+        }
+        Ok(properties)
+    }
+
+    fn flatten(prefix: &str, value: &Value, items: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map {
+                    let key = if prefix.is_empty() {
+                        k.to_owned()
+                    } else {
+                        format!("{}.{}", prefix, k)
+                    };
+                    Self::flatten(&key, v, items);
+                }
+            }
+            Value::Array(list) => {
+                for (i, v) in list.iter().enumerate() {
+                    Self::flatten(&format!("{}[{}]", prefix, i), v, items);
+                }
+            }
+            Value::Null => items.push((prefix.to_owned(), Value::String("".to_owned()))),
+            _ => items.push((prefix.to_owned(), value.clone())),
+        }
+    }
+
+    ///
+    /// 按java properties格式解析,支持行尾`\`续行、`=`/`:`/空白分隔符及转义字符
+    pub fn parse_properties(content: &str) -> Vec<(String, String)> {
+        let mut properties: Vec<(String, String)> = vec![];
+        let mut lines = content.lines();
+        while let Some(line) = lines.next() {
+            let mut line = line.trim_start().to_owned();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+            while Self::is_continue_line(&line) {
+                line.pop();
+                match lines.next() {
+                    Some(next) => line.push_str(next.trim_start()),
+                    None => break,
+                }
+            }
+            let (key, value) = Self::split_key_value(&line);
+            let key = Self::unescape(key);
+            let value = Self::unescape(value);
+            match properties.iter_mut().find(|(k, _)| k == &key) {
+                Some(item) => item.1 = value,
+                None => properties.push((key, value)),
+            }
+        }
+        properties
+    }
+
+    fn is_continue_line(line: &str) -> bool {
+        line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+    }
+
+    fn split_key_value(line: &str) -> (&str, &str) {
+        let mut escape = false;
+        for (i, c) in line.char_indices() {
+            if escape {
+                escape = false;
+                continue;
+            }
+            match c {
+                '\\' => escape = true,
+                '=' | ':' | ' ' | '\t' | '\x0c' => {
+                    let key = &line[..i];
+                    let mut value = line[i..].trim_start_matches([' ', '\t', '\x0c']);
+                    if c != '=' && c != ':' {
+                        if let Some(v) = value.strip_prefix(['=', ':']) {
+                            value = v;
+                        }
+                    } else {
+                        value = &value[1..];
+                    }
+                    return (key, value.trim_start_matches([' ', '\t', '\x0c']));
+                }
+                _ => {}
+            }
+        }
+        (line, "")
+    }
+
+    fn unescape(v: &str) -> String {
+        let mut result = String::with_capacity(v.len());
+        let mut chars = v.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('t') => result.push('\t'),
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('f') => result.push('\x0c'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        result.push(c);
+                    }
+                }
+                Some(c) => result.push(c),
+                None => {}
+            }
+        }
+        result
+    }
+
+    ///
+    /// 输出为properties文本,属性按名称排序
+    pub fn to_properties_string(properties: &Map<String, Value>) -> String {
+        let mut items: Vec<(&String, &Value)> = properties.iter().collect();
+        items.sort_by(|a, b| a.0.cmp(b.0));
+        let mut content = String::new();
+        for (key, value) in items {
+            let value = match value {
+                Value::String(v) => v.to_owned(),
+                v => v.to_string(),
+            };
+            content.push_str(&format!(
+                "{}: {}\n",
+                key,
+                value.replace('\\', "\\\\").replace('\n', "\\n")
+            ));
+        }
+        content
+    }
+
+    ///
+    /// 把展开的属性还原为嵌套结构,用于输出yaml、json
+    pub fn to_nested_value(properties: &Map<String, Value>) -> Value {
+        let mut root = Value::Object(Map::new());
+        for (key, value) in properties {
+            let mut node = &mut root;
+            for part in Self::split_key(key) {
+                node = match part {
+                    KeyPart::Name(name) => {
+                        if !node.is_object() {
+                            *node = Value::Object(Map::new());
+                        }
+                        node.as_object_mut()
+                            .unwrap()
+                            .entry(name)
+                            .or_insert(Value::Null)
+                    }
+                    KeyPart::Index(index) => {
+                        if !node.is_array() {
+                            *node = Value::Array(vec![]);
+                        }
+                        let list = node.as_array_mut().unwrap();
+                        if list.len() <= index {
+                            list.resize(index + 1, Value::Null);
+                        }
+                        &mut list[index]
+                    }
+                };
+            }
+            *node = value.clone();
+        }
+        root
+    }
+
+    fn split_key(key: &str) -> Vec<KeyPart> {
+        let mut parts = vec![];
+        for segment in key.split('.') {
+            let (name, mut rest) = match segment.find('[') {
+                Some(i) => (&segment[..i], &segment[i..]),
+                None => (segment, ""),
+            };
+            let mut indexes = vec![];
+            while let Some((index, next)) = rest
+                .strip_prefix('[')
+                .and_then(|e| e.split_once(']'))
+                .and_then(|(i, next)| i.parse::<usize>().ok().map(|i| (i, next)))
+            {
+                indexes.push(index);
+                rest = next;
+            }
+            if indexes.iter().any(|e| *e > MAX_ARRAY_INDEX) {
+                parts.push(KeyPart::Name(segment.to_owned()));
+                continue;
+            }
+            if !name.is_empty() {
+                parts.push(KeyPart::Name(name.to_owned()));
+            }
+            parts.extend(indexes.into_iter().map(KeyPart::Index));
+        }
+        parts
+    }
+}
+
+enum KeyPart {
+    Name(String),
+    Index(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_properties() {
+        let content = "# comment\na=1\nb : x\\\n  y\nc d\ne=\\u4e2d\\tz\na=2";
+        let properties = ConfigPropertiesParser::parse_properties(content);
+        assert_eq!(
+            properties,
+            vec![
+                ("a".to_owned(), "2".to_owned()),
+                ("b".to_owned(), "xy".to_owned()),
+                ("c".to_owned(), "d".to_owned()),
+                ("e".to_owned(), "中\tz".to_owned()),
+            ]
+        );
+
+        let content = "server:\n  port: 8080\nlist:\n  - a\n  - b\n---\nserver:\n  port: 9090";
+        let properties = ConfigPropertiesParser::parse(&ConfigType::Yaml, content).unwrap();
+        assert_eq!(properties.len(), 3);
+        assert_eq!(properties.get("server.port"), Some(&Value::from(9090)));
+        assert_eq!(properties.get("list[1]"), Some(&Value::from("b")));
+        assert_eq!(
+            ConfigPropertiesParser::get_config_type(None, "app.yml"),
+            Some(ConfigType::Yaml)
+        );
+        assert_eq!(ConfigPropertiesParser::get_config_type(None, "app"), None);
+    }
+
+    #[test]
+    fn render_config_properties() {
+        let properties: Map<String, Value> = vec![
+            ("server.port".to_owned(), Value::from(8080)),
+            ("list[1]".to_owned(), Value::from("b")),
+            ("list[0]".to_owned(), Value::from("a")),
+            ("msg".to_owned(), Value::from("a\nb")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            ConfigPropertiesParser::to_nested_value(&properties),
+            serde_json::json!({"server": {"port": 8080}, "list": ["a", "b"], "msg": "a\nb"})
+        );
+        assert_eq!(
+            ConfigPropertiesParser::to_properties_string(&properties),
+            "list[0]: a\nlist[1]: b\nmsg: a\\nb\nserver.port: 8080\n"
+        );
+    }
+
+    #[test]
+    fn render_oversized_array_index() {
+        let properties: Map<String, Value> = vec![
+            ("list[10000]".to_owned(), Value::from("a")),
+            ("big[4294967295].name".to_owned(), Value::from("b")),
+        ]
+        .into_iter()
+        .collect();
+        let value = ConfigPropertiesParser::to_nested_value(&properties);
+        assert_eq!(value["list"].as_array().map(|e| e.len()), Some(10001));
+        assert_eq!(value["big[4294967295]"], serde_json::json!({"name": "b"}));
+    }
+}
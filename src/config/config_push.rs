@@ -0,0 +1,275 @@
+use std::{collections::HashMap, sync::Arc};
+
+use inner_mem_cache::TimeoutSet;
+use serde::{Deserialize, Serialize};
+
+use super::config_subscribe::ConfigListenerInfo;
+use super::core::ConfigKey;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ConfigPushStatus {
+    Pending,
+    Acked,
+    Failed,
+}
+
+///
+/// 客户端对某个配置最近一次变更推送的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPushState {
+    pub status: ConfigPushStatus,
+    pub retry_count: u32,
+    pub push_time: u64,
+    pub last_push_time: u64,
+    pub ack_time: u64,
+}
+
+///
+/// 配置推送状态视图,latest_md5为客户端应获取到的最新配置md5
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPushStatusInfo {
+    #[serde(flatten)]
+    pub listener: ConfigListenerInfo,
+    pub latest_md5: Arc<String>,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigPushCounter {
+    pub push_count: u64,
+    pub ack_count: u64,
+    pub retry_count: u64,
+    pub fail_count: u64,
+}
+
+struct PushItem {
+    request_id: Arc<String>,
+    state: ConfigPushState,
+}
+
+///
+/// 跟踪grpc配置变更推送的客户端应答;超时未应答时按指数退避重推,超过重试次数记为失败
+pub struct ConfigPushTracker {
+    items: HashMap<Arc<String>, HashMap<ConfigKey, PushItem>>,
+    pending: HashMap<(Arc<String>, Arc<String>), ConfigKey>,
+    timeout_set: TimeoutSet<(Arc<String>, Arc<String>)>,
+    ack_timeout: u64,
+    max_retry: u32,
+    counter: ConfigPushCounter,
+}
+
+impl Default for ConfigPushTracker {
+    fn default() -> Self {
+        Self::new(3000, 3)
+    }
+}
+
+impl ConfigPushTracker {
+    pub fn new(ack_timeout: u64, max_retry: u32) -> Self {
+        Self {
+            items: Default::default(),
+            pending: Default::default(),
+            timeout_set: Default::default(),
+            ack_timeout,
+            max_retry,
+            counter: Default::default(),
+        }
+    }
+
+    ///
+    /// 记录一次新的推送,覆盖该客户端对同一配置未完成的推送
+    pub fn push(
+        &mut self,
+        client_id: Arc<String>,
+        key: ConfigKey,
+        request_id: Arc<String>,
+        now: u64,
+    ) {
+        let state = ConfigPushState {
+            status: ConfigPushStatus::Pending,
+            retry_count: 0,
+            push_time: now,
+            last_push_time: now,
+            ack_time: 0,
+        };
+        let item = PushItem {
+            request_id: request_id.clone(),
+            state,
+        };
+        if let Some(old) = self
+            .items
+            .entry(client_id.clone())
+            .or_default()
+            .insert(key.clone(), item)
+        {
+            self.pending.remove(&(client_id.clone(), old.request_id));
+        }
+        self.add_pending(client_id, key, request_id, now, 0);
+        self.counter.push_count += 1;
+    }
+
+    ///
+    /// 重推时使用新的请求id,等待时间随重试次数翻倍
+    pub fn retry(
+        &mut self,
+        client_id: Arc<String>,
+        key: ConfigKey,
+        request_id: Arc<String>,
+        now: u64,
+    ) {
+        let retry_count = match self.items.get_mut(&client_id).and_then(|e| e.get_mut(&key)) {
+            Some(item) => {
+                item.request_id = request_id.clone();
+                item.state.retry_count += 1;
+                item.state.last_push_time = now;
+                item.state.retry_count
+            }
+            None => return,
+        };
+        self.add_pending(client_id, key, request_id, now, retry_count);
+        self.counter.retry_count += 1;
+    }
+
+    fn add_pending(
+        &mut self,
+        client_id: Arc<String>,
+        key: ConfigKey,
+        request_id: Arc<String>,
+        now: u64,
+        retry_count: u32,
+    ) {
+        let pending_key = (client_id, request_id);
+        self.pending.insert(pending_key.clone(), key);
+        self.timeout_set
+            .add(now + (self.ack_timeout << retry_count.min(16)), pending_key);
+    }
+
+    ///
+    /// 客户端应答推送,返回是否匹配到待确认的推送
+    pub fn ack(&mut self, client_id: Arc<String>, request_id: Arc<String>, now: u64) -> bool {
+        let key = match self.pending.remove(&(client_id.clone(), request_id)) {
+            Some(v) => v,
+            None => return false,
+        };
+        if let Some(item) = self.items.get_mut(&client_id).and_then(|e| e.get_mut(&key)) {
+            item.state.status = ConfigPushStatus::Acked;
+            item.state.ack_time = now;
+        }
+        self.counter.ack_count += 1;
+        true
+    }
+
+    ///
+    /// 返回超时需要重推的推送;超过重试次数的推送标记为失败
+    pub fn timeout(&mut self, now: u64) -> Vec<(Arc<String>, ConfigKey)> {
+        let mut retry_list = vec![];
+        for pending_key in self.timeout_set.timeout(now) {
+            let key = match self.pending.remove(&pending_key) {
+                Some(v) => v,
+                None => continue,
+            };
+            let client_id = pending_key.0;
+            if let Some(item) = self.items.get_mut(&client_id).and_then(|e| e.get_mut(&key)) {
+                if item.state.retry_count < self.max_retry {
+                    retry_list.push((client_id, key));
+                } else {
+                    item.state.status = ConfigPushStatus::Failed;
+                    self.counter.fail_count += 1;
+                    log::warn!(
+                        "config push failed,client_id:{},key:{}",
+                        &client_id,
+                        key.build_key()
+                    );
+                }
+            }
+        }
+        retry_list
+    }
+
+    pub fn remove_client(&mut self, client_id: &Arc<String>) {
+        if let Some(items) = self.items.remove(client_id) {
+            for (_, item) in items {
+                self.pending.remove(&(client_id.clone(), item.request_id));
+            }
+        }
+    }
+
+    pub fn get_state(&self, client_id: &Arc<String>, key: &ConfigKey) -> Option<ConfigPushState> {
+        self.items
+            .get(client_id)
+            .and_then(|e| e.get(key))
+            .map(|e| e.state.clone())
+    }
+
+    pub fn pending_size(&self) -> usize {
+        self.pending.len()
+    }
+
+    ///
+    /// 取出上次采集后的推送计数
+    pub fn take_counter(&mut self) -> ConfigPushCounter {
+        std::mem::take(&mut self.counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_push_tracker() {
+        let mut tracker = ConfigPushTracker::new(100, 1);
+        let client_id = Arc::new("c01".to_owned());
+        let key = ConfigKey::new("d01", "g01", "");
+        let other_key = ConfigKey::new("d02", "g01", "");
+        tracker.push(client_id.clone(), key.clone(), Arc::new("1".to_owned()), 0);
+        tracker.push(
+            client_id.clone(),
+            other_key.clone(),
+            Arc::new("2".to_owned()),
+            0,
+        );
+        assert!(tracker.ack(client_id.clone(), Arc::new("2".to_owned()), 10));
+        assert!(!tracker.ack(client_id.clone(), Arc::new("2".to_owned()), 10));
+        assert_eq!(
+            tracker.get_state(&client_id, &other_key).unwrap().status,
+            ConfigPushStatus::Acked
+        );
+
+        assert!(tracker.timeout(99).is_empty());
+        let retry_list = tracker.timeout(100);
+        assert_eq!(retry_list, vec![(client_id.clone(), key.clone())]);
+        tracker.retry(
+            client_id.clone(),
+            key.clone(),
+            Arc::new("3".to_owned()),
+            100,
+        );
+        //第一次重试的等待时间翻倍
+        assert!(tracker.timeout(299).is_empty());
+        assert!(tracker.timeout(300).is_empty());
+        let state = tracker.get_state(&client_id, &key).unwrap();
+        assert_eq!(state.status, ConfigPushStatus::Failed);
+        assert_eq!(state.retry_count, 1);
+
+        let counter = tracker.take_counter();
+        assert_eq!(counter.push_count, 2);
+        assert_eq!(counter.ack_count, 1);
+        assert_eq!(counter.retry_count, 1);
+        assert_eq!(counter.fail_count, 1);
+        assert_eq!(tracker.take_counter().push_count, 0);
+
+        tracker.push(
+            client_id.clone(),
+            key.clone(),
+            Arc::new("4".to_owned()),
+            300,
+        );
+        tracker.remove_client(&client_id);
+        assert_eq!(tracker.pending_size(), 0);
+        assert!(tracker.timeout(1000).is_empty());
+    }
+}
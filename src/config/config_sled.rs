@@ -346,6 +346,7 @@ mod tests {
             order_by_desc: None,
             limit: Some(10),
             offset: Some(0),
+            decrypt: false,
         };
         // let mut iter = config_db.config_history_db.iter();
         // while let Some(Ok((k, v))) = iter.next() {
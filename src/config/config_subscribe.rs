@@ -3,14 +3,97 @@ use std::{
     sync::Arc,
 };
 
+use super::config_push::ConfigPushState;
 use super::core::{ConfigKey, ListenerItem};
 use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd};
 use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ConfigListenType {
+    Grpc,
+    Http,
+}
+
+///
+/// 配置监听信息,md5为客户端最近一次上报的值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigListenerInfo {
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+    /// grpc连接id,http长轮询为空
+    pub client_id: Option<Arc<String>>,
+    pub client_ip: Arc<String>,
+    pub md5: Arc<String>,
+    pub listen_type: ConfigListenType,
+    /// grpc客户端最近一次变更推送的状态
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_state: Option<ConfigPushState>,
+}
+
+impl ConfigListenerInfo {
+    pub fn new(
+        key: &ConfigKey,
+        client_id: Option<Arc<String>>,
+        client_ip: Arc<String>,
+        md5: Arc<String>,
+        listen_type: ConfigListenType,
+    ) -> Self {
+        Self {
+            data_id: key.data_id.clone(),
+            group: key.group.clone(),
+            tenant: key.tenant.clone(),
+            client_id,
+            client_ip,
+            md5,
+            listen_type,
+            push_state: None,
+        }
+    }
+}
+
+///
+/// 监听查询条件:指定配置时查询监听该配置的客户端,否则按客户端查询其监听的配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigListenerQueryParam {
+    /// ConfigKey::build_key的值
+    pub key: Option<String>,
+    pub client_id: Option<Arc<String>>,
+    pub client_ip: Option<Arc<String>>,
+}
+
+impl ConfigListenerQueryParam {
+    pub fn get_key(&self) -> Option<ConfigKey> {
+        self.key.as_ref().map(|e| ConfigKey::from(e.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key.is_none() && self.client_id.is_none() && self.client_ip.is_none()
+    }
+
+    pub fn match_client(&self, client_id: Option<&Arc<String>>, client_ip: &Arc<String>) -> bool {
+        if let Some(v) = &self.client_id {
+            if client_id != Some(v) {
+                return false;
+            }
+        }
+        if let Some(v) = &self.client_ip {
+            if v != client_ip {
+                return false;
+            }
+        }
+        true
+    }
+}
 
 #[derive(Default)]
 pub struct Subscriber {
     listener: HashMap<ConfigKey, HashSet<Arc<String>>>,
-    client_keys: HashMap<Arc<String>, HashSet<ConfigKey>>,
+    client_keys: HashMap<Arc<String>, HashMap<ConfigKey, Arc<String>>>,
+    client_ips: HashMap<Arc<String>, Arc<String>>,
     conn_manage: Option<Addr<BiStreamManage>>,
 }
 
@@ -19,6 +102,7 @@ impl Subscriber {
         Self {
             listener: Default::default(),
             client_keys: Default::default(),
+            client_ips: Default::default(),
             conn_manage: Default::default(),
         }
     }
@@ -27,7 +111,12 @@ impl Subscriber {
         self.conn_manage = Some(conn_manage);
     }
 
-    pub fn add_subscribe(&mut self, client_id: Arc<String>, items: Vec<ListenerItem>) {
+    pub fn add_subscribe(
+        &mut self,
+        client_id: Arc<String>,
+        client_ip: Arc<String>,
+        items: Vec<ListenerItem>,
+    ) {
         for item in &items {
             match self.listener.get_mut(&item.key) {
                 Some(set) => {
@@ -40,19 +129,10 @@ impl Subscriber {
                 }
             };
         }
-        match self.client_keys.get_mut(&client_id) {
-            Some(set) => {
-                for item in items {
-                    set.insert(item.key);
-                }
-            }
-            None => {
-                let mut set = HashSet::new();
-                for item in items {
-                    set.insert(item.key);
-                }
-                self.client_keys.insert(client_id, set);
-            }
+        self.client_ips.insert(client_id.clone(), client_ip);
+        let keys = self.client_keys.entry(client_id).or_default();
+        for item in items {
+            keys.insert(item.key, item.md5);
         }
     }
 
@@ -81,13 +161,15 @@ impl Subscriber {
         };
         if remove_empty_client {
             self.client_keys.remove(&client_id);
+            self.client_ips.remove(&client_id);
         }
     }
 
     pub fn remove_client_subscribe(&mut self, client_id: Arc<String>) {
+        self.client_ips.remove(&client_id);
         if let Some(set) = self.client_keys.remove(&client_id) {
             let mut remove_keys = vec![];
-            for key in set {
+            for key in set.into_keys() {
                 if let Some(set) = self.listener.get_mut(&key) {
                     set.remove(&client_id);
                     if set.is_empty() {
@@ -114,6 +196,7 @@ impl Subscriber {
             }
             for key in &remove_keys {
                 self.client_keys.remove(key);
+                self.client_ips.remove(key);
             }
         }
     }
@@ -126,6 +209,56 @@ impl Subscriber {
         }
     }
 
+    ///
+    /// 查询grpc客户端的配置监听信息
+    pub fn query_listener(&self, param: &ConfigListenerQueryParam) -> Vec<ConfigListenerInfo> {
+        let mut list = vec![];
+        if let Some(key) = param.get_key() {
+            if let Some(set) = self.listener.get(&key) {
+                for client_id in set {
+                    let client_ip = self.get_client_ip(client_id);
+                    if !param.match_client(Some(client_id), &client_ip) {
+                        continue;
+                    }
+                    let md5 = self
+                        .client_keys
+                        .get(client_id)
+                        .and_then(|keys| keys.get(&key))
+                        .cloned()
+                        .unwrap_or_default();
+                    list.push(ConfigListenerInfo::new(
+                        &key,
+                        Some(client_id.clone()),
+                        client_ip,
+                        md5,
+                        ConfigListenType::Grpc,
+                    ));
+                }
+            }
+            return list;
+        }
+        for (client_id, keys) in &self.client_keys {
+            let client_ip = self.get_client_ip(client_id);
+            if !param.match_client(Some(client_id), &client_ip) {
+                continue;
+            }
+            for (key, md5) in keys {
+                list.push(ConfigListenerInfo::new(
+                    key,
+                    Some(client_id.clone()),
+                    client_ip.clone(),
+                    md5.clone(),
+                    ConfigListenType::Grpc,
+                ));
+            }
+        }
+        list
+    }
+
+    fn get_client_ip(&self, client_id: &Arc<String>) -> Arc<String> {
+        self.client_ips.get(client_id).cloned().unwrap_or_default()
+    }
+
     pub fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
@@ -150,3 +283,54 @@ impl Subscriber {
         sum
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_subscriber_listener() {
+        let mut subscriber = Subscriber::new();
+        let key = ConfigKey::new("app.yaml", "DEFAULT_GROUP", "");
+        let other_key = ConfigKey::new("db.yaml", "DEFAULT_GROUP", "");
+        let client_id = Arc::new("c1".to_owned());
+        subscriber.add_subscribe(
+            client_id.clone(),
+            Arc::new("10.0.0.1".to_owned()),
+            vec![
+                ListenerItem::new(key.clone(), Arc::new("md5_a".to_owned())),
+                ListenerItem::new(other_key.clone(), Arc::new("md5_b".to_owned())),
+            ],
+        );
+        subscriber.add_subscribe(
+            Arc::new("c2".to_owned()),
+            Arc::new("10.0.0.2".to_owned()),
+            vec![ListenerItem::new(key.clone(), Arc::new("".to_owned()))],
+        );
+        let param = ConfigListenerQueryParam {
+            key: Some(key.build_key()),
+            ..Default::default()
+        };
+        assert_eq!(subscriber.query_listener(&param).len(), 2);
+
+        let param = ConfigListenerQueryParam {
+            client_ip: Some(Arc::new("10.0.0.1".to_owned())),
+            ..Default::default()
+        };
+        let list = subscriber.query_listener(&param);
+        assert_eq!(list.len(), 2);
+        assert!(list
+            .iter()
+            .all(|e| e.client_id.as_ref() == Some(&client_id)));
+
+        subscriber.remove_config_key(other_key);
+        subscriber.remove_client_subscribe(Arc::new("c2".to_owned()));
+        let param = ConfigListenerQueryParam {
+            key: Some(key.build_key()),
+            ..Default::default()
+        };
+        let list = subscriber.query_listener(&param);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].md5.as_str(), "md5_a");
+    }
+}
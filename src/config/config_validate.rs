@@ -0,0 +1,249 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::config_type::ConfigType;
+
+const MAX_SCHEMA_ERRORS: usize = 5;
+
+///
+/// 发布配置时按配置类型校验内容格式,并支持按json schema校验
+pub struct ConfigContentValidator;
+
+impl ConfigContentValidator {
+    ///
+    /// 按配置类型校验内容,错误信息包含出错的行列位置;text、html类型不校验
+    pub fn validate(config_type: &ConfigType, content: &str) -> anyhow::Result<()> {
+        match config_type {
+            ConfigType::Json => {
+                serde_json::from_str::<serde::de::IgnoredAny>(content)
+                    .map_err(|e| Self::json_error(&e))?;
+            }
+            ConfigType::Yaml => {
+                for document in serde_yaml::Deserializer::from_str(content) {
+                    serde_yaml::Value::deserialize(document).map_err(|e| Self::yaml_error(&e))?;
+                }
+            }
+            ConfigType::Toml => {
+                toml::from_str::<toml::Table>(content)
+                    .map_err(|e| Self::toml_error(content, &e))?;
+            }
+            ConfigType::Xml => {
+                roxmltree::Document::parse(content).map_err(|e| {
+                    let pos = e.pos();
+                    Self::location_error("xml", pos.row as usize, pos.col as usize, &e)
+                })?;
+            }
+            ConfigType::Properties => Self::validate_properties(content)?,
+            ConfigType::Text | ConfigType::Html => {}
+        }
+        Ok(())
+    }
+
+    ///
+    /// 转换为json值用于schema校验,yaml多文档时每个文档对应一个值;不支持的类型返回空列表
+    pub fn to_json_values(config_type: &ConfigType, content: &str) -> anyhow::Result<Vec<Value>> {
+        let values = match config_type {
+            ConfigType::Json => {
+                vec![serde_json::from_str(content).map_err(|e| Self::json_error(&e))?]
+            }
+            ConfigType::Yaml => {
+                let mut values = vec![];
+                for document in serde_yaml::Deserializer::from_str(content) {
+                    let value = serde_yaml::Value::deserialize(document)
+                        .map_err(|e| Self::yaml_error(&e))?;
+                    values.push(serde_json::to_value(value)?);
+                }
+                values
+            }
+            ConfigType::Toml => {
+                let value = toml::from_str::<toml::Table>(content)
+                    .map_err(|e| Self::toml_error(content, &e))?;
+                vec![serde_json::to_value(value)?]
+            }
+            _ => vec![],
+        };
+        Ok(values)
+    }
+
+    ///
+    /// 校验json schema本身是否合法
+    pub fn check_schema(schema: &str) -> anyhow::Result<jsonschema::JSONSchema> {
+        let schema: Value = serde_json::from_str(schema)
+            .map_err(|e| anyhow::anyhow!("json schema is not valid json: {}", e))?;
+        jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| anyhow::anyhow!("json schema compile error: {}", e))
+    }
+
+    ///
+    /// 使用json schema校验配置内容
+    pub fn validate_schema(
+        schema: &str,
+        config_type: &ConfigType,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let compiled = Self::check_schema(schema)?;
+        for value in Self::to_json_values(config_type, content)? {
+            if let Err(errors) = compiled.validate(&value) {
+                let messages: Vec<String> = errors
+                    .take(MAX_SCHEMA_ERRORS)
+                    .map(|e| {
+                        let path = e.instance_path.to_string();
+                        if path.is_empty() {
+                            format!("/: {}", e)
+                        } else {
+                            format!("{}: {}", path, e)
+                        }
+                    })
+                    .collect();
+                return Err(anyhow::anyhow!(
+                    "config content does not match json schema, {}",
+                    messages.join("; ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// properties格式较宽松,只校验\uxxxx转义是否合法
+    fn validate_properties(content: &str) -> anyhow::Result<()> {
+        for (i, line) in content.lines().enumerate() {
+            let trim_line = line.trim_start();
+            if trim_line.starts_with('#') || trim_line.starts_with('!') {
+                continue;
+            }
+            let chars: Vec<char> = line.chars().collect();
+            let mut j = 0;
+            while j < chars.len() {
+                if chars[j] == '\\' {
+                    if chars.get(j + 1) == Some(&'u') {
+                        let hex: String = chars.iter().skip(j + 2).take(4).collect();
+                        if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                            return Err(Self::location_error(
+                                "properties",
+                                i + 1,
+                                j + 1,
+                                "malformed \\uxxxx encoding",
+                            ));
+                        }
+                        j += 6;
+                        continue;
+                    }
+                    j += 2;
+                    continue;
+                }
+                j += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn json_error(err: &serde_json::Error) -> anyhow::Error {
+        Self::location_error("json", err.line(), err.column(), err)
+    }
+
+    fn yaml_error(err: &serde_yaml::Error) -> anyhow::Error {
+        let (line, column) = err
+            .location()
+            .map(|e| (e.line(), e.column()))
+            .unwrap_or_default();
+        Self::location_error("yaml", line, column, err)
+    }
+
+    fn toml_error(content: &str, err: &toml::de::Error) -> anyhow::Error {
+        let offset = err.span().map(|e| e.start).unwrap_or_default();
+        let (line, column) = Self::offset_to_location(content, offset);
+        let msg = if err.message().is_empty() {
+            "invalid expression"
+        } else {
+            err.message()
+        };
+        Self::location_error("toml", line, column, msg)
+    }
+
+    fn offset_to_location(content: &str, offset: usize) -> (usize, usize) {
+        let before = &content[..offset.min(content.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        (line, column)
+    }
+
+    ///
+    /// 统一错误格式,去掉解析库错误信息中自带的位置描述
+    fn location_error<T: std::fmt::Display>(
+        type_name: &str,
+        line: usize,
+        column: usize,
+        err: T,
+    ) -> anyhow::Error {
+        let msg = err.to_string();
+        let msg = match msg.rfind(" at ") {
+            Some(i)
+                if msg[i + 4..]
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || " :linecolumn".contains(c)) =>
+            {
+                msg[..i].to_owned()
+            }
+            _ => msg,
+        };
+        anyhow::anyhow!(
+            "config content is not valid {}, line {}, column {}: {}",
+            type_name,
+            line,
+            column,
+            msg
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_config_content() {
+        assert!(ConfigContentValidator::validate(&ConfigType::Json, "{\"a\":1}").is_ok());
+        let err = ConfigContentValidator::validate(&ConfigType::Json, "{\n\"a\":1,\n}")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("config content is not valid json, line 3, column 1:"));
+
+        assert!(ConfigContentValidator::validate(&ConfigType::Yaml, "a: 1\n---\nb: 2").is_ok());
+        let err = ConfigContentValidator::validate(&ConfigType::Yaml, "a: 1\n b: 2")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2"));
+
+        assert!(ConfigContentValidator::validate(&ConfigType::Toml, "a = 1\n[b]\nc = 'x'").is_ok());
+        let err = ConfigContentValidator::validate(&ConfigType::Toml, "a = 1\nb = ")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2"));
+
+        assert!(ConfigContentValidator::validate(&ConfigType::Xml, "<a><b/></a>").is_ok());
+        assert!(ConfigContentValidator::validate(&ConfigType::Xml, "<a>\n<b></a>").is_err());
+
+        assert!(ConfigContentValidator::validate(&ConfigType::Properties, "a=\\u4e2d").is_ok());
+        let err = ConfigContentValidator::validate(&ConfigType::Properties, "a=1\nb=\\u4e")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2, column 3"));
+        assert!(ConfigContentValidator::validate(&ConfigType::Text, "{").is_ok());
+    }
+
+    #[test]
+    fn validate_config_schema() {
+        let schema =
+            r#"{"type":"object","required":["port"],"properties":{"port":{"type":"integer"}}}"#;
+        assert!(
+            ConfigContentValidator::validate_schema(schema, &ConfigType::Yaml, "port: 80").is_ok()
+        );
+        let err = ConfigContentValidator::validate_schema(schema, &ConfigType::Yaml, "port: abc")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/port"));
+        assert!(ConfigContentValidator::validate_schema(schema, &ConfigType::Json, "{}").is_err());
+        assert!(ConfigContentValidator::check_schema("{").is_err());
+    }
+}
@@ -4,30 +4,46 @@ use bean_factory::Inject;
 use chrono::Local;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::sync::Arc;
 use std::sync::Weak;
 use std::time::Duration;
 
-use crate::raft::store::ClientRequest;
+use crate::raft::store::{ClientRequest, ClientResponse};
 use crate::raft::NacosRaft;
 use crate::utils::get_md5;
 use serde::{Deserialize, Serialize};
 
 use crate::common::byte_utils::id_to_bin;
-use crate::common::constant::{CONFIG_TREE_NAME, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG};
+use crate::common::constant::{
+    CONFIG_HISTORY_TREE_NAME, CONFIG_SCHEMA_TREE_NAME, CONFIG_TREE_NAME, SEQUENCE_TREE_NAME,
+    SEQ_KEY_CONFIG,
+};
 use crate::common::sequence_utils::SimpleSequence;
 use actix::prelude::*;
 
-use super::config_subscribe::Subscriber;
+use super::config_crypto::ConfigCryptoManager;
+use super::config_history::{ConfigHistoryIndex, ConfigHistoryRetention, ConfigHistoryUtils};
+use super::config_subscribe::{
+    ConfigListenType, ConfigListenerInfo, ConfigListenerQueryParam, Subscriber,
+};
+use super::config_validate::ConfigContentValidator;
 use super::dal::ConfigHistoryParam;
 use crate::config::config_index::{ConfigQueryParam, TenantIndex};
 use crate::config::config_type::ConfigType;
 use crate::config::model::{
-    ConfigRaftCmd, ConfigRaftResult, ConfigValueDO, HistoryItem, SetConfigParam,
+    ConfigCasConflictError, ConfigHistoryItemDO, ConfigRaftCmd, ConfigRaftResult, ConfigValueDO,
+    HistoryItem, SetConfigParam,
 };
 use crate::config::utils::param_utils;
+use crate::config::utils::search_util::{ContentMatchLine, ContentMatcher};
+use crate::config::ConfigUtils;
 use crate::now_millis_i64;
+use crate::raft::db::route::TableRoute;
+use crate::raft::db::table::{
+    TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult,
+};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
 
@@ -108,12 +124,55 @@ pub struct ConfigValue {
     pub(crate) content: Arc<String>,
     pub(crate) md5: Arc<String>,
     pub(crate) tmp: bool,
-    pub(crate) histories: Vec<HistoryItem>,
     pub(crate) config_type: Option<Arc<String>>,
     pub(crate) desc: Option<Arc<String>>,
+    pub(crate) app_name: Option<Arc<String>>,
+    pub(crate) config_tags: Option<Arc<String>>,
+    pub(crate) last_modified: i64,
+    pub(crate) beta: Option<ConfigBetaValue>,
+}
+
+///
+/// 配置灰度内容,只对beta_ips中的客户端生效
+#[derive(Clone)]
+pub struct ConfigBetaValue {
+    pub(crate) content: Arc<String>,
+    pub(crate) md5: Arc<String>,
+    pub(crate) beta_ips: Arc<String>,
+    pub(crate) ip_set: HashSet<String>,
+    pub(crate) op_user: Option<Arc<String>>,
     pub(crate) last_modified: i64,
 }
 
+impl ConfigBetaValue {
+    pub fn new(
+        content: Arc<String>,
+        beta_ips: Arc<String>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    ) -> Self {
+        let md5 = Arc::new(get_md5(&content));
+        let ip_set = beta_ips
+            .split(',')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+            .map(|e| e.to_owned())
+            .collect();
+        Self {
+            content,
+            md5,
+            beta_ips,
+            ip_set,
+            op_user,
+            last_modified: op_time,
+        }
+    }
+
+    pub fn match_client(&self, client_ip: &str) -> bool {
+        self.ip_set.contains(client_ip)
+    }
+}
+
 impl ConfigValue {
     pub fn new(content: Arc<String>) -> Self {
         let md5 = get_md5(&content);
@@ -121,68 +180,58 @@ impl ConfigValue {
             content,
             md5: Arc::new(md5),
             tmp: false,
-            histories: vec![],
             config_type: None,
             desc: None,
+            app_name: None,
+            config_tags: None,
             last_modified: now_millis_i64(),
+            beta: None,
         }
     }
 
-    pub fn init(
-        content: Arc<String>,
-        history_id: u64,
-        op_time: i64,
-        md5: Option<Arc<String>>,
-        op_user: Option<Arc<String>>,
-    ) -> Self {
+    pub fn init(content: Arc<String>, op_time: i64, md5: Option<Arc<String>>) -> Self {
         let md5 = if let Some(v) = md5 {
             v
         } else {
             Arc::new(get_md5(&content))
         };
         Self {
-            content: content.clone(),
+            content,
             md5,
             tmp: false,
-            histories: vec![HistoryItem {
-                id: history_id,
-                content,
-                modified_time: op_time,
-                op_user,
-            }],
             config_type: None,
             desc: None,
+            app_name: None,
+            config_tags: None,
             last_modified: op_time,
+            beta: None,
         }
     }
 
-    pub fn update_value(
-        &mut self,
-        content: Arc<String>,
-        history_id: u64,
-        op_time: i64,
-        md5: Option<Arc<String>>,
-        op_user: Option<Arc<String>>,
-    ) {
+    pub fn update_value(&mut self, content: Arc<String>, op_time: i64, md5: Option<Arc<String>>) {
         let md5 = if let Some(v) = md5 {
             v
         } else {
             Arc::new(get_md5(&content))
         };
         self.md5 = md5;
-        self.content = content.clone();
+        self.content = content;
         self.tmp = false;
-        let item = HistoryItem {
-            id: history_id,
-            content,
-            modified_time: op_time,
-            op_user,
-        };
-        if self.histories.len() >= 100 {
-            self.histories.remove(0);
-        }
         self.last_modified = op_time;
-        self.histories.push(item);
+    }
+
+    ///
+    /// 客户端命中灰度时返回灰度内容
+    pub fn get_client_beta(&self, client_ip: &str) -> Option<&ConfigBetaValue> {
+        self.beta.as_ref().filter(|e| e.match_client(client_ip))
+    }
+
+    pub fn get_client_md5(&self, client_ip: &str) -> &Arc<String> {
+        if let Some(beta) = self.get_client_beta(client_ip) {
+            &beta.md5
+        } else {
+            &self.md5
+        }
     }
 }
 
@@ -194,6 +243,10 @@ pub struct ConfigInfoDto {
     pub data_id: Arc<String>,
     pub content: Option<Arc<String>>,
     pub md5: Option<Arc<String>>,
+    pub app_name: Option<Arc<String>>,
+    pub config_tags: Option<Arc<String>>,
+    /// 内容搜索时匹配的行
+    pub match_lines: Option<Vec<ContentMatchLine>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -300,11 +353,19 @@ pub enum ListenerResult {
 type ListenerSenderType = tokio::sync::oneshot::Sender<ListenerResult>;
 //type ListenerReceiverType = tokio::sync::oneshot::Receiver<ListenerResult>;
 
+///
+/// http长轮询的客户端及其上报的监听项
+struct ListenerClient {
+    client_ip: Arc<String>,
+    items: Vec<ListenerItem>,
+}
+
 pub(crate) struct ConfigListener {
     version: u64,
     listener: HashMap<ConfigKey, Vec<u64>>,
     time_listener: BTreeMap<i64, Vec<OnceListener>>,
     sender_map: HashMap<u64, ListenerSenderType>,
+    client_map: HashMap<u64, ListenerClient>,
 }
 
 impl ConfigListener {
@@ -314,10 +375,17 @@ impl ConfigListener {
             listener: Default::default(),
             time_listener: Default::default(),
             sender_map: Default::default(),
+            client_map: Default::default(),
         }
     }
 
-    fn add(&mut self, items: Vec<ListenerItem>, sender: ListenerSenderType, time: i64) {
+    fn add(
+        &mut self,
+        items: Vec<ListenerItem>,
+        sender: ListenerSenderType,
+        time: i64,
+        client_ip: Arc<String>,
+    ) {
         self.version += 1;
         for item in &items {
             let key = item.key.clone();
@@ -331,6 +399,8 @@ impl ConfigListener {
             };
         }
         self.sender_map.insert(self.version, sender);
+        self.client_map
+            .insert(self.version, ListenerClient { client_ip, items });
         let once_listener = OnceListener {
             version: self.version,
             //time,
@@ -349,6 +419,7 @@ impl ConfigListener {
     fn notify(&mut self, key: ConfigKey) {
         if let Some(list) = self.listener.remove(&key) {
             for v in list {
+                self.client_map.remove(&v);
                 if let Some(sender) = self.sender_map.remove(&v) {
                     sender.send(ListenerResult::DATA(vec![key.clone()])).ok();
                 }
@@ -364,6 +435,7 @@ impl ConfigListener {
                 keys.push(*key);
                 for item in list {
                     let v = item.version;
+                    self.client_map.remove(&v);
                     if let Some(sender) = self.sender_map.remove(&v) {
                         sender.send(ListenerResult::NULL).ok();
                     }
@@ -381,6 +453,36 @@ impl ConfigListener {
         self.sender_map.len()
     }
 
+    ///
+    /// 查询正在等待的http长轮询监听信息
+    fn query_listener(&self, param: &ConfigListenerQueryParam) -> Vec<ConfigListenerInfo> {
+        let mut list = vec![];
+        if param.client_id.is_some() {
+            return list;
+        }
+        let query_key = param.get_key();
+        for client in self.client_map.values() {
+            if !param.match_client(None, &client.client_ip) {
+                continue;
+            }
+            for item in &client.items {
+                if let Some(key) = &query_key {
+                    if key != &item.key {
+                        continue;
+                    }
+                }
+                list.push(ConfigListenerInfo::new(
+                    &item.key,
+                    None,
+                    client.client_ip.clone(),
+                    item.md5.clone(),
+                    ConfigListenType::Http,
+                ));
+            }
+        }
+        list
+    }
+
     pub(crate) fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
@@ -394,6 +496,13 @@ pub struct ConfigActor {
     pub(crate) tenant_index: TenantIndex,
     raft: Option<Weak<NacosRaft>>,
     sequence: SimpleSequence,
+    crypto: Arc<ConfigCryptoManager>,
+    history_retention: Arc<ConfigHistoryRetention>,
+    history_index: ConfigHistoryIndex,
+    table_manager: Option<Addr<TableManager>>,
+    table_route: Option<Arc<TableRoute>>,
+    /// 临时值覆盖前已应用的md5,CAS校验只依赖raft状态,保证各节点结果一致
+    tmp_applied_md5: HashMap<ConfigKey, Arc<String>>,
 }
 
 impl Inject for ConfigActor {
@@ -407,6 +516,14 @@ impl Inject for ConfigActor {
     ) {
         let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
         self.raft = raft.map(|e| Arc::downgrade(&e));
+        if let Some(crypto) = factory_data.get_bean() {
+            self.crypto = crypto;
+        }
+        if let Some(history_retention) = factory_data.get_bean() {
+            self.history_retention = history_retention;
+        }
+        self.table_manager = factory_data.get_actor();
+        self.table_route = factory_data.get_bean();
         if let Some(conn_manage) = factory_data.get_actor() {
             self.subscriber.set_conn_manage(conn_manage);
         }
@@ -429,73 +546,379 @@ impl ConfigActor {
             tenant_index: TenantIndex::new(),
             raft: None,
             sequence: SimpleSequence::new(0, 100),
+            crypto: Default::default(),
+            history_retention: Default::default(),
+            history_index: Default::default(),
+            tmp_applied_md5: Default::default(),
+            table_manager: None,
+            table_route: None,
         }
     }
 
     fn set_tmp_config(&mut self, key: ConfigKey, val: Arc<String>) {
+        let md5 = Arc::new(get_md5(&val));
+        let val = match self.crypto.encrypt(&key, val) {
+            Ok(v) => v,
+            Err(err) => {
+                log::warn!("encrypt tmp config error,{}", err);
+                return;
+            }
+        };
         if let Some(v) = self.cache.get_mut(&key) {
+            if !v.tmp {
+                self.tmp_applied_md5.insert(key.clone(), v.md5.clone());
+            }
             v.tmp = true;
-            v.md5 = Arc::new(get_md5(&val));
+            v.md5 = md5;
             v.content = val;
         } else {
             let mut config_val = ConfigValue::new(val);
+            config_val.md5 = md5;
             config_val.tmp = true;
             self.cache.insert(key, config_val);
         }
     }
 
+    ///
+    /// 加密需要加密存储的配置内容,返回(存储内容,明文md5)
+    fn encrypt_value(
+        &self,
+        key: &ConfigKey,
+        value: Arc<String>,
+        is_beta: bool,
+    ) -> anyhow::Result<(Arc<String>, Option<Arc<String>>)> {
+        if !self.crypto.need_encrypt(key) {
+            return Ok((value, None));
+        }
+        let md5 = Arc::new(get_md5(&value));
+        //内容未变化且已使用当前密钥加密时复用原密文,避免产生无效的变更
+        let current = self.cache.get(key).and_then(|v| {
+            if is_beta {
+                v.beta.as_ref().map(|e| (&e.md5, &e.content))
+            } else {
+                Some((&v.md5, &v.content))
+            }
+        });
+        if let Some((current_md5, current_content)) = current {
+            if *current_md5 == md5 && self.crypto.is_active_encrypted(key, current_content) {
+                return Ok((current_content.clone(), Some(md5)));
+            }
+        }
+        Ok((self.crypto.encrypt(key, value)?, Some(md5)))
+    }
+
+    ///
+    /// 未授权解密时返回存储的密文
+    fn read_value(
+        &self,
+        key: &ConfigKey,
+        value: &Arc<String>,
+        decrypt: bool,
+    ) -> anyhow::Result<Arc<String>> {
+        if decrypt {
+            self.crypto.decrypt(key, value)
+        } else {
+            Ok(value.clone())
+        }
+    }
+
+    ///
+    /// 列表查询解密失败时保留密文
+    fn decrypt_value_or_raw(&self, key: &ConfigKey, value: &Arc<String>) -> Arc<String> {
+        self.crypto.decrypt_or_raw(key, value)
+    }
+
+    ///
+    /// 查询命名空间下需要使用当前密钥重新加密的配置
+    fn query_cipher_rotate_keys(&self, tenant: &Arc<String>) -> Vec<ConfigKey> {
+        self.cache
+            .iter()
+            .filter(|(k, v)| {
+                &k.tenant == tenant
+                    && self.crypto.need_encrypt(k)
+                    && !self.crypto.is_active_encrypted(k, &v.content)
+            })
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    ///
+    /// 命名空间下所有需要加密存储的配置,用于检查其历史记录是否需要重新加密
+    fn query_cipher_keys(&self, tenant: &Arc<String>) -> Vec<ConfigKey> {
+        self.cache
+            .keys()
+            .filter(|k| &k.tenant == tenant && self.crypto.need_encrypt(k))
+            .cloned()
+            .collect()
+    }
+
     fn inner_set_config(&mut self, key: ConfigKey, value: ConfigValue) {
+        self.tmp_applied_md5.remove(&key);
         self.tenant_index.insert_config(key.clone());
         self.cache.insert(key, value);
     }
 
     fn set_config(&mut self, param: SetConfigParam) -> anyhow::Result<ConfigResult> {
+        self.tmp_applied_md5.remove(&param.key);
         if let Some(history_table_id) = param.history_table_id {
             self.sequence.set_valid_last_id(history_table_id);
         }
+        let md5 = match param.md5 {
+            Some(v) => v,
+            None => Arc::new(get_md5(param.value.as_str())),
+        };
         if let Some(v) = self.cache.get_mut(&param.key) {
-            let md5 = get_md5(param.value.as_str());
             if let Some(s) = param.config_type {
                 v.config_type = Some(s);
             }
             if let Some(s) = param.desc {
                 v.desc = Some(s);
             }
-            if !v.tmp && v.md5.as_str() == md5 {
+            if let Some(s) = param.app_name {
+                v.app_name = Self::not_empty_arc(s);
+            }
+            if let Some(s) = param.config_tags {
+                v.config_tags = Self::not_empty_arc(Arc::new(ConfigUtils::normalize_tags(&s)));
+            }
+            if !v.tmp && v.md5 == md5 {
+                //明文未变化只更换了加密密钥(密钥轮换),不产生历史记录与变更通知
+                //历史记录由轮换接口单独通过raft重新加密
+                if v.content != param.value {
+                    v.content = param.value;
+                }
                 return Ok(ConfigResult::NULL);
             }
-            if v.histories.is_empty() {
+            if v.tmp {
                 self.tenant_index.insert_config(param.key.clone());
             }
-            v.update_value(
-                param.value,
-                param.history_id,
-                param.op_time,
-                Some(Arc::new(md5)),
-                param.op_user,
-            );
+            v.update_value(param.value.clone(), param.op_time, Some(md5));
         } else {
-            let mut v = ConfigValue::init(
-                param.value,
-                param.history_id,
-                param.op_time,
-                None,
-                param.op_user,
-            );
+            let mut v = ConfigValue::init(param.value.clone(), param.op_time, Some(md5));
             v.config_type = param.config_type;
             v.desc = param.desc;
+            v.app_name = param.app_name.and_then(Self::not_empty_arc);
+            v.config_tags = param
+                .config_tags
+                .and_then(|s| Self::not_empty_arc(Arc::new(ConfigUtils::normalize_tags(&s))));
             self.cache.insert(param.key.clone(), v);
             self.tenant_index.insert_config(param.key.clone());
         }
+        let history = HistoryItem {
+            id: param.history_id,
+            content: param.value,
+            modified_time: param.op_time,
+            op_user: param.op_user,
+        };
+        self.add_history(&param.key, history);
         self.listener.notify(param.key.clone());
         self.subscriber.notify(param.key);
         Ok(ConfigResult::NULL)
     }
 
+    ///
+    /// 历史记录写入raft table,并按保留策略清理过期记录
+    fn add_history(&mut self, key: &ConfigKey, item: HistoryItem) {
+        self.history_index.insert(key, item.id, item.modified_time);
+        let rule = self.history_retention.get_rule(key.tenant.as_str());
+        let expired_ids = self.history_index.remove_expired(key, rule);
+        let table_manager = if let Some(v) = &self.table_manager {
+            v
+        } else {
+            return;
+        };
+        let history_key = ConfigHistoryUtils::build_key(key, item.id);
+        match ConfigHistoryItemDO::from(item).to_bytes() {
+            Ok(value) => {
+                table_manager.do_send(TableManagerReq::Set {
+                    table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+                    key: history_key,
+                    value,
+                    last_seq_id: None,
+                });
+            }
+            Err(err) => log::error!("encode config history error,{}", err),
+        }
+        Self::remove_histories(table_manager, key, expired_ids);
+    }
+
+    ///
+    /// 密钥轮换时在处理请求的节点上用当前密钥重新加密历史记录,再通过raft写入,保证各节点数据一致
+    /// 返回有历史记录被重新加密的配置
+    async fn rotate_histories(
+        table_manager: Option<Addr<TableManager>>,
+        table_route: Option<Arc<TableRoute>>,
+        crypto: Arc<ConfigCryptoManager>,
+        keys: Vec<ConfigKey>,
+    ) -> anyhow::Result<Vec<ConfigKey>> {
+        let (table_manager, table_route) = match (table_manager, table_route) {
+            (Some(table_manager), Some(table_route)) => (table_manager, table_route),
+            _ => return Ok(vec![]),
+        };
+        let mut rotated_keys = vec![];
+        for key in keys {
+            let req = TableManagerQueryReq::QueryPrefixPageList {
+                table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+                prefix: ConfigHistoryUtils::build_prefix(&key),
+                offset: None,
+                limit: None,
+                is_rev: false,
+            };
+            let list = match table_manager.send(req).await?? {
+                TableManagerResult::PageListResult(_, list) => list,
+                _ => continue,
+            };
+            let mut rotated = false;
+            for (history_key, v) in list {
+                let mut item: HistoryItem = ConfigHistoryItemDO::from_bytes(&v)?.into();
+                if !ConfigCryptoManager::is_encrypted(&item.content)
+                    || crypto.is_active_encrypted(&key, &item.content)
+                {
+                    continue;
+                }
+                let content = crypto.decrypt(&key, &item.content)?;
+                item.content = crypto.encrypt(&key, content)?;
+                table_route
+                    .request(TableManagerReq::Set {
+                        table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+                        key: history_key,
+                        value: ConfigHistoryItemDO::from(item).to_bytes()?,
+                        last_seq_id: None,
+                    })
+                    .await?;
+                rotated = true;
+            }
+            if rotated {
+                rotated_keys.push(key);
+            }
+        }
+        Ok(rotated_keys)
+    }
+
+    fn remove_histories(table_manager: &Addr<TableManager>, key: &ConfigKey, ids: Vec<u64>) {
+        for id in ids {
+            table_manager.do_send(TableManagerReq::Remove {
+                table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+                key: ConfigHistoryUtils::build_key(key, id),
+            });
+        }
+    }
+
+    ///
+    /// 发布配置使用的类型,未指定时沿用已有配置的类型
+    fn get_publish_config_type(
+        &self,
+        key: &ConfigKey,
+        config_type: &Option<Arc<String>>,
+    ) -> ConfigType {
+        let config_type = config_type
+            .as_ref()
+            .or_else(|| self.cache.get(key).and_then(|v| v.config_type.as_ref()));
+        config_type
+            .map(|v| ConfigType::new_by_value(v))
+            .unwrap_or_default()
+    }
+
+    ///
+    /// 配置设置了json schema时按schema校验内容
+    async fn validate_config_schema(
+        table_manager: &Option<Addr<TableManager>>,
+        key: &ConfigKey,
+        config_type: &ConfigType,
+        value: &str,
+    ) -> anyhow::Result<()> {
+        let table_manager = if let Some(v) = table_manager {
+            v
+        } else {
+            return Ok(());
+        };
+        let req = TableManagerQueryReq::GetByBytes {
+            table_name: CONFIG_SCHEMA_TREE_NAME.clone(),
+            key: key.build_key().into_bytes(),
+        };
+        if let TableManagerResult::Value(schema) = table_manager.send(req).await?? {
+            ConfigContentValidator::validate_schema(
+                &String::from_utf8_lossy(&schema),
+                config_type,
+                value,
+            )?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// 校验CAS期望的md5,配置不存在时当前md5视为空字符串
+    /// 临时值只在本节点存在,需使用被覆盖前已应用的md5
+    fn match_cas_md5(&self, key: &ConfigKey, cas_md5: &Option<Arc<String>>) -> bool {
+        match cas_md5 {
+            Some(cas_md5) => {
+                let current_md5 = match self.tmp_applied_md5.get(key) {
+                    Some(v) => v.as_str(),
+                    None => self
+                        .cache
+                        .get(key)
+                        .filter(|v| !v.tmp)
+                        .map(|v| v.md5.as_str())
+                        .unwrap_or_default(),
+                };
+                current_md5 == cas_md5.as_str()
+            }
+            None => true,
+        }
+    }
+
+    fn not_empty_arc(v: Arc<String>) -> Option<Arc<String>> {
+        if v.is_empty() {
+            None
+        } else {
+            Some(v)
+        }
+    }
+
+    fn set_beta_config(
+        &mut self,
+        key: ConfigKey,
+        value: Arc<String>,
+        beta_ips: Arc<String>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+        md5: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        if let Some(v) = self.cache.get_mut(&key) {
+            let mut beta = ConfigBetaValue::new(value, beta_ips, op_time, op_user);
+            if let Some(md5) = md5 {
+                beta.md5 = md5;
+            }
+            v.beta = Some(beta);
+        } else {
+            return Err(anyhow::anyhow!(
+                "the beta config base value is not exist: {}",
+                key.build_key()
+            ));
+        }
+        self.listener.notify(key.clone());
+        self.subscriber.notify(key);
+        Ok(())
+    }
+
+    fn del_beta_config(&mut self, key: ConfigKey) -> anyhow::Result<()> {
+        if let Some(v) = self.cache.get_mut(&key) {
+            if v.beta.take().is_some() {
+                self.listener.notify(key.clone());
+                self.subscriber.notify(key);
+            }
+        }
+        Ok(())
+    }
+
     fn del_config(&mut self, key: ConfigKey) -> anyhow::Result<()> {
         self.cache.remove(&key);
+        self.tmp_applied_md5.remove(&key);
         //self.config_db.del_config(&key).ok();
         self.tenant_index.remove_config(&key);
+        let history_ids = self.history_index.remove(&key);
+        if let Some(table_manager) = &self.table_manager {
+            Self::remove_histories(table_manager, &key, history_ids);
+        }
         self.listener.notify(key.clone());
         self.subscriber.notify(key.clone());
         self.subscriber.remove_config_key(key);
@@ -521,18 +944,28 @@ impl ConfigActor {
     async fn send_raft_request(
         raft: &Option<Weak<NacosRaft>>,
         req: ClientRequest,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<ClientResponse> {
         if let Some(weak_raft) = raft {
             if let Some(raft) = weak_raft.upgrade() {
                 //TODO换成feature,非wait的方式
-                raft.client_write(ClientWriteRequest::new(req)).await?;
+                let resp = raft.client_write(ClientWriteRequest::new(req)).await?;
+                return Ok(resp.data);
             }
         }
-        Ok(())
+        Ok(ClientResponse::default())
     }
 
     pub fn get_config_info_page(&self, param: &ConfigQueryParam) -> (usize, Vec<ConfigInfoDto>) {
-        let (size, list) = self.tenant_index.query_config_page(param);
+        let (size, list) = if param.has_value_filter() {
+            self.tenant_index.query_config_page_by_filter(param, |key| {
+                self.cache
+                    .get(key)
+                    .map(|v| Self::match_config_value(param, v))
+                    .unwrap_or(false)
+            })
+        } else {
+            self.tenant_index.query_config_page(param)
+        };
 
         if size == 0 {
             return (size, Vec::new());
@@ -541,24 +974,99 @@ impl ConfigActor {
         let mut info_list = Vec::with_capacity(size);
         for item in &list {
             if let Some(value) = self.cache.get(item) {
-                let mut info = ConfigInfoDto {
-                    tenant: item.tenant.clone(),
-                    group: item.group.clone(),
-                    data_id: item.data_id.clone(),
-                    //md5:Some(value.md5.clone()),
-                    //content:Some(value.content.clone()),
-                    ..Default::default()
-                };
-                if param.query_context {
-                    info.content = Some(value.content.clone());
-                    info.md5 = Some(value.md5.clone());
-                }
-                info_list.push(info);
+                info_list.push(self.build_config_info(item, value, param));
             }
         }
         (size, info_list)
     }
 
+    ///
+    /// 按配置内容搜索(子串或正则),返回匹配的行
+    /// 未授权解密时不搜索加密存储的配置
+    pub fn search_config_content(
+        &self,
+        param: &ConfigQueryParam,
+    ) -> anyhow::Result<(usize, Vec<ConfigInfoDto>)> {
+        let matcher = ContentMatcher::new(
+            param.content_keyword.as_deref().unwrap_or_default(),
+            param.content_regex,
+        )?;
+        let (size, list) = self.tenant_index.query_config_page_by_filter(param, |key| {
+            self.cache
+                .get(key)
+                .map(|v| {
+                    Self::match_config_value(param, v)
+                        && self
+                            .search_content(key, &v.content, param.decrypt)
+                            .map(|content| matcher.is_match(&content))
+                            .unwrap_or(false)
+                })
+                .unwrap_or(false)
+        });
+        let mut info_list = Vec::with_capacity(list.len());
+        for item in &list {
+            if let Some(value) = self.cache.get(item) {
+                let content = self
+                    .search_content(item, &value.content, param.decrypt)
+                    .unwrap_or_default();
+                let mut info = self.build_config_info(item, value, param);
+                info.match_lines = Some(matcher.match_lines(&content));
+                info_list.push(info);
+            }
+        }
+        Ok((size, info_list))
+    }
+
+    fn search_content(
+        &self,
+        key: &ConfigKey,
+        content: &Arc<String>,
+        decrypt: bool,
+    ) -> Option<Arc<String>> {
+        if ConfigCryptoManager::is_cipher_key(key) && ConfigCryptoManager::is_encrypted(content) {
+            if decrypt {
+                self.crypto.decrypt(key, content).ok()
+            } else {
+                None
+            }
+        } else {
+            Some(content.clone())
+        }
+    }
+
+    fn match_config_value(param: &ConfigQueryParam, value: &ConfigValue) -> bool {
+        param.match_app_name(&value.app_name)
+            && param.match_tags(&value.config_tags)
+            && param.match_config_type(&value.config_type)
+    }
+
+    fn build_config_info(
+        &self,
+        key: &ConfigKey,
+        value: &ConfigValue,
+        param: &ConfigQueryParam,
+    ) -> ConfigInfoDto {
+        let mut info = ConfigInfoDto {
+            tenant: key.tenant.clone(),
+            group: key.group.clone(),
+            data_id: key.data_id.clone(),
+            app_name: value.app_name.clone(),
+            config_tags: value.config_tags.clone(),
+            //md5:Some(value.md5.clone()),
+            //content:Some(value.content.clone()),
+            ..Default::default()
+        };
+        if param.query_context {
+            info.content = Some(if param.decrypt {
+                self.decrypt_value_or_raw(key, &value.content)
+            } else {
+                value.content.clone()
+            });
+            info.md5 = Some(value.md5.clone());
+        }
+        info
+    }
+
     /*
     pub(crate) fn get_history_info_page_old(
         &self,
@@ -581,33 +1089,67 @@ impl ConfigActor {
      */
 
     ///
-    /// 从内存列表中直接查询历史记录
-    pub(crate) fn get_history_info_page(
-        &self,
+    /// 从raft table中按配置key前缀分页查询历史记录
+    async fn query_history_page(
+        table_manager: Option<Addr<TableManager>>,
+        crypto: Arc<ConfigCryptoManager>,
         param: &ConfigHistoryParam,
-    ) -> (usize, Vec<ConfigHistoryInfoDto>) {
-        if let (Some(t), Some(g), Some(id)) = (&param.tenant, &param.group, &param.data_id) {
-            let key = ConfigKey::new(id, g, t);
-            if let Some(v) = self.cache.get(&key) {
-                let mut ret = vec![];
-                let iter = v.histories.iter().rev();
-                if let Some(offset) = param.offset {
-                    let n_i = iter.skip(offset as usize);
-                    if let Some(limit) = param.limit {
-                        let t = n_i.take(limit as usize);
-                        for item in t {
-                            ret.push(item.to_dto(&key));
-                        }
-                    } else {
-                        for item in n_i {
-                            ret.push(item.to_dto(&key));
-                        }
-                    }
+    ) -> anyhow::Result<(usize, Vec<ConfigHistoryInfoDto>)> {
+        let (table_manager, key) =
+            match (table_manager, &param.tenant, &param.group, &param.data_id) {
+                (Some(table_manager), Some(t), Some(g), Some(id)) => {
+                    (table_manager, ConfigKey::new(id, g, t))
                 }
-                return (v.histories.len(), ret);
+                _ => return Ok((0, vec![])),
             };
+        let req = TableManagerQueryReq::QueryPrefixPageList {
+            table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+            prefix: ConfigHistoryUtils::build_prefix(&key),
+            offset: param.offset,
+            limit: param.limit,
+            is_rev: true,
         };
-        (0, vec![])
+        match table_manager.send(req).await?? {
+            TableManagerResult::PageListResult(size, list) => {
+                let mut ret = Vec::with_capacity(list.len());
+                for (_, v) in list {
+                    let item: HistoryItem = ConfigHistoryItemDO::from_bytes(&v)?.into();
+                    let mut dto = item.to_dto(&key);
+                    if param.decrypt {
+                        dto.content = Some(crypto.decrypt_or_raw(&key, &item.content).to_string());
+                    }
+                    ret.push(dto);
+                }
+                Ok((size, ret))
+            }
+            _ => Ok((0, vec![])),
+        }
+    }
+
+    async fn get_history(
+        table_manager: Option<Addr<TableManager>>,
+        crypto: Arc<ConfigCryptoManager>,
+        key: ConfigKey,
+        history_id: u64,
+        decrypt: bool,
+    ) -> anyhow::Result<ConfigResult> {
+        let table_manager = if let Some(v) = table_manager {
+            v
+        } else {
+            return Ok(ConfigResult::NULL);
+        };
+        let req = TableManagerQueryReq::GetByBytes {
+            table_name: CONFIG_HISTORY_TREE_NAME.clone(),
+            key: ConfigHistoryUtils::build_key(&key, history_id),
+        };
+        if let TableManagerResult::Value(v) = table_manager.send(req).await?? {
+            let mut item: HistoryItem = ConfigHistoryItemDO::from_bytes(&v)?.into();
+            if decrypt {
+                item.content = crypto.decrypt(&key, &item.content)?;
+            }
+            return Ok(ConfigResult::HistoryItem(item));
+        }
+        Ok(ConfigResult::NULL)
     }
 
     ///
@@ -650,16 +1192,31 @@ pub enum ConfigCmd {
     SetTmpValue(ConfigKey, Arc<String>),
     InnerSet(ConfigKey, ConfigValue),
     InnerSetLastId(u64),
-    GET(ConfigKey),
+    InnerSetHistory(ConfigKey, HistoryItem),
+    /// 最后一个参数为是否解密cipher-配置,未授权的调用方传false获取密文
+    GET(ConfigKey, bool),
+    GetByClient(ConfigKey, Arc<String>, bool),
+    GetBeta(ConfigKey, bool),
     QueryPageInfo(Box<ConfigQueryParam>),
-    QueryHistoryPageInfo(Box<ConfigHistoryParam>),
-    LISTENER(Vec<ListenerItem>, ListenerSenderType, i64),
-    Subscribe(Vec<ListenerItem>, Arc<String>),
+    SearchContent(Box<ConfigQueryParam>),
+    QueryCipherRotateKeys(Arc<String>),
+    LISTENER(Vec<ListenerItem>, ListenerSenderType, i64, Arc<String>),
+    Subscribe(Vec<ListenerItem>, Arc<String>, Arc<String>),
     RemoveSubscribe(Vec<ListenerItem>, Arc<String>),
     RemoveSubscribeClient(Arc<String>),
+    QueryListener(Box<ConfigListenerQueryParam>),
     BuildSnapshot(Addr<SnapshotWriterActor>),
 }
 
+///
+/// 配置历史记录查询,历史内容存放在raft table中需要异步查询
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<ConfigResult>")]
+pub enum ConfigHistoryCmd {
+    GetHistory(ConfigKey, u64, bool),
+    QueryHistoryPageInfo(Box<ConfigHistoryParam>),
+}
+
 #[derive(Message)]
 #[rtype(result = "anyhow::Result<ConfigResult>")]
 pub enum ConfigAsyncCmd {
@@ -669,8 +1226,21 @@ pub enum ConfigAsyncCmd {
         op_user: Option<Arc<String>>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        app_name: Option<Arc<String>>,
+        config_tags: Option<Arc<String>>,
+        cas_md5: Option<Arc<String>>,
     },
     Delete(ConfigKey),
+    AddBeta {
+        key: ConfigKey,
+        value: Arc<String>,
+        beta_ips: Arc<String>,
+        op_user: Option<Arc<String>>,
+    },
+    DeleteBeta(ConfigKey),
+    ///
+    /// 重新加密命名空间下非当前密钥加密的历史记录,包括已用当前密钥重新发布的配置
+    RotateCipherHistories(Arc<String>),
 }
 
 pub enum ConfigResult {
@@ -679,12 +1249,18 @@ pub enum ConfigResult {
         md5: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        app_name: Option<Arc<String>>,
+        config_tags: Option<Arc<String>>,
         last_modified: i64,
+        beta: bool,
     },
+    BetaData(ConfigBetaValue),
+    HistoryItem(HistoryItem),
     NULL,
     ChangeKey(Vec<ConfigKey>),
     ConfigInfoPage(usize, Vec<ConfigInfoDto>),
     ConfigHistoryInfoPage(usize, Vec<ConfigHistoryInfoDto>),
+    ListenerInfo(Vec<ConfigListenerInfo>),
 }
 
 impl Actor for ConfigActor {
@@ -716,22 +1292,61 @@ impl Handler<ConfigCmd> for ConfigActor {
             ConfigCmd::InnerSetLastId(last_id) => {
                 self.sequence.set_last_id(last_id);
             }
-            ConfigCmd::GET(key) => {
+            ConfigCmd::InnerSetHistory(key, item) => {
+                self.add_history(&key, item);
+            }
+            ConfigCmd::GET(key, decrypt) => {
                 if let Some(v) = self.cache.get(&key) {
                     return Ok(ConfigResult::Data {
-                        value: v.content.clone(),
+                        value: self.read_value(&key, &v.content, decrypt)?,
+                        md5: v.md5.clone(),
+                        config_type: v.config_type.clone(),
+                        desc: v.desc.clone(),
+                        app_name: v.app_name.clone(),
+                        config_tags: v.config_tags.clone(),
+                        last_modified: v.last_modified,
+                        beta: false,
+                    });
+                }
+            }
+            ConfigCmd::GetByClient(key, client_ip, decrypt) => {
+                if let Some(v) = self.cache.get(&key) {
+                    if let Some(beta) = v.get_client_beta(&client_ip) {
+                        return Ok(ConfigResult::Data {
+                            value: self.read_value(&key, &beta.content, decrypt)?,
+                            md5: beta.md5.clone(),
+                            config_type: v.config_type.clone(),
+                            desc: v.desc.clone(),
+                            app_name: v.app_name.clone(),
+                            config_tags: v.config_tags.clone(),
+                            last_modified: beta.last_modified,
+                            beta: true,
+                        });
+                    }
+                    return Ok(ConfigResult::Data {
+                        value: self.read_value(&key, &v.content, decrypt)?,
                         md5: v.md5.clone(),
                         config_type: v.config_type.clone(),
                         desc: v.desc.clone(),
+                        app_name: v.app_name.clone(),
+                        config_tags: v.config_tags.clone(),
                         last_modified: v.last_modified,
+                        beta: false,
                     });
                 }
             }
-            ConfigCmd::LISTENER(items, sender, time) => {
+            ConfigCmd::GetBeta(key, decrypt) => {
+                if let Some(Some(beta)) = self.cache.get(&key).map(|v| &v.beta) {
+                    let mut beta = beta.clone();
+                    beta.content = self.read_value(&key, &beta.content, decrypt)?;
+                    return Ok(ConfigResult::BetaData(beta));
+                }
+            }
+            ConfigCmd::LISTENER(items, sender, time, client_ip) => {
                 let mut changes = vec![];
                 for item in &items {
                     if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
+                        if *v.get_client_md5(&client_ip) != item.md5 {
                             changes.push(item.key.clone());
                         }
                     } else if !item.md5.is_empty() {
@@ -742,22 +1357,22 @@ impl Handler<ConfigCmd> for ConfigActor {
                     sender.send(ListenerResult::DATA(changes)).ok();
                     return Ok(ConfigResult::NULL);
                 } else {
-                    self.listener.add(items, sender, time);
+                    self.listener.add(items, sender, time, client_ip);
                     return Ok(ConfigResult::NULL);
                 }
             }
-            ConfigCmd::Subscribe(items, client_id) => {
+            ConfigCmd::Subscribe(items, client_id, client_ip) => {
                 let mut changes = vec![];
                 for item in &items {
                     if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
+                        if *v.get_client_md5(&client_ip) != item.md5 {
                             changes.push(item.key.clone());
                         }
                     } else if !item.md5.is_empty() {
                         changes.push(item.key.clone());
                     }
                 }
-                self.subscriber.add_subscribe(client_id, items);
+                self.subscriber.add_subscribe(client_id, client_ip, items);
                 if !changes.is_empty() {
                     return Ok(ConfigResult::ChangeKey(changes));
                 }
@@ -768,13 +1383,23 @@ impl Handler<ConfigCmd> for ConfigActor {
             ConfigCmd::RemoveSubscribeClient(client_id) => {
                 self.subscriber.remove_client_subscribe(client_id);
             }
+            ConfigCmd::QueryListener(param) => {
+                let mut list = self.subscriber.query_listener(&param);
+                list.extend(self.listener.query_listener(&param));
+                return Ok(ConfigResult::ListenerInfo(list));
+            }
             ConfigCmd::QueryPageInfo(config_query_param) => {
                 let (size, list) = self.get_config_info_page(config_query_param.as_ref());
                 return Ok(ConfigResult::ConfigInfoPage(size, list));
             }
-            ConfigCmd::QueryHistoryPageInfo(query_param) => {
-                let (size, list) = self.get_history_info_page(query_param.as_ref());
-                return Ok(ConfigResult::ConfigHistoryInfoPage(size, list));
+            ConfigCmd::SearchContent(config_query_param) => {
+                let (size, list) = self.search_config_content(config_query_param.as_ref())?;
+                return Ok(ConfigResult::ConfigInfoPage(size, list));
+            }
+            ConfigCmd::QueryCipherRotateKeys(tenant) => {
+                return Ok(ConfigResult::ChangeKey(
+                    self.query_cipher_rotate_keys(&tenant),
+                ));
             }
             ConfigCmd::BuildSnapshot(writer) => {
                 self.build_snapshot(writer).ok();
@@ -784,11 +1409,62 @@ impl Handler<ConfigCmd> for ConfigActor {
     }
 }
 
+impl Handler<ConfigHistoryCmd> for ConfigActor {
+    type Result = ResponseActFuture<Self, anyhow::Result<ConfigResult>>;
+
+    fn handle(&mut self, msg: ConfigHistoryCmd, _ctx: &mut Context<Self>) -> Self::Result {
+        let table_manager = self.table_manager.clone();
+        let crypto = self.crypto.clone();
+        let fut = async move {
+            match msg {
+                ConfigHistoryCmd::GetHistory(key, history_id, decrypt) => {
+                    Self::get_history(table_manager, crypto, key, history_id, decrypt).await
+                }
+                ConfigHistoryCmd::QueryHistoryPageInfo(param) => {
+                    let (size, list) =
+                        Self::query_history_page(table_manager, crypto, &param).await?;
+                    Ok(ConfigResult::ConfigHistoryInfoPage(size, list))
+                }
+            }
+        }
+        .into_actor(self)
+        .map(|r, _act, _ctx| r);
+        Box::pin(fut)
+    }
+}
+
 impl Handler<ConfigAsyncCmd> for ConfigActor {
     type Result = ResponseActFuture<Self, anyhow::Result<ConfigResult>>;
 
     fn handle(&mut self, msg: ConfigAsyncCmd, _ctx: &mut Context<Self>) -> Self::Result {
         let raft = self.raft.clone();
+        let table_manager = self.table_manager.clone();
+        let table_route = self.table_route.clone();
+        let crypto = self.crypto.clone();
+        let cipher_keys = if let ConfigAsyncCmd::RotateCipherHistories(tenant) = &msg {
+            self.query_cipher_keys(tenant)
+        } else {
+            vec![]
+        };
+        let publish_config_type = if let ConfigAsyncCmd::Add {
+            key,
+            value,
+            config_type,
+            cas_md5,
+            ..
+        } = &msg
+        {
+            if !self.match_cas_md5(key, cas_md5) {
+                return Box::pin(actix::fut::ready(Err(ConfigCasConflictError.into())));
+            }
+            let publish_config_type = self.get_publish_config_type(key, config_type);
+            if let Err(err) = ConfigContentValidator::validate(&publish_config_type, value) {
+                return Box::pin(actix::fut::ready(Err(err)));
+            }
+            Some(publish_config_type)
+        } else {
+            None
+        };
         let history_info = if let ConfigAsyncCmd::Add { .. } = &msg {
             match self.sequence.next_state() {
                 Ok(v) => Some(v),
@@ -797,6 +1473,15 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
         } else {
             None
         };
+        let cipher_value = match &msg {
+            ConfigAsyncCmd::Add { key, value, .. } => {
+                Some(self.encrypt_value(key, value.clone(), false))
+            }
+            ConfigAsyncCmd::AddBeta { key, value, .. } => {
+                Some(self.encrypt_value(key, value.clone(), true))
+            }
+            _ => None,
+        };
         let fut = async move {
             match msg {
                 ConfigAsyncCmd::Add {
@@ -805,19 +1490,40 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
                     op_user,
                     config_type,
                     desc,
+                    app_name,
+                    config_tags,
+                    cas_md5,
                 } => {
+                    if let Some(publish_config_type) = &publish_config_type {
+                        Self::validate_config_schema(
+                            &table_manager,
+                            &key,
+                            publish_config_type,
+                            &value,
+                        )
+                        .await?;
+                    }
+                    let (value, md5) = cipher_value.unwrap_or(Ok((value, None)))?;
                     if let Some((history_id, history_table_id)) = history_info {
                         let req = ClientRequest::ConfigSet {
                             key: key.build_key(),
                             value,
                             config_type,
                             desc,
+                            app_name,
+                            config_tags,
                             history_id,
                             history_table_id,
                             op_time: now_millis_i64(),
                             op_user,
+                            md5,
+                            cas_md5,
                         };
-                        Self::send_raft_request(&raft, req).await.ok();
+                        if let Ok(ClientResponse::ConfigCasConflict) =
+                            Self::send_raft_request(&raft, req).await
+                        {
+                            return Err(ConfigCasConflictError.into());
+                        }
                     }
                 }
                 ConfigAsyncCmd::Delete(key) => {
@@ -826,6 +1532,35 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
                     };
                     Self::send_raft_request(&raft, req).await.ok();
                 }
+                ConfigAsyncCmd::AddBeta {
+                    key,
+                    value,
+                    beta_ips,
+                    op_user,
+                } => {
+                    let (value, md5) = cipher_value.unwrap_or(Ok((value, None)))?;
+                    let req = ClientRequest::ConfigBetaSet {
+                        key: key.build_key(),
+                        value,
+                        beta_ips,
+                        op_time: now_millis_i64(),
+                        op_user,
+                        md5,
+                    };
+                    Self::send_raft_request(&raft, req).await.ok();
+                }
+                ConfigAsyncCmd::DeleteBeta(key) => {
+                    let req = ClientRequest::ConfigBetaRemove {
+                        key: key.build_key(),
+                    };
+                    Self::send_raft_request(&raft, req).await.ok();
+                }
+                ConfigAsyncCmd::RotateCipherHistories(_) => {
+                    let keys =
+                        Self::rotate_histories(table_manager, table_route, crypto, cipher_keys)
+                            .await?;
+                    return Ok(ConfigResult::ChangeKey(keys));
+                }
             }
             Ok(ConfigResult::NULL)
         }
@@ -845,22 +1580,35 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 value,
                 config_type,
                 desc,
+                app_name,
+                config_tags,
                 history_id,
                 history_table_id,
                 op_time,
                 op_user,
+                md5,
+                cas_md5,
             } => {
                 let key: ConfigKey = (&key as &str).into();
+                if !self.match_cas_md5(&key, &cas_md5) {
+                    if let Some(history_table_id) = history_table_id {
+                        self.sequence.set_valid_last_id(history_table_id);
+                    }
+                    return Ok(ConfigRaftResult::CasConflict);
+                }
                 let param = SetConfigParam {
                     key,
                     value,
                     config_type: config_type
                         .map(|v| ConfigType::new_by_value(v.as_ref()).get_value()),
                     desc,
+                    app_name,
+                    config_tags,
                     history_id,
                     history_table_id,
                     op_time,
                     op_user,
+                    md5,
                 };
                 self.set_config(param).ok();
             }
@@ -868,6 +1616,30 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 let config_key: ConfigKey = (&key as &str).into();
                 self.del_config(config_key).ok();
             }
+            ConfigRaftCmd::ConfigBetaAdd {
+                key,
+                value,
+                beta_ips,
+                op_time,
+                op_user,
+                md5,
+            } => {
+                let config_key: ConfigKey = (&key as &str).into();
+                if let Err(err) =
+                    self.set_beta_config(config_key, value, beta_ips, op_time, op_user, md5)
+                {
+                    log::warn!("set beta config error,{}", err);
+                }
+            }
+            ConfigRaftCmd::ConfigBetaRemove { key } => {
+                let config_key: ConfigKey = (&key as &str).into();
+                self.del_beta_config(config_key).ok();
+            }
+            ConfigRaftCmd::HistoryTableReq(req) => {
+                if let Some(table_manager) = &self.table_manager {
+                    table_manager.do_send(req);
+                }
+            }
             ConfigRaftCmd::ApplySnaphot => {
                 //self.load_config();
             }
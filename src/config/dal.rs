@@ -225,6 +225,8 @@ pub struct ConfigHistoryParam {
     pub order_by_desc: Option<bool>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// 是否解密cipher-配置的历史内容,未授权时返回密文
+    pub decrypt: bool,
 }
 pub struct ConfigHistorySql {}
 
@@ -1,6 +1,9 @@
 use crate::config::core::ConfigActor;
-use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::config::DEFAULT_TENANT;
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{
+    LabelMetricsItem, LabelMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+};
 use actix::prelude::*;
 
 impl Handler<MetricsQuery> for ConfigActor {
@@ -48,3 +51,30 @@ impl Handler<MetricsQuery> for ConfigActor {
         Ok(list)
     }
 }
+
+impl Handler<LabelMetricsQuery> for ConfigActor {
+    type Result = anyhow::Result<Vec<LabelMetricsItem>>;
+
+    fn handle(&mut self, _msg: LabelMetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let list = self
+            .tenant_index
+            .tenant_group
+            .iter()
+            .map(|(tenant, index)| {
+                LabelMetricsItem::new(
+                    MetricsKey::NamespaceConfigSize,
+                    vec![Label::new(
+                        "namespace",
+                        if tenant.is_empty() {
+                            DEFAULT_TENANT.to_owned()
+                        } else {
+                            tenant.as_ref().to_owned()
+                        },
+                    )],
+                    MetricsRecord::Gauge(index.get_config_count() as f32),
+                )
+            })
+            .collect();
+        Ok(list)
+    }
+}
@@ -1,8 +1,14 @@
+pub mod config_approval;
+pub mod config_crypto;
 pub mod config_db;
+pub mod config_history;
 pub mod config_index;
+pub mod config_properties;
+pub mod config_push;
 pub mod config_sled;
 pub mod config_subscribe;
 pub mod config_type;
+pub mod config_validate;
 pub mod core;
 pub mod dal;
 pub mod metrics;
@@ -21,4 +27,15 @@ impl ConfigUtils {
             val
         }
     }
+
+    ///
+    /// 配置标签去除空白、去重并排序后以逗号拼接
+    pub fn normalize_tags(tags: &str) -> String {
+        let set: std::collections::BTreeSet<&str> = Self::split_tags(tags).collect();
+        set.into_iter().collect::<Vec<_>>().join(",")
+    }
+
+    pub fn split_tags(tags: &str) -> impl Iterator<Item = &str> {
+        tags.split(',').map(|e| e.trim()).filter(|e| !e.is_empty())
+    }
 }
@@ -1,9 +1,11 @@
 use crate::config::config_type::ConfigType;
-use crate::config::core::{ConfigHistoryInfoDto, ConfigKey, ConfigValue};
+use crate::config::core::{ConfigBetaValue, ConfigHistoryInfoDto, ConfigKey, ConfigValue};
+use crate::raft::db::table::TableManagerReq;
 use crate::utils::get_md5;
 use actix::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use thiserror::Error;
 
 #[derive(Message)]
 #[rtype(result = "anyhow::Result<ConfigRaftResult>")]
@@ -13,27 +15,70 @@ pub enum ConfigRaftCmd {
         value: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        app_name: Option<Arc<String>>,
+        config_tags: Option<Arc<String>>,
         history_id: u64,
         history_table_id: Option<u64>,
         op_time: i64,
         op_user: Option<Arc<String>>,
+        md5: Option<Arc<String>>,
+        cas_md5: Option<Arc<String>>,
     },
     ConfigRemove {
         key: String,
     },
+    ConfigBetaAdd {
+        key: String,
+        value: Arc<String>,
+        beta_ips: Arc<String>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+        md5: Option<Arc<String>>,
+    },
+    ConfigBetaRemove {
+        key: String,
+    },
+    ///
+    /// 写入配置历史记录表的raft请求,经ConfigActor转发以保持与配置变更相同的应用顺序
+    HistoryTableReq(TableManagerReq),
     ApplySnaphot,
 }
 
+///
+/// CAS发布时当前配置md5与期望值不一致
+#[derive(Clone, Debug, Error)]
+#[error("config cas publish conflict, the config md5 has been changed")]
+pub struct ConfigCasConflictError;
+
+impl ConfigCasConflictError {
+    pub fn is_conflict(err: &anyhow::Error) -> bool {
+        err.downcast_ref::<Self>().is_some()
+    }
+
+    ///
+    /// 集群转发时错误只保留信息,按信息还原冲突错误
+    pub fn from_message(message: &str) -> Option<Self> {
+        if message == Self.to_string() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SetConfigParam {
     pub key: ConfigKey,
     pub value: Arc<String>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub app_name: Option<Arc<String>>,
+    pub config_tags: Option<Arc<String>>,
     pub history_id: u64,
     pub history_table_id: Option<u64>,
     pub op_time: i64,
     pub op_user: Option<Arc<String>>,
+    pub md5: Option<Arc<String>>,
 }
 
 pub enum ConfigRaftResult {
@@ -41,6 +86,7 @@ pub enum ConfigRaftResult {
         data: Vec<(ConfigKey, Arc<String>)>,
         history_table_id: u64,
     },
+    CasConflict,
     None,
 }
 
@@ -77,6 +123,21 @@ pub struct ConfigHistoryItemDO {
     pub op_user: Option<String>,
 }
 
+impl ConfigHistoryItemDO {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+}
+
 impl From<HistoryItem> for ConfigHistoryItemDO {
     fn from(value: HistoryItem) -> Self {
         Self {
@@ -108,6 +169,57 @@ pub struct ConfigValueDO {
     pub config_type: Option<String>,
     #[prost(string, optional, tag = "4")]
     pub desc: Option<String>,
+    #[prost(message, optional, tag = "5")]
+    pub beta: Option<ConfigBetaDO>,
+    #[prost(string, optional, tag = "6")]
+    pub md5: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub app_name: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub config_tags: Option<String>,
+    #[prost(int64, optional, tag = "9")]
+    pub last_modified: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct ConfigBetaDO {
+    #[prost(string, optional, tag = "1")]
+    pub content: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub beta_ips: Option<String>,
+    #[prost(int64, optional, tag = "3")]
+    pub last_time: Option<i64>,
+    #[prost(string, optional, tag = "4")]
+    pub op_user: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub md5: Option<String>,
+}
+
+impl From<ConfigBetaValue> for ConfigBetaDO {
+    fn from(value: ConfigBetaValue) -> Self {
+        Self {
+            content: Some(value.content.as_ref().to_owned()),
+            beta_ips: Some(value.beta_ips.as_ref().to_owned()),
+            last_time: Some(value.last_modified),
+            op_user: value.op_user.map(|e| e.as_ref().to_owned()),
+            md5: Some(value.md5.as_ref().to_owned()),
+        }
+    }
+}
+
+impl From<ConfigBetaDO> for ConfigBetaValue {
+    fn from(value: ConfigBetaDO) -> Self {
+        let mut beta = ConfigBetaValue::new(
+            Arc::new(value.content.unwrap_or_default()),
+            Arc::new(value.beta_ips.unwrap_or_default()),
+            value.last_time.unwrap_or_default(),
+            value.op_user.map(Arc::new),
+        );
+        if let Some(md5) = value.md5 {
+            beta.md5 = Arc::new(md5);
+        }
+        beta
+    }
 }
 
 impl ConfigValueDO {
@@ -129,9 +241,15 @@ impl From<ConfigValue> for ConfigValueDO {
     fn from(value: ConfigValue) -> Self {
         Self {
             content: Some(value.content.as_ref().to_owned()),
-            histories: value.histories.into_iter().map(|e| e.into()).collect(),
+            //历史记录存放在独立的raft table中
+            histories: vec![],
             config_type: value.config_type.map(|e| e.as_ref().to_owned()),
             desc: value.desc.map(|e| e.as_ref().to_owned()),
+            beta: value.beta.map(|e| e.into()),
+            md5: Some(value.md5.as_ref().to_owned()),
+            app_name: value.app_name.map(|e| e.as_ref().to_owned()),
+            config_tags: value.config_tags.map(|e| e.as_ref().to_owned()),
+            last_modified: Some(value.last_modified),
         }
     }
 }
@@ -139,23 +257,54 @@ impl From<ConfigValue> for ConfigValueDO {
 impl From<ConfigValueDO> for ConfigValue {
     fn from(value: ConfigValueDO) -> Self {
         let content = value.content.unwrap_or_default();
-        let md5 = Arc::new(get_md5(&content));
-        let last_modified =
-            if let Some(Some(v)) = value.histories.iter().last().map(|e| e.last_time) {
-                v
-            } else {
-                0
-            };
+        //加密存储的配置md5为明文md5,需要从快照中恢复
+        let md5 = Arc::new(value.md5.unwrap_or_else(|| get_md5(&content)));
+        let last_modified = value.last_modified.unwrap_or_default();
         Self {
             content: Arc::new(content),
             md5,
             tmp: false,
-            histories: value.histories.into_iter().map(|e| e.into()).collect(),
             config_type: value
                 .config_type
                 .map(|v| ConfigType::new_by_value(&v).get_value()),
             desc: value.desc.map(Arc::new),
+            app_name: value.app_name.map(Arc::new),
+            config_tags: value.config_tags.map(Arc::new),
             last_modified,
+            beta: value.beta.map(|e| e.into()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_beta_value_do_convert() {
+        let mut value = ConfigValue::new(Arc::new("a=1".to_owned()));
+        value.beta = Some(ConfigBetaValue::new(
+            Arc::new("a=2".to_owned()),
+            Arc::new("127.0.0.1, 192.168.1.2".to_owned()),
+            1,
+            None,
+        ));
+        let data = ConfigValueDO::from(value).to_bytes().unwrap();
+        let value: ConfigValue = ConfigValueDO::from_bytes(&data).unwrap().into();
+        assert!(value.get_client_beta("192.168.1.2").is_some());
+        assert!(value.get_client_beta("192.168.1.3").is_none());
+        assert_eq!(value.get_client_md5("127.0.0.1").as_str(), get_md5("a=2"));
+        assert_eq!(value.get_client_md5("").as_str(), get_md5("a=1"));
+    }
+
+    #[test]
+    fn config_cas_conflict_error() {
+        let err: anyhow::Error = ConfigCasConflictError.into();
+        assert!(ConfigCasConflictError::is_conflict(&err));
+        assert!(ConfigCasConflictError::from_message(&err.to_string()).is_some());
+        assert!(ConfigCasConflictError::from_message("server error").is_none());
+        assert!(!ConfigCasConflictError::is_conflict(&anyhow::anyhow!(
+            "server error"
+        )));
+    }
+}
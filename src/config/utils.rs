@@ -104,3 +104,185 @@ pub mod property_util {
         ConfigProperty::new().max_content
     }
 }
+
+pub mod diff_util {
+    use serde::{Deserialize, Serialize};
+
+    /// 超过该规模时不再逐行求最长公共子序列,直接整体替换
+    const MAX_DIFF_MATRIX_SIZE: usize = 4_000_000;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum DiffOp {
+        Equal,
+        Delete,
+        Add,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct DiffLine {
+        pub op: DiffOp,
+        pub content: String,
+    }
+
+    impl DiffLine {
+        fn new(op: DiffOp, content: &str) -> Self {
+            Self {
+                op,
+                content: content.to_owned(),
+            }
+        }
+    }
+
+    ///
+    /// 按行比较两个配置内容
+    pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let (n, m) = (old_lines.len(), new_lines.len());
+        if n.saturating_mul(m) > MAX_DIFF_MATRIX_SIZE {
+            let mut list: Vec<DiffLine> = old_lines
+                .iter()
+                .map(|e| DiffLine::new(DiffOp::Delete, e))
+                .collect();
+            list.extend(new_lines.iter().map(|e| DiffLine::new(DiffOp::Add, e)));
+            return list;
+        }
+        // lcs[i][j]: old_lines[i..] 与 new_lines[j..] 的最长公共子序列长度
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_lines[i] == new_lines[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let mut list = Vec::with_capacity(n.max(m));
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old_lines[i] == new_lines[j] {
+                list.push(DiffLine::new(DiffOp::Equal, old_lines[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                list.push(DiffLine::new(DiffOp::Delete, old_lines[i]));
+                i += 1;
+            } else {
+                list.push(DiffLine::new(DiffOp::Add, new_lines[j]));
+                j += 1;
+            }
+        }
+        list.extend(
+            old_lines[i..]
+                .iter()
+                .map(|e| DiffLine::new(DiffOp::Delete, e)),
+        );
+        list.extend(new_lines[j..].iter().map(|e| DiffLine::new(DiffOp::Add, e)));
+        list
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_line_diff() {
+            let list = line_diff("a\nb\nc", "a\nc\nd");
+            let ops: Vec<DiffOp> = list.iter().map(|e| e.op).collect();
+            assert_eq!(
+                ops,
+                vec![DiffOp::Equal, DiffOp::Delete, DiffOp::Equal, DiffOp::Add]
+            );
+            assert_eq!(list[1].content, "b");
+            assert_eq!(list[3].content, "d");
+        }
+    }
+}
+
+pub mod search_util {
+    use regex::{Regex, RegexBuilder};
+    use serde::{Deserialize, Serialize};
+
+    /// 每个配置最多返回的匹配行数
+    pub const MAX_MATCH_LINES: usize = 50;
+    const MAX_REGEX_SIZE: usize = 1 << 20;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ContentMatchLine {
+        /// 行号,从1开始
+        pub line_no: usize,
+        pub content: String,
+    }
+
+    ///
+    /// 配置内容匹配,支持子串与正则
+    #[derive(Debug, Clone)]
+    pub enum ContentMatcher {
+        Substring(String),
+        Regex(Regex),
+    }
+
+    impl ContentMatcher {
+        pub fn new(keyword: &str, is_regex: bool) -> anyhow::Result<Self> {
+            if keyword.is_empty() {
+                return Err(anyhow::anyhow!("the search keyword is empty"));
+            }
+            if is_regex {
+                let regex = RegexBuilder::new(keyword)
+                    .size_limit(MAX_REGEX_SIZE)
+                    .build()?;
+                Ok(Self::Regex(regex))
+            } else {
+                Ok(Self::Substring(keyword.to_owned()))
+            }
+        }
+
+        pub fn is_match(&self, content: &str) -> bool {
+            content.lines().any(|line| self.is_match_line(line))
+        }
+
+        fn is_match_line(&self, line: &str) -> bool {
+            match self {
+                Self::Substring(s) => line.contains(s.as_str()),
+                Self::Regex(r) => r.is_match(line),
+            }
+        }
+
+        pub fn match_lines(&self, content: &str) -> Vec<ContentMatchLine> {
+            content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| self.is_match_line(line))
+                .take(MAX_MATCH_LINES)
+                .map(|(i, line)| ContentMatchLine {
+                    line_no: i + 1,
+                    content: line.to_owned(),
+                })
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn content_match_lines() {
+            let content = "a=1\nurl=http://10.0.0.1:8080\nb=2\nbackup=http://10.0.0.1:9090";
+            let matcher = ContentMatcher::new("10.0.0.1", false).unwrap();
+            assert!(matcher.is_match(content));
+            let lines = matcher.match_lines(content);
+            assert_eq!(
+                lines.iter().map(|e| e.line_no).collect::<Vec<_>>(),
+                vec![2, 4]
+            );
+            let matcher = ContentMatcher::new(r":9\d+$", true).unwrap();
+            assert_eq!(matcher.match_lines(content)[0].line_no, 4);
+            assert!(!ContentMatcher::new("c=", false).unwrap().is_match(content));
+            assert!(ContentMatcher::new("(", true).is_err());
+        }
+    }
+}
@@ -191,6 +191,22 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/user/web_resources")
                     .route(web::get().to(v2::user_api::get_user_web_resources)),
             )
+            .service(
+                web::resource("/accesskey/list")
+                    .route(web::get().to(v2::access_key_api::get_access_key_page_list)),
+            )
+            .service(
+                web::resource("/accesskey/add")
+                    .route(web::post().to(v2::access_key_api::add_access_key)),
+            )
+            .service(
+                web::resource("/accesskey/update")
+                    .route(web::post().to(v2::access_key_api::update_access_key)),
+            )
+            .service(
+                web::resource("/accesskey/remove")
+                    .route(web::post().to(v2::access_key_api::remove_access_key)),
+            )
             .service(
                 web::resource("/user/reset_password")
                     .route(web::post().to(v2::user_api::reset_password)),
@@ -240,6 +256,70 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/config/history")
                     .route(web::get().to(v2::config_api::query_history_config_page)),
             )
+            .service(
+                web::resource("/config/history/rollback")
+                    .route(web::post().to(v2::config_api::rollback_history_config)),
+            )
+            .service(
+                web::resource("/config/history/diff")
+                    .route(web::get().to(v2::config_api::diff_history_config)),
+            )
+            .service(
+                web::resource("/config/listener/list")
+                    .route(web::get().to(v2::config_api::query_config_listener)),
+            )
+            .service(
+                web::resource("/config/push/status")
+                    .route(web::get().to(v2::config_api::query_config_push_status)),
+            )
+            .service(
+                web::resource("/config/schema/info")
+                    .route(web::get().to(v2::config_api::get_config_schema)),
+            )
+            .service(
+                web::resource("/config/schema/set")
+                    .route(web::post().to(v2::config_api::set_config_schema)),
+            )
+            .service(
+                web::resource("/config/approval/list")
+                    .route(web::get().to(v2::config_approval_api::query_config_approval_list)),
+            )
+            .service(
+                web::resource("/config/approval/info")
+                    .route(web::get().to(v2::config_approval_api::get_config_approval)),
+            )
+            .service(
+                web::resource("/config/approval/submit")
+                    .route(web::post().to(v2::config_approval_api::submit_config_approval)),
+            )
+            .service(
+                web::resource("/config/approval/approve")
+                    .route(web::post().to(v2::config_approval_api::approve_config_approval)),
+            )
+            .service(
+                web::resource("/config/approval/reject")
+                    .route(web::post().to(v2::config_approval_api::reject_config_approval)),
+            )
+            .service(
+                web::resource("/config/cipher/rotate")
+                    .route(web::post().to(v2::config_api::rotate_cipher_config)),
+            )
+            .service(
+                web::resource("/config/beta/info")
+                    .route(web::get().to(v2::config_api::get_beta_config)),
+            )
+            .service(
+                web::resource("/config/beta/add")
+                    .route(web::post().to(v2::config_api::add_beta_config)),
+            )
+            .service(
+                web::resource("/config/beta/promote")
+                    .route(web::post().to(v2::config_api::promote_beta_config)),
+            )
+            .service(
+                web::resource("/config/beta/remove")
+                    .route(web::post().to(v2::config_api::remove_beta_config)),
+            )
             .service(
                 web::resource("/service/list")
                     .route(web::get().to(v2::naming_api::query_service_list)),
@@ -272,6 +352,14 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/instance/remove")
                     .route(web::post().to(v2::naming_api::remove_instance)),
             )
+            .service(
+                web::resource("/audit/list")
+                    .route(web::get().to(v2::audit_api::query_audit_log_list)),
+            )
+            .service(
+                web::resource("/audit/export")
+                    .route(web::get().to(v2::audit_api::export_audit_log)),
+            )
             .service(
                 web::resource("/metrics/timeline")
                     .route(web::get().to(v2::metrics_api::query_metrics_timeline))
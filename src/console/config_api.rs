@@ -8,12 +8,15 @@ use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::text::Text;
 use actix_multipart::form::MultipartForm;
 use actix_multipart::Multipart;
-use actix_web::{http::header, web, Error, HttpRequest, HttpResponse, Responder};
+use actix_web::{http::header, web, Error, HttpMessage, HttpRequest, HttpResponse, Responder};
 use zip::write::FileOptions;
 
 use crate::common::appdata::AppShareData;
+use crate::common::model::UserSession;
+use crate::common::web_utils::{can_decrypt_config, get_session_privilege};
 use crate::config::core::{
-    ConfigActor, ConfigAsyncCmd, ConfigCmd, ConfigInfoDto, ConfigKey, ConfigResult,
+    ConfigActor, ConfigAsyncCmd, ConfigCmd, ConfigHistoryCmd, ConfigInfoDto, ConfigKey,
+    ConfigResult,
 };
 use crate::config::ConfigUtils;
 use crate::console::model::config_model::{
@@ -28,12 +31,16 @@ use zip::{ZipArchive, ZipWriter};
 
 use super::model::config_model::OpsConfigImportInfo;
 use super::model::PageResult;
+use super::NamespaceUtils;
 
 pub async fn query_config_list(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
-    let cmd = ConfigCmd::QueryPageInfo(Box::new(request.0.to_param().unwrap()));
+    let mut param = request.0.to_param().unwrap();
+    param.privilege = get_session_privilege(&req);
+    let cmd = ConfigCmd::QueryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
         Ok(res) => {
             let r: ConfigResult = res.unwrap();
@@ -56,33 +63,33 @@ pub async fn query_config_list(
 }
 
 pub async fn query_history_config_page(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
-    let param = match request.0.to_history_param() {
+    let mut param = match request.0.to_history_param() {
         Ok(param) => param,
         Err(err) => {
             return HttpResponse::InternalServerError().body(err.to_string());
         }
     };
-    let cmd = ConfigCmd::QueryHistoryPageInfo(Box::new(param));
+    param.decrypt = can_decrypt_config(&req);
+    let cmd = ConfigHistoryCmd::QueryHistoryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
-        Ok(res) => {
-            let r: ConfigResult = res.unwrap();
-            match r {
-                ConfigResult::ConfigHistoryInfoPage(size, list) => {
-                    let response = PageResult {
-                        count: size as u64,
-                        list,
-                    };
-                    let v = serde_json::to_string(&response).unwrap();
-                    HttpResponse::Ok()
-                        .insert_header(header::ContentType(mime::APPLICATION_JSON))
-                        .body(v)
-                }
-                _ => HttpResponse::InternalServerError().body("config result error"),
+        Ok(res) => match res {
+            Ok(ConfigResult::ConfigHistoryInfoPage(size, list)) => {
+                let response = PageResult {
+                    count: size as u64,
+                    list,
+                };
+                let v = serde_json::to_string(&response).unwrap();
+                HttpResponse::Ok()
+                    .insert_header(header::ContentType(mime::APPLICATION_JSON))
+                    .body(v)
             }
-        }
+            Ok(_) => HttpResponse::InternalServerError().body("config result error"),
+            Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        },
         Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
     }
 }
@@ -107,6 +114,28 @@ pub async fn import_config(
         },
     ));
     //let tenant = Arc::new(ConfigUtils::default_tenant(config_info.0.tenant.unwrap_or_default()));
+    if let Err(err) = NamespaceUtils::check_config_publish(&app.config_addr, &tenant).await {
+        return Ok(HttpResponse::Forbidden().body(err.to_string()));
+    }
+    let privilege = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .and_then(|e| e.namespace_privilege.clone());
+    //导入前先校验压缩包内所有分组的写权限
+    if let Some(privilege) = &privilege {
+        for f in &form.files {
+            if let Ok(archive) = zip::ZipArchive::new(f.file.as_file()) {
+                let no_privilege_group = archive
+                    .file_names()
+                    .filter_map(|name| name.split_once('/').map(|(group, _)| group))
+                    .find(|group| !privilege.check(&tenant, Some(group), true));
+                if let Some(group) = no_privilege_group {
+                    return Ok(HttpResponse::Forbidden()
+                        .body(format!("no write privilege of group:{}", group)));
+                }
+            }
+        }
+    }
     for f in form.files {
         match zip::ZipArchive::new(f.file) {
             Ok(mut archive) => {
@@ -147,7 +176,7 @@ pub async fn import_config(
             Err(_) => todo!(),
         }
     }
-    Ok(HttpResponse::Ok())
+    Ok(HttpResponse::Ok().finish())
 }
 
 fn zip_file(mut zip: ZipWriter<&mut File>, list: Vec<ConfigInfoDto>) -> anyhow::Result<()> {
@@ -177,12 +206,15 @@ fn zip_file(mut zip: ZipWriter<&mut File>, list: Vec<ConfigInfoDto>) -> anyhow::
 ///
 /// 按查询条件导出配置
 pub async fn download_config(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
     let mut param = request.0.to_param().unwrap();
     param.limit = 0xffff_ffff;
     param.query_context = true;
+    param.decrypt = can_decrypt_config(&req);
+    param.privilege = get_session_privilege(&req);
     let cmd = ConfigCmd::QueryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
         Ok(res) => {
@@ -110,6 +110,8 @@ pub async fn login(
                     + &uuid::Uuid::new_v4().to_string().replace('-', ""),
             );
             let session = Arc::new(UserSession {
+                namespace_privilege: user.get_session_privilege(),
+                config_decrypt: user.has_config_decrypt(),
                 username: user.username,
                 nickname: user.nickname,
                 roles: user.roles.unwrap_or_default(),
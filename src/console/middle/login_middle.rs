@@ -12,8 +12,13 @@ use actix_web::{
 use futures_util::future::LocalBoxFuture;
 use regex::Regex;
 
+use crate::audit::core::AuditLogReq;
+use crate::audit::model::{AuditLogUtils, AUDIT_STATUS_FORBIDDEN};
 use crate::common::appdata::AppShareData;
 use crate::common::model::{ApiResultOld, UserSession};
+use crate::common::web_utils::{
+    build_audit_log, check_request_privilege, get_client_ip, is_privilege_check_path,
+};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
 use crate::user::permission::UserRole;
@@ -77,10 +82,11 @@ where
 
     dev::forward_ready!(service);
 
-    fn call(&self, request: ServiceRequest) -> Self::Future {
+    fn call(&self, mut request: ServiceRequest) -> Self::Future {
         let path = request.path();
         let is_check_path = !IGNORE_CHECK_LOGIN.contains(&path) && !STATIC_FILE_PATH.is_match(path);
         let is_page = !API_PATH.is_match(path);
+        let is_audit = AuditLogUtils::is_audit_request(request.method().as_str(), path);
         let token = if let Some(ck) = request.cookie("token") {
             ck.value().to_owned()
         } else if let Some(v) = request.headers().get("Token") {
@@ -90,6 +96,7 @@ where
         };
         let token = Arc::new(token);
         let cache_manager = self.app_share_data.cache_manager.clone();
+        let audit_log_manager = self.app_share_data.audit_log_manager.clone();
         //request.parts()
         //let (http_request, _pl) = request.parts();
         //let http_request = http_request.to_owned();
@@ -112,6 +119,14 @@ where
                 {
                     user_has_permission =
                         UserRole::match_url_by_roles(&session.roles, path, method);
+                    if user_has_permission {
+                        if let Some(privilege) = &session.namespace_privilege {
+                            if is_privilege_check_path(request.path()) {
+                                user_has_permission =
+                                    check_request_privilege(&mut request, privilege).await;
+                            }
+                        }
+                    }
                     request.extensions_mut().insert(session);
                     true
                 } else {
@@ -121,11 +136,35 @@ where
             //log::info!("token: {}|{}|{}|{}|{}|{}",&token,is_page,is_check_path,is_login,request.path(),request.query_string());
             if is_login {
                 if user_has_permission {
+                    let audit_log = if is_audit {
+                        let op_user = request
+                            .extensions()
+                            .get::<Arc<UserSession>>()
+                            .map(|e| e.username.clone());
+                        Some(build_audit_log(&mut request, op_user).await)
+                    } else {
+                        None
+                    };
                     let res = service.call(request);
                     // forwarded responses map to "left" body
-                    res.await.map(ServiceResponse::map_into_left_body)
+                    res.await.map(|item| {
+                        if let Some(mut audit_log) = audit_log {
+                            audit_log.status = item.response().status().as_u16() as u32;
+                            audit_log_manager.do_send(AuditLogReq::Add(audit_log));
+                        }
+                        ServiceResponse::map_into_left_body(item)
+                    })
                 } else {
                     //已登录没有权限
+                    if is_audit {
+                        let op_user = request
+                            .extensions()
+                            .get::<Arc<UserSession>>()
+                            .map(|e| e.username.clone());
+                        let mut audit_log = build_audit_log(&mut request, op_user).await;
+                        audit_log.status = AUDIT_STATUS_FORBIDDEN;
+                        audit_log_manager.do_send(AuditLogReq::Add(audit_log));
+                    }
                     let response = if is_page {
                         //let move_url = format!("/nopermission?path={}", request.path());
                         let move_url = format!("/rnacos/nopermission?path={}", request.path());
@@ -146,6 +185,13 @@ where
                 }
             } else {
                 //没有登录
+                if is_audit {
+                    AuditLogUtils::log_unauthorized(
+                        &get_client_ip(request.request()),
+                        request.method().as_str(),
+                        request.path(),
+                    );
+                }
                 let response = if is_page {
                     let move_url =
                         if request.path() == "/rnacos/p/login" || request.path() == "/p/login" {
@@ -33,16 +33,20 @@ lazy_static::lazy_static! {
             namespace_id: Some("".to_owned()),
             namespace_name: Some(DEFAULT_NAMESPACE.to_owned()),
             r#type: Some("0".to_owned()),
+            need_approval: None,
     });
 }
 
 impl NamespaceUtils {
     pub async fn get_namespaces(config_addr: &Addr<ConfigActor>) -> Vec<Arc<NamespaceInfo>> {
-        let cmd = ConfigCmd::GET(ConfigKey::new(
-            SYSCONFIG_NAMESPACE_KEY,
-            SYSCONFIG_GROUP,
-            SYSCONFIG_NAMESPACE,
-        ));
+        let cmd = ConfigCmd::GET(
+            ConfigKey::new(
+                SYSCONFIG_NAMESPACE_KEY,
+                SYSCONFIG_GROUP,
+                SYSCONFIG_NAMESPACE,
+            ),
+            true,
+        );
         let namespace_str = match config_addr.send(cmd).await {
             Ok(res) => {
                 let r: ConfigResult = res.unwrap();
@@ -68,11 +72,14 @@ impl NamespaceUtils {
     }
 
     pub async fn load_namespace_from_config(config_addr: &Addr<ConfigActor>) -> Vec<NamespaceInfo> {
-        let cmd = ConfigCmd::GET(ConfigKey::new(
-            SYSCONFIG_NAMESPACE_KEY,
-            SYSCONFIG_GROUP,
-            SYSCONFIG_NAMESPACE,
-        ));
+        let cmd = ConfigCmd::GET(
+            ConfigKey::new(
+                SYSCONFIG_NAMESPACE_KEY,
+                SYSCONFIG_GROUP,
+                SYSCONFIG_NAMESPACE,
+            ),
+            true,
+        );
         let namespace_str = match config_addr.send(cmd).await {
             Ok(res) => {
                 let r: ConfigResult = res.unwrap();
@@ -93,6 +100,36 @@ impl NamespaceUtils {
         }
     }
 
+    ///
+    /// 命名空间是否要求配置变更经过审批
+    pub async fn is_need_approval(config_addr: &Addr<ConfigActor>, namespace_id: &str) -> bool {
+        if namespace_id.is_empty() || namespace_id == SYSCONFIG_NAMESPACE {
+            return false;
+        }
+        Self::load_namespace_from_config(config_addr)
+            .await
+            .iter()
+            .any(|e| {
+                e.need_approval.unwrap_or(false)
+                    && e.namespace_id.as_deref().unwrap_or_default() == namespace_id
+            })
+    }
+
+    ///
+    /// 受保护的命名空间不允许直接发布配置
+    pub async fn check_config_publish(
+        config_addr: &Addr<ConfigActor>,
+        namespace_id: &str,
+    ) -> anyhow::Result<()> {
+        if Self::is_need_approval(config_addr, namespace_id).await {
+            return Err(anyhow::anyhow!(
+                "namespace {} requires approval for config changes, please submit a config change request",
+                namespace_id
+            ));
+        }
+        Ok(())
+    }
+
     pub async fn save_namespace(
         app_data: &Arc<AppShareData>,
         value: &Vec<NamespaceInfo>,
@@ -131,6 +168,7 @@ impl NamespaceUtils {
                 namespace_id: Some(namespace_id),
                 namespace_name: Some(namespace_name),
                 r#type: Some("2".to_owned()),
+                need_approval: info.need_approval.filter(|e| *e),
             };
             infos.push(new_info);
             Self::save_namespace(app_data, &infos).await
@@ -154,6 +192,9 @@ impl NamespaceUtils {
             for mut item in infos {
                 if namespace_id.eq(item.namespace_id.as_ref().unwrap() as &str) {
                     item.namespace_name = Some(namespace_name.clone());
+                    if let Some(need_approval) = info.need_approval {
+                        item.need_approval = Some(need_approval).filter(|e| *e);
+                    }
                     update_mark = true;
                 }
                 new_infos.push(item);
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::audit::model::AuditLogQueryParam;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPageParams {
+    pub op_user: Option<String>,
+    pub source: Option<String>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub resource: Option<String>,
+    /// 开始时间,毫秒
+    pub start_time: Option<i64>,
+    /// 结束时间,毫秒
+    pub end_time: Option<i64>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl AuditLogPageParams {
+    pub fn to_param(self) -> AuditLogQueryParam {
+        let limit = self.page_size.unwrap_or(20);
+        let offset = (self.page_no.unwrap_or(1).max(1) - 1) * limit;
+        AuditLogQueryParam {
+            op_user: self.op_user.filter(|e| !e.is_empty()),
+            source: self.source.filter(|e| !e.is_empty()),
+            method: self.method.filter(|e| !e.is_empty()),
+            path: self.path.filter(|e| !e.is_empty()),
+            resource: self.resource.filter(|e| !e.is_empty()),
+            start_time: self.start_time,
+            end_time: self.end_time,
+            offset,
+            limit,
+        }
+    }
+
+    ///
+    /// 导出不分页,返回全部匹配的日志
+    pub fn to_export_param(self) -> AuditLogQueryParam {
+        let mut param = self.to_param();
+        param.offset = 0;
+        param.limit = usize::MAX;
+        param
+    }
+}
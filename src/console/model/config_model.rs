@@ -1,6 +1,11 @@
+use crate::common::string_utils::StringUtils;
+use crate::config::config_approval::{ConfigApprovalQueryParam, ConfigApprovalStatus};
 use crate::config::config_index::ConfigQueryParam;
+use crate::config::config_subscribe::ConfigListenerQueryParam;
+use crate::config::config_type::ConfigType;
 use crate::config::core::{ConfigInfoDto, ConfigKey};
 use crate::config::dal::ConfigHistoryParam;
+use crate::config::utils::diff_util::DiffLine;
 use crate::config::ConfigUtils;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -21,6 +26,12 @@ pub struct OpsConfigQueryListRequest {
     pub data_param: Option<String>,
     pub group: Option<String>,
     pub data_id: Option<String>,
+    pub app_name: Option<String>,
+    pub config_tags: Option<String>,
+    pub config_type: Option<String>,
+    /// 配置内容搜索关键字
+    pub content_keyword: Option<String>,
+    pub content_regex: Option<bool>,
 }
 
 impl OpsConfigQueryListRequest {
@@ -32,6 +43,12 @@ impl OpsConfigQueryListRequest {
             offset,
             like_group: self.group_param,
             like_data_id: self.data_param,
+            like_app_name: self.app_name,
+            config_tags: self.config_tags,
+            config_type: StringUtils::map_not_empty(self.config_type)
+                .map(|v| ConfigType::new_by_value(&v).get_value().as_ref().to_owned()),
+            content_keyword: StringUtils::map_not_empty(self.content_keyword),
+            content_regex: self.content_regex.unwrap_or_default(),
             ..Default::default()
         };
         if let Some(tenant) = self.tenant {
@@ -86,6 +103,10 @@ pub struct ConfigParams {
     pub content: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub beta_ips: Option<Arc<String>>,
+    pub app_name: Option<Arc<String>>,
+    pub config_tags: Option<Arc<String>>,
+    pub cas_md5: Option<Arc<String>>,
 }
 
 impl ConfigParams {
@@ -106,4 +127,173 @@ pub struct ConfigInfo {
     pub md5: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub app_name: Option<Arc<String>>,
+    pub config_tags: Option<Arc<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBetaInfo {
+    pub value: Option<Arc<String>>,
+    pub md5: Option<Arc<String>>,
+    pub beta_ips: Option<Arc<String>>,
+    pub op_user: Option<Arc<String>>,
+    pub last_modified: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHistoryParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub history_id: Option<u64>,
+    /// 对比的历史记录id,为空时与当前配置对比
+    pub compare_history_id: Option<u64>,
+}
+
+impl ConfigHistoryParams {
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+}
+
+///
+/// 配置内容的json schema,发布配置时按schema校验内容
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSchemaParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub schema: Option<String>,
+}
+
+impl ConfigSchemaParams {
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+}
+
+///
+/// 配置监听查询,指定dataId时按配置查询,否则按客户端查询
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigListenerParams {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub tenant: Option<String>,
+    pub client_id: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+impl ConfigListenerParams {
+    pub fn to_param(self) -> anyhow::Result<ConfigListenerQueryParam> {
+        let key = match StringUtils::map_not_empty(self.data_id) {
+            Some(data_id) => {
+                let group =
+                    StringUtils::map_not_empty(self.group).unwrap_or("DEFAULT_GROUP".to_owned());
+                let tenant = ConfigUtils::default_tenant(self.tenant.unwrap_or_default());
+                let key = ConfigKey::new(&data_id, &group, &tenant);
+                key.is_valid()?;
+                Some(key.build_key())
+            }
+            None => None,
+        };
+        let param = ConfigListenerQueryParam {
+            key,
+            client_id: StringUtils::map_not_empty(self.client_id).map(Arc::new),
+            client_ip: StringUtils::map_not_empty(self.client_ip).map(Arc::new),
+        };
+        if param.is_empty() {
+            return Err(anyhow::anyhow!("the dataId or client is required"));
+        }
+        Ok(param)
+    }
+}
+
+///
+/// 配置推送状态查询,only_stale为true时只返回md5不是最新值的客户端
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPushStatusParams {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub tenant: Option<String>,
+    pub only_stale: Option<bool>,
+}
+
+impl ConfigPushStatusParams {
+    pub fn to_key(&self) -> anyhow::Result<ConfigKey> {
+        let data_id = match StringUtils::map_not_empty(self.data_id.clone()) {
+            Some(v) => v,
+            None => return Err(anyhow::anyhow!("the dataId is required")),
+        };
+        let group =
+            StringUtils::map_not_empty(self.group.clone()).unwrap_or("DEFAULT_GROUP".to_owned());
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        let key = ConfigKey::new(&data_id, &group, &tenant);
+        key.is_valid()?;
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCipherRotateParams {
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffInfo {
+    pub history_id: u64,
+    pub compare_history_id: Option<u64>,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigApprovalPageParams {
+    pub tenant: Option<String>,
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub status: Option<ConfigApprovalStatus>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl ConfigApprovalPageParams {
+    pub fn to_param(self) -> ConfigApprovalQueryParam {
+        let limit = self.page_size.unwrap_or(20);
+        let offset = (self.page_no.unwrap_or(1).max(1) - 1) * limit;
+        ConfigApprovalQueryParam {
+            tenant: self
+                .tenant
+                .map(|e| Arc::new(ConfigUtils::default_tenant(e))),
+            data_id: self.data_id.filter(|e| !e.is_empty()),
+            group: self.group.filter(|e| !e.is_empty()),
+            status: self.status,
+            privilege: None,
+            offset,
+            limit,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigApprovalReviewParams {
+    pub id: Arc<String>,
+    pub comment: Option<String>,
 }
@@ -1,3 +1,4 @@
+pub mod audit_model;
 pub mod cluster_model;
 pub mod config_model;
 pub mod login_model;
@@ -14,6 +15,8 @@ pub struct NamespaceInfo {
     pub namespace_id: Option<String>,
     pub namespace_name: Option<String>,
     pub r#type: Option<String>,
+    /// 受保护的命名空间,配置变更需审批后发布
+    pub need_approval: Option<bool>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Default)]
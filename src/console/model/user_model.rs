@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::common::model::privilege::PrivilegeGroup;
 use crate::user::{model::UserDto, permission::UserRoleHelper};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -28,6 +29,7 @@ pub struct UpdateUserInfoParam {
     pub password: Option<String>,
     pub enable: Option<bool>,
     pub roles: Option<String>,
+    pub namespace_privilege: Option<PrivilegeGroup>,
 }
 
 impl UpdateUserInfoParam {
@@ -52,6 +54,7 @@ impl From<UpdateUserInfoParam> for UserDto {
             password: value.password,
             enable: value.enable,
             roles,
+            namespace_privilege: value.namespace_privilege,
             ..Default::default()
         }
     }
@@ -73,3 +76,20 @@ impl UserPageParams {
         (limit, offset)
     }
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessKeyPageParams {
+    pub like_key: Option<String>,
+    pub is_rev: Option<bool>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl AccessKeyPageParams {
+    pub fn get_limit_info(&self) -> (usize, usize) {
+        let limit = self.page_size.unwrap_or(0xffff_ffff);
+        let offset = (self.page_no.unwrap_or(1) - 1) * limit;
+        (limit, offset)
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::{ApiResult, PageResult};
+use crate::console::model::user_model::AccessKeyPageParams;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::user::access_key::{AccessKeyDto, AccessKeyReq, AccessKeyResult};
+
+pub async fn get_access_key_page_list(
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<AccessKeyPageParams>,
+) -> actix_web::Result<impl Responder> {
+    let (limit, offset) = param.get_limit_info();
+    let msg = AccessKeyReq::QueryPageList {
+        like_key: param.like_key,
+        offset: Some(offset as i64),
+        limit: Some(limit as i64),
+        is_rev: param.is_rev.unwrap_or_default(),
+    };
+    match app.access_key_manager.send(msg).await {
+        Ok(Ok(AccessKeyResult::PageResult(total_count, list))) => {
+            Ok(HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list }))))
+        }
+        _ => Ok(HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some("query access key list error".to_owned()),
+        ))),
+    }
+}
+
+///
+/// 创建访问密钥,secretKey只在创建时返回
+pub async fn add_access_key(
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessKeyDto>,
+) -> actix_web::Result<impl Responder> {
+    Ok(handle_access_key_req(&app, AccessKeyReq::Add(param)).await)
+}
+
+pub async fn update_access_key(
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessKeyDto>,
+) -> actix_web::Result<impl Responder> {
+    Ok(handle_access_key_req(&app, AccessKeyReq::Update(param)).await)
+}
+
+pub async fn remove_access_key(
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessKeyDto>,
+) -> actix_web::Result<impl Responder> {
+    Ok(handle_access_key_req(&app, AccessKeyReq::Remove(param.access_key)).await)
+}
+
+async fn handle_access_key_req(app: &Arc<AppShareData>, msg: AccessKeyReq) -> HttpResponse {
+    match app.access_key_manager.send(msg).await {
+        Ok(Ok(AccessKeyResult::Info(v))) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(e)) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some(e.to_string()),
+        )),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some(e.to_string()),
+        )),
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use actix_web::http::header;
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::audit::core::{AuditLogReq, AuditLogResult};
+use crate::common::appdata::AppShareData;
+use crate::common::model::{ApiResult, PageResult};
+use crate::console::model::audit_model::AuditLogPageParams;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::now_millis;
+
+pub async fn query_audit_log_list(
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<AuditLogPageParams>,
+) -> impl Responder {
+    let msg = AuditLogReq::QueryPageList(param.to_param());
+    match app.audit_log_manager.send(msg).await {
+        Ok(Ok(AuditLogResult::PageResult(total_count, list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
+        }
+        _ => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some("query audit log list error".to_owned()),
+        )),
+    }
+}
+
+///
+/// 按查询条件导出审计日志,每行一条json记录
+pub async fn export_audit_log(
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<AuditLogPageParams>,
+) -> impl Responder {
+    let msg = AuditLogReq::QueryPageList(param.to_export_param());
+    match app.audit_log_manager.send(msg).await {
+        Ok(Ok(AuditLogResult::PageResult(_, list))) => {
+            let mut buf = Vec::new();
+            for item in list {
+                if let Ok(line) = serde_json::to_vec(&item) {
+                    buf.extend_from_slice(&line);
+                    buf.push(b'\n');
+                }
+            }
+            let filename = format!("rnacos_audit_log_{}.jsonl", now_millis());
+            HttpResponse::Ok()
+                .insert_header((header::CONTENT_TYPE, "application/x-ndjson"))
+                .insert_header(header::ContentDisposition::attachment(filename))
+                .body(buf)
+        }
+        Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+        _ => HttpResponse::InternalServerError().body("query audit log error"),
+    }
+}
@@ -1,33 +1,58 @@
 use crate::common::appdata::AppShareData;
-use crate::common::model::{ApiResult, PageResult};
-use crate::config::core::{ConfigActor, ConfigCmd, ConfigResult};
-use crate::console::model::config_model::{ConfigInfo, ConfigParams, OpsConfigQueryListRequest};
+use crate::common::constant::CONFIG_SCHEMA_TREE_NAME;
+use crate::common::model::{ApiResult, PageResult, UserSession};
+use crate::common::web_utils::{can_decrypt_config, get_session_privilege};
+use crate::config::config_validate::ConfigContentValidator;
+use crate::config::core::{
+    ConfigActor, ConfigAsyncCmd, ConfigCmd, ConfigHistoryCmd, ConfigKey, ConfigResult,
+};
+use crate::config::model::{ConfigCasConflictError, HistoryItem};
+use crate::config::utils::diff_util;
+use crate::config::ConfigUtils;
+use crate::console::model::config_model::{
+    ConfigBetaInfo, ConfigCipherRotateParams, ConfigDiffInfo, ConfigHistoryParams, ConfigInfo,
+    ConfigListenerParams, ConfigParams, ConfigPushStatusParams, ConfigSchemaParams,
+    OpsConfigQueryListRequest,
+};
 use actix::Addr;
 use actix_web::web::Data;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 pub use crate::console::config_api::{download_config, import_config};
-use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
-use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
+use crate::console::v2::{ERROR_CODE_CONFIG_CAS_CONFLICT, ERROR_CODE_SYSTEM_ERROR};
+use crate::console::NamespaceUtils;
+use crate::raft::cluster::model::{DelConfigReq, SetBetaConfigReq, SetConfigReq};
+use crate::raft::db::table::{TableManagerQueryReq, TableManagerReq, TableManagerResult};
 
 pub async fn query_config_list(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
-    let cmd = ConfigCmd::QueryPageInfo(Box::new(request.0.to_param().unwrap()));
+    let mut param = request.0.to_param().unwrap();
+    param.decrypt = can_decrypt_config(&req);
+    param.privilege = get_session_privilege(&req);
+    let cmd = if param.content_keyword.is_some() {
+        ConfigCmd::SearchContent(Box::new(param))
+    } else {
+        ConfigCmd::QueryPageInfo(Box::new(param))
+    };
     match config_addr.send(cmd).await {
-        Ok(res) => {
-            let r: ConfigResult = res.unwrap();
-            match r {
-                ConfigResult::ConfigInfoPage(total_count, list) => HttpResponse::Ok()
-                    .json(ApiResult::success(Some(PageResult { total_count, list }))),
-                _ => HttpResponse::Ok().json(ApiResult::<()>::error(
-                    ERROR_CODE_SYSTEM_ERROR.to_string(),
-                    None,
-                )),
+        Ok(res) => match res {
+            Ok(ConfigResult::ConfigInfoPage(total_count, list)) => {
+                HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
             }
-        }
+            Ok(_) => HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                None,
+            )),
+            Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some(err.to_string()),
+            )),
+        },
         Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
             Some(err.to_string()),
@@ -36,10 +61,11 @@ pub async fn query_config_list(
 }
 
 pub async fn query_history_config_page(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
-    let param = match request.0.to_history_param() {
+    let mut param = match request.0.to_history_param() {
         Ok(param) => param,
         Err(err) => {
             return HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -48,19 +74,22 @@ pub async fn query_history_config_page(
             ));
         }
     };
-    let cmd = ConfigCmd::QueryHistoryPageInfo(Box::new(param));
+    param.decrypt = can_decrypt_config(&req);
+    let cmd = ConfigHistoryCmd::QueryHistoryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
-        Ok(res) => {
-            let r: ConfigResult = res.unwrap();
-            match r {
-                ConfigResult::ConfigHistoryInfoPage(total_count, list) => HttpResponse::Ok()
-                    .json(ApiResult::success(Some(PageResult { total_count, list }))),
-                _ => HttpResponse::Ok().json(ApiResult::<()>::error(
-                    ERROR_CODE_SYSTEM_ERROR.to_string(),
-                    None,
-                )),
+        Ok(res) => match res {
+            Ok(ConfigResult::ConfigHistoryInfoPage(total_count, list)) => {
+                HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
             }
-        }
+            Ok(_) => HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                None,
+            )),
+            Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some(err.to_string()),
+            )),
+        },
         Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
             Some(err.to_string()),
@@ -69,16 +98,19 @@ pub async fn query_history_config_page(
 }
 
 pub(crate) async fn get_config(
+    req: HttpRequest,
     web::Query(param): web::Query<ConfigParams>,
     appdata: Data<Arc<AppShareData>>,
 ) -> impl Responder {
     let config_key = param.to_key();
-    let cmd = ConfigCmd::GET(config_key);
+    let cmd = ConfigCmd::GET(config_key, can_decrypt_config(&req));
     if let Ok(Ok(ConfigResult::Data {
         value: v,
         md5,
         config_type,
         desc,
+        app_name,
+        config_tags,
         ..
     })) = appdata.config_addr.send(cmd).await
     {
@@ -87,6 +119,8 @@ pub(crate) async fn get_config(
             md5: Some(md5),
             config_type,
             desc,
+            app_name,
+            config_tags,
         })))
     } else {
         HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -108,24 +142,173 @@ pub async fn add_config(
             Some(e.to_string()),
         ));
     }
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
     let mut req = SetConfigReq::new(config_key, content);
     req.config_type = param.config_type;
     req.desc = param.desc;
-    if appdata.config_route.set_config(req).await.is_ok() {
-        HttpResponse::Ok().json(ApiResult::success(Some(true)))
-    } else {
-        HttpResponse::Ok().json(ApiResult::<()>::error(
+    req.app_name = param.app_name;
+    req.config_tags = param.config_tags;
+    req.cas_md5 = param.cas_md5.filter(|v| !v.is_empty());
+    match appdata.config_route.set_config(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) if ConfigCasConflictError::is_conflict(&err) => {
+            HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_CONFIG_CAS_CONFLICT.to_string(),
+                Some(err.to_string()),
+            ))
+        }
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
-            None,
-        ))
+            Some(err.to_string()),
+        )),
+    }
+}
+
+///
+/// 查询配置的监听客户端,或客户端监听的配置;汇总集群各节点的连接
+pub async fn query_config_listener(
+    web::Query(param): web::Query<ConfigListenerParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = match param.to_param() {
+        Ok(v) => v,
+        Err(err) => {
+            return HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some(err.to_string()),
+            ))
+        }
+    };
+    match appdata
+        .config_route
+        .query_listener(param, &appdata.naming_node_manage)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(ApiResult::success(Some(list))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+///
+/// 查询配置变更的推送状态,列出仍持有旧md5的客户端
+pub async fn query_config_push_status(
+    web::Query(param): web::Query<ConfigPushStatusParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = match param.to_key() {
+        Ok(v) => v,
+        Err(err) => {
+            return HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some(err.to_string()),
+            ))
+        }
+    };
+    match appdata
+        .config_route
+        .query_push_status(key, &appdata.naming_node_manage)
+        .await
+    {
+        Ok(mut list) => {
+            if param.only_stale.unwrap_or(false) {
+                list.retain(|e| !e.is_latest);
+            }
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+pub async fn get_config_schema(
+    web::Query(param): web::Query<ConfigSchemaParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let req = TableManagerQueryReq::GetByBytes {
+        table_name: CONFIG_SCHEMA_TREE_NAME.clone(),
+        key: param.to_key().build_key().into_bytes(),
+    };
+    match appdata.raft_table_manage.send(req).await {
+        Ok(Ok(TableManagerResult::Value(v))) => HttpResponse::Ok().json(ApiResult::success(Some(
+            String::from_utf8_lossy(&v).to_string(),
+        ))),
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::<String>::success(None)),
+        Ok(Err(err)) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
     }
 }
 
+///
+/// 设置配置的json schema,schema为空时删除
+pub async fn set_config_schema(
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigSchemaParams>,
+) -> impl Responder {
+    match do_set_config_schema(&appdata, param).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+async fn do_set_config_schema(
+    appdata: &Data<Arc<AppShareData>>,
+    param: ConfigSchemaParams,
+) -> anyhow::Result<()> {
+    let config_key = param.to_key();
+    config_key.is_valid()?;
+    let key = config_key.build_key().into_bytes();
+    let req = match param.schema {
+        Some(schema) if !schema.trim().is_empty() => {
+            ConfigContentValidator::check_schema(&schema)?;
+            TableManagerReq::Set {
+                table_name: CONFIG_SCHEMA_TREE_NAME.clone(),
+                key,
+                value: schema.into_bytes(),
+                last_seq_id: None,
+            }
+        }
+        _ => TableManagerReq::Remove {
+            table_name: CONFIG_SCHEMA_TREE_NAME.clone(),
+            key,
+        },
+    };
+    appdata.raft_table_route.request(req).await
+}
+
 pub async fn remove_config(
     appdata: Data<Arc<AppShareData>>,
     web::Json(param): web::Json<ConfigParams>,
 ) -> impl Responder {
     let config_key = param.to_key();
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
     let req = DelConfigReq::new(config_key);
     if appdata.config_route.del_config(req).await.is_ok() {
         HttpResponse::Ok().json(ApiResult::success(Some(true)))
@@ -136,3 +319,307 @@ pub async fn remove_config(
         ))
     }
 }
+
+pub(crate) async fn get_beta_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let cmd = ConfigCmd::GetBeta(param.to_key(), can_decrypt_config(&req));
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::BetaData(beta))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(ConfigBetaInfo {
+                value: Some(beta.content),
+                md5: Some(beta.md5),
+                beta_ips: Some(beta.beta_ips),
+                op_user: beta.op_user,
+                last_modified: beta.last_modified,
+            })))
+        }
+        Ok(_) => HttpResponse::Ok().json(ApiResult::<ConfigBetaInfo>::success(None)),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+pub async fn add_beta_config(
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    let beta_ips = param.beta_ips.clone().unwrap_or_default();
+    if beta_ips.trim().is_empty() {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some("betaIps is empty".to_owned()),
+        ));
+    }
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
+    if let Ok(Ok(ConfigResult::NULL)) = appdata
+        .config_addr
+        .send(ConfigCmd::GET(config_key.clone(), false))
+        .await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some("the config is not exist".to_owned()),
+        ));
+    }
+    let content = param.content.clone().unwrap_or_default();
+    let req = SetBetaConfigReq::new(config_key, content, beta_ips);
+    match appdata.config_route.set_beta_config(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+///
+/// 灰度内容转为正式发布,并删除灰度
+pub async fn promote_beta_config(
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
+    let beta = match appdata
+        .config_addr
+        .send(ConfigCmd::GetBeta(config_key.clone(), true))
+        .await
+    {
+        Ok(Ok(ConfigResult::BetaData(beta))) => beta,
+        _ => {
+            return HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some("the beta config is not exist".to_owned()),
+            ));
+        }
+    };
+    let mut req = SetConfigReq::new(config_key.clone(), beta.content);
+    req.op_user = beta.op_user;
+    let result = match appdata.config_route.set_config(req).await {
+        Ok(_) => {
+            appdata
+                .config_route
+                .del_beta_config(DelConfigReq::new(config_key))
+                .await
+        }
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+pub async fn remove_beta_config(
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let req = DelConfigReq::new(param.to_key());
+    match appdata.config_route.del_beta_config(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+async fn get_history_item(
+    appdata: &Data<Arc<AppShareData>>,
+    key: ConfigKey,
+    history_id: u64,
+    decrypt: bool,
+) -> anyhow::Result<HistoryItem> {
+    match appdata
+        .config_addr
+        .send(ConfigHistoryCmd::GetHistory(key, history_id, decrypt))
+        .await??
+    {
+        ConfigResult::HistoryItem(item) => Ok(item),
+        _ => Err(anyhow::anyhow!(
+            "the config history is not exist: {}",
+            history_id
+        )),
+    }
+}
+
+///
+/// 回滚到指定的历史版本
+pub async fn rollback_history_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigHistoryParams>,
+) -> impl Responder {
+    let history_id = match param.history_id {
+        Some(v) => v,
+        None => {
+            return HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some("historyId is empty".to_owned()),
+            ));
+        }
+    };
+    let config_key = param.to_key();
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
+    let item = match get_history_item(&appdata, config_key.clone(), history_id, true).await {
+        Ok(v) => v,
+        Err(err) => {
+            return HttpResponse::Ok().json(ApiResult::<()>::error(
+                ERROR_CODE_SYSTEM_ERROR.to_string(),
+                Some(err.to_string()),
+            ));
+        }
+    };
+    let mut config_req = SetConfigReq::new(config_key, item.content);
+    config_req.op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone());
+    config_req.desc = Some(Arc::new(format!("rollback from #{}", history_id)));
+    match appdata.config_route.set_config(config_req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+///
+/// 使用命名空间当前密钥重新加密配置及其历史记录,用于密钥轮换
+/// 历史记录在当前节点重新加密后通过raft写入;轮换前的raft日志仍是旧密钥密文,需在生成新的raft镜像后再移除旧密钥
+pub async fn rotate_cipher_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigCipherRotateParams>,
+) -> impl Responder {
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone());
+    match do_rotate_cipher_config(&appdata, param, op_user).await {
+        Ok(v) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+async fn do_rotate_cipher_config(
+    appdata: &Data<Arc<AppShareData>>,
+    param: ConfigCipherRotateParams,
+    op_user: Option<Arc<String>>,
+) -> anyhow::Result<usize> {
+    let tenant = Arc::new(ConfigUtils::default_tenant(
+        param.tenant.unwrap_or_default(),
+    ));
+    let keys = match appdata
+        .config_addr
+        .send(ConfigCmd::QueryCipherRotateKeys(tenant.clone()))
+        .await??
+    {
+        ConfigResult::ChangeKey(keys) => keys,
+        _ => vec![],
+    };
+    let mut rotated_keys = HashSet::new();
+    for key in keys {
+        if let ConfigResult::Data { value, .. } = appdata
+            .config_addr
+            .send(ConfigCmd::GET(key.clone(), true))
+            .await??
+        {
+            let mut config_req = SetConfigReq::new(key.clone(), value);
+            config_req.op_user = op_user.clone();
+            appdata.config_route.set_config(config_req).await?;
+            rotated_keys.insert(key);
+        }
+    }
+    if let ConfigResult::ChangeKey(keys) = appdata
+        .config_addr
+        .send(ConfigAsyncCmd::RotateCipherHistories(tenant))
+        .await??
+    {
+        rotated_keys.extend(keys);
+    }
+    Ok(rotated_keys.len())
+}
+
+///
+/// 比较历史版本与另一个历史版本(或当前配置)的差异
+pub async fn diff_history_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigHistoryParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    match do_diff_history_config(&appdata, &param, can_decrypt_config(&req)).await {
+        Ok(v) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+async fn do_diff_history_config(
+    appdata: &Data<Arc<AppShareData>>,
+    param: &ConfigHistoryParams,
+    decrypt: bool,
+) -> anyhow::Result<ConfigDiffInfo> {
+    let history_id = param
+        .history_id
+        .ok_or_else(|| anyhow::anyhow!("historyId is empty"))?;
+    let config_key = param.to_key();
+    let old_content = get_history_item(appdata, config_key.clone(), history_id, decrypt)
+        .await?
+        .content;
+    let new_content = if let Some(compare_id) = param.compare_history_id {
+        get_history_item(appdata, config_key, compare_id, decrypt)
+            .await?
+            .content
+    } else {
+        match appdata
+            .config_addr
+            .send(ConfigCmd::GET(config_key, decrypt))
+            .await??
+        {
+            ConfigResult::Data { value, .. } => value,
+            _ => return Err(anyhow::anyhow!("the config is not exist")),
+        }
+    };
+    Ok(ConfigDiffInfo {
+        history_id,
+        compare_history_id: param.compare_history_id,
+        lines: diff_util::line_diff(&old_content, &new_content),
+    })
+}
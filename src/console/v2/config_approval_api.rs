@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::{ApiResult, PageResult, UserSession};
+use crate::common::web_utils::{can_decrypt_config, get_session_privilege};
+use crate::config::config_approval::{
+    ConfigApprovalDto, ConfigApprovalReq, ConfigApprovalResult, ConfigApprovalReviewParam,
+};
+use crate::console::model::config_model::{
+    ConfigApprovalPageParams, ConfigApprovalReviewParams, ConfigParams,
+};
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+
+fn get_op_user(req: &HttpRequest) -> Option<Arc<String>> {
+    req.extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone())
+}
+
+pub async fn query_config_approval_list(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigApprovalPageParams>,
+) -> impl Responder {
+    let mut param = param.to_param();
+    param.privilege = get_session_privilege(&req);
+    let msg = ConfigApprovalReq::QueryPageList(param);
+    match app.config_approval_manager.send(msg).await {
+        Ok(Ok(ConfigApprovalResult::PageResult(total_count, list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
+        }
+        _ => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some("query config change list error".to_owned()),
+        )),
+    }
+}
+
+///
+/// 查询变更详情,待审批的变更返回与当前配置的差异
+pub async fn get_config_approval(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigApprovalReviewParams>,
+) -> impl Responder {
+    let msg = ConfigApprovalReq::Get(param.id, can_decrypt_config(&req));
+    handle_config_approval_req(&app, msg).await
+}
+
+///
+/// 提交配置变更,审批通过后才发布
+pub async fn submit_config_approval(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let key = param.to_key();
+    let dto = ConfigApprovalDto {
+        data_id: key.data_id,
+        group: key.group,
+        tenant: key.tenant,
+        content: param.content,
+        config_type: param.config_type,
+        desc: param.desc,
+        author: get_op_user(&req),
+        ..Default::default()
+    };
+    handle_config_approval_req(&app, ConfigApprovalReq::Submit(dto)).await
+}
+
+pub async fn approve_config_approval(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigApprovalReviewParams>,
+) -> impl Responder {
+    let msg = ConfigApprovalReq::Review(ConfigApprovalReviewParam {
+        id: param.id,
+        reviewer: get_op_user(&req),
+        approve: true,
+        comment: param.comment,
+    });
+    handle_config_approval_req(&app, msg).await
+}
+
+pub async fn reject_config_approval(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigApprovalReviewParams>,
+) -> impl Responder {
+    let msg = ConfigApprovalReq::Review(ConfigApprovalReviewParam {
+        id: param.id,
+        reviewer: get_op_user(&req),
+        approve: false,
+        comment: param.comment,
+    });
+    handle_config_approval_req(&app, msg).await
+}
+
+async fn handle_config_approval_req(
+    app: &Arc<AppShareData>,
+    msg: ConfigApprovalReq,
+) -> HttpResponse {
+    match app.config_approval_manager.send(msg).await {
+        Ok(Ok(ConfigApprovalResult::Info(v))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(v)))
+        }
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(e)) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some(e.to_string()),
+        )),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_owned(),
+            Some(e.to_string()),
+        )),
+    }
+}
@@ -1,8 +1,11 @@
 use crate::common::model::ApiResult;
 use actix_web::HttpResponse;
 
+pub mod access_key_api;
+pub mod audit_api;
 pub mod cluster_api;
 pub mod config_api;
+pub mod config_approval_api;
 pub mod login_api;
 pub mod metrics_api;
 pub mod namespace_api;
@@ -10,6 +13,7 @@ pub mod naming_api;
 pub mod user_api;
 
 pub const ERROR_CODE_SYSTEM_ERROR: &str = "SYSTEM_ERROR";
+pub const ERROR_CODE_CONFIG_CAS_CONFLICT: &str = "CONFIG_CAS_CONFLICT";
 
 pub enum ApiResponse<T>
 where
@@ -1,5 +1,6 @@
 use crate::common::appdata::AppShareData;
 use crate::common::model::{ApiResult, PageResult};
+use crate::common::web_utils::get_session_privilege;
 use crate::console::model::naming_model::{
     InstanceParams, ServiceDto, ServiceParam, ServiceQueryListRequest,
 };
@@ -9,14 +10,16 @@ use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
 use crate::naming::model::{InstanceUpdateTag, ServiceDetailDto};
 use actix::Addr;
 use actix_web::web::Data;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use std::sync::Arc;
 
 pub async fn query_service_list(
+    req: HttpRequest,
     param: web::Query<ServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
-    let service_param = param.0.to_param().unwrap();
+    let mut service_param = param.0.to_param().unwrap();
+    service_param.privilege = get_session_privilege(&req);
     match naming_addr
         .send(NamingCmd::QueryServiceInfoPage(service_param))
         .await
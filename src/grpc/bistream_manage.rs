@@ -5,7 +5,10 @@ use std::{
 };
 
 use crate::{
-    config::core::{ConfigActor, ConfigCmd, ConfigKey},
+    config::{
+        config_push::{ConfigPushState, ConfigPushTracker},
+        core::{ConfigActor, ConfigCmd, ConfigKey},
+    },
     naming::{
         core::{NamingActor, NamingCmd},
         model::{ServiceInfo, ServiceKey},
@@ -14,7 +17,10 @@ use crate::{
 };
 
 use super::{
-    api_model::{ConfigChangeNotifyRequest, NotifySubscriberRequest, CONFIG_MODEL, NAMING_MODEL},
+    api_model::{
+        BaseResponse, ConfigChangeNotifyRequest, NotifySubscriberRequest, CONFIG_MODEL,
+        NAMING_MODEL, SUCCESS_CODE,
+    },
     bistream_conn::{BiStreamConn, BiStreamSenderCmd},
     handler::converter::ModelConverter,
     nacos_proto::Payload,
@@ -47,6 +53,7 @@ pub struct BiStreamManage {
     detection_time_out: u64,
     response_time_out: u64,
     request_id: u64,
+    pub(crate) config_push_tracker: ConfigPushTracker,
     config_addr: Option<Addr<ConfigActor>>,
     naming_addr: Option<Addr<NamingActor>>,
 }
@@ -93,6 +100,61 @@ impl BiStreamManage {
         self.request_id.to_string()
     }
 
+    fn build_config_notify_payload(&mut self, config_key: &ConfigKey) -> Arc<Payload> {
+        let request = ConfigChangeNotifyRequest {
+            group: config_key.group.clone(),
+            data_id: config_key.data_id.clone(),
+            tenant: config_key.tenant.clone(),
+            request_id: Some(self.next_request_id()),
+            module: Some(CONFIG_MODEL.to_string()),
+            ..Default::default()
+        };
+        Arc::new(PayloadUtils::build_payload(
+            "ConfigChangeNotifyRequest",
+            serde_json::to_string(&request).unwrap(),
+        ))
+    }
+
+    ///
+    /// 客户端应答配置变更推送
+    fn ack_config_push(&mut self, client_id: Arc<String>, payload: &Payload) {
+        let body = match &payload.body {
+            Some(v) => &v.value,
+            None => return,
+        };
+        if let Ok(response) = serde_json::from_slice::<BaseResponse>(body) {
+            if response.result_code != SUCCESS_CODE {
+                return;
+            }
+            if let Some(request_id) = response.request_id {
+                self.config_push_tracker
+                    .ack(client_id, Arc::new(request_id), now_millis());
+            }
+        }
+    }
+
+    ///
+    /// 重推超时未应答的配置变更,连接已断开的客户端不再重推
+    fn check_config_push(&mut self, now: u64) {
+        let retry_list = self.config_push_tracker.timeout(now);
+        if !retry_list.is_empty() {
+            log::info!("retry config push, size:{}", retry_list.len());
+        }
+        for (client_id, config_key) in retry_list {
+            if !self.conn_cache.contains_key(&client_id) {
+                self.config_push_tracker.remove_client(&client_id);
+                continue;
+            }
+            let payload = self.build_config_notify_payload(&config_key);
+            let request_id = Arc::new(self.request_id.to_string());
+            self.config_push_tracker
+                .retry(client_id.clone(), config_key, request_id, now);
+            if let Some(item) = self.conn_cache.get(&client_id) {
+                item.conn.do_send(BiStreamSenderCmd::Send(payload));
+            }
+        }
+    }
+
     fn check_active_time_set(&mut self, now: u64) {
         let keys = self.active_time_set.timeout(now);
         let mut check_keys = vec![];
@@ -135,6 +197,7 @@ impl BiStreamManage {
             log::info!("check timeout close client, size:{}", del_keys.len());
         }
         for key in &del_keys {
+            self.config_push_tracker.remove_client(key);
             if let Some(item) = self.conn_cache.remove(key) {
                 //item.conn.do_send(BiStreamSenderCmd::Reset(self.next_request_id(),None,None));
                 item.conn.do_send(BiStreamSenderCmd::Close);
@@ -157,6 +220,7 @@ impl BiStreamManage {
             let now = now_millis();
             act.check_active_time_set(now);
             act.check_response_time_set(now);
+            act.check_config_push(now);
             act.time_out_heartbeat(ctx);
         });
     }
@@ -202,10 +266,12 @@ pub enum BiStreamManageCmd {
     NotifyConfig(ConfigKey, HashSet<Arc<String>>),
     NotifyNaming(ServiceKey, HashSet<Arc<String>>, ServiceInfo),
     QueryConnList,
+    QueryConfigPushState(Vec<(Arc<String>, ConfigKey)>),
 }
 
 pub enum BiStreamManageResult {
     ConnList(Vec<Arc<String>>),
+    ConfigPushState(Vec<Option<ConfigPushState>>),
     None,
 }
 
@@ -216,7 +282,10 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
         match msg {
             BiStreamManageCmd::Response(client_id, payload) => {
                 //println!("BiStreamManageCmd payload:{},client_id:{}",PayloadUtils::get_payload_string(&payload),&client_id);
-                if let Some(_t) = PayloadUtils::get_payload_type(&payload) {
+                if let Some(t) = PayloadUtils::get_payload_type(&payload) {
+                    if t == "ConfigChangeNotifyResponse" {
+                        self.ack_config_push(client_id.clone(), &payload);
+                    }
                     self.active_client(client_id).ok();
                     //if "ClientDetectionResponse"== t {
                     //}
@@ -224,6 +293,7 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
             }
             BiStreamManageCmd::ConnClose(client_id) => {
                 self.conn_cache.remove(&client_id);
+                self.config_push_tracker.remove_client(&client_id);
                 if let Some(config_addr) = &self.config_addr {
                     config_addr.do_send(ConfigCmd::RemoveSubscribeClient(client_id.clone()))
                 }
@@ -240,21 +310,18 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 self.active_client(client_id)?;
             }
             BiStreamManageCmd::NotifyConfig(config_key, client_id_set) => {
-                let request = ConfigChangeNotifyRequest {
-                    group: config_key.group,
-                    data_id: config_key.data_id,
-                    tenant: config_key.tenant,
-                    request_id: Some(self.next_request_id()),
-                    module: Some(CONFIG_MODEL.to_string()),
-                    ..Default::default()
-                };
-                let payload = Arc::new(PayloadUtils::build_payload(
-                    "ConfigChangeNotifyRequest",
-                    serde_json::to_string(&request).unwrap(),
-                ));
-                for item in &client_id_set {
-                    if let Some(item) = self.conn_cache.get(item) {
+                let payload = self.build_config_notify_payload(&config_key);
+                let request_id = Arc::new(self.request_id.to_string());
+                let now = now_millis();
+                for client_id in client_id_set {
+                    if let Some(item) = self.conn_cache.get(&client_id) {
                         item.conn.do_send(BiStreamSenderCmd::Send(payload.clone()));
+                        self.config_push_tracker.push(
+                            client_id,
+                            config_key.clone(),
+                            request_id.clone(),
+                            now,
+                        );
                     }
                 }
             }
@@ -286,6 +353,13 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 }
                 return Ok(BiStreamManageResult::ConnList(list));
             }
+            BiStreamManageCmd::QueryConfigPushState(keys) => {
+                let list = keys
+                    .iter()
+                    .map(|(client_id, key)| self.config_push_tracker.get_state(client_id, key))
+                    .collect();
+                return Ok(BiStreamManageResult::ConfigPushState(list));
+            }
         }
         Ok(BiStreamManageResult::None)
     }
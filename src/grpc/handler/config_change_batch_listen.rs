@@ -44,7 +44,11 @@ impl PayloadHandler for ConfigChangeBatchListenRequestHandler {
             listener_items.push(ListenerItem::new(key, item.md5));
         }
         let cmd = if request.listen {
-            ConfigCmd::Subscribe(listener_items, request_meta.connection_id)
+            ConfigCmd::Subscribe(
+                listener_items,
+                request_meta.connection_id,
+                Arc::new(request_meta.client_ip),
+            )
         } else {
             ConfigCmd::RemoveSubscribe(listener_items, request_meta.connection_id)
         };
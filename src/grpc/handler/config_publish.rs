@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 use crate::common::string_utils::StringUtils;
 use crate::config::config_type::ConfigType;
+use crate::config::model::ConfigCasConflictError;
+use crate::console::NamespaceUtils;
 use crate::grpc::HandlerResult;
 use crate::{
     common::appdata::AppShareData,
@@ -18,6 +20,9 @@ use crate::{
 use actix::prelude::Addr;
 use async_trait::async_trait;
 
+/// CAS发布冲突错误码
+const CONFIG_CAS_CONFLICT_ERROR_CODE: u16 = 409;
+
 pub struct ConfigPublishRequestHandler {
     app_data: Arc<AppShareData>,
 }
@@ -41,13 +46,30 @@ impl PayloadHandler for ConfigPublishRequestHandler {
             .map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
         let desc =
             StringUtils::map_not_empty(request.get_addition_param("desc").cloned()).map(Arc::new);
+        let app_name = request.get_addition_param("appName").cloned().map(Arc::new);
+        let config_tags = request
+            .get_addition_param("config_tags")
+            .cloned()
+            .map(Arc::new);
         let mut req = SetConfigReq::new(
             ConfigKey::new(&request.data_id, &request.group, &request.tenant),
             request.content,
         );
         req.config_type = config_type;
         req.desc = desc;
-        match self.app_data.config_route.set_config(req).await {
+        req.app_name = app_name;
+        req.config_tags = config_tags;
+        req.cas_md5 = StringUtils::map_not_empty(request.cas_md5).map(Arc::new);
+        let result = match NamespaceUtils::check_config_publish(
+            &self.app_data.config_addr,
+            &req.config_key.tenant,
+        )
+        .await
+        {
+            Ok(_) => self.app_data.config_route.set_config(req).await,
+            Err(err) => Err(err),
+        };
+        match result {
             Ok(_res) => {
                 //let res:ConfigResult = res.unwrap();
                 let mut response = BaseResponse::build_success_response();
@@ -58,7 +80,12 @@ impl PayloadHandler for ConfigPublishRequestHandler {
                 )))
             }
             Err(err) => {
-                let mut response = BaseResponse::build_error_response(500u16, err.to_string());
+                let error_code = if ConfigCasConflictError::is_conflict(&err) {
+                    CONFIG_CAS_CONFLICT_ERROR_CODE
+                } else {
+                    500u16
+                };
+                let mut response = BaseResponse::build_error_response(error_code, err.to_string());
                 response.request_id = request.request_id;
                 Ok(HandlerResult::success(PayloadUtils::build_payload(
                     "ErrorResponse",
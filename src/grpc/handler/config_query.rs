@@ -35,31 +35,31 @@ impl PayloadHandler for ConfigQueryRequestHandler {
     async fn handle(
         &self,
         request_payload: crate::grpc::nacos_proto::Payload,
-        _request_meta: crate::grpc::RequestMeta,
+        request_meta: crate::grpc::RequestMeta,
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ConfigQueryRequest = serde_json::from_slice(&body_vec)?;
-        let cmd = ConfigCmd::GET(ConfigKey::new(
-            &request.data_id,
-            &request.group,
-            &request.tenant,
-        ));
+        let decrypt = request_meta.can_decrypt_config();
+        let cmd = ConfigCmd::GetByClient(
+            ConfigKey::new(&request.data_id, &request.group, &request.tenant),
+            Arc::new(request_meta.client_ip),
+            decrypt,
+        );
         let mut response = ConfigQueryResponse {
             request_id: request.request_id,
             ..Default::default()
         };
         match self.app_data.config_addr.send(cmd).await {
             Ok(res) => {
-                //let res:ConfigResult = res.unwrap();
-                let r: ConfigResult = res.unwrap();
-                match r {
-                    ConfigResult::Data {
+                match res {
+                    Ok(ConfigResult::Data {
                         value: content,
                         md5,
                         config_type,
                         last_modified,
+                        beta,
                         ..
-                    } => {
+                    }) => {
                         //v.to_owned()
                         response.result_code = SUCCESS_CODE;
                         response.content = content;
@@ -75,12 +75,18 @@ impl PayloadHandler for ConfigQueryRequestHandler {
                         }
                         response.last_modified = last_modified;
                         response.md5 = Some(md5);
+                        response.beta = beta;
                     }
-                    _ => {
+                    Ok(_) => {
                         response.result_code = ERROR_CODE;
                         response.error_code = NOT_FOUND;
                         response.message = Some("config data not exist".to_owned());
                     }
+                    Err(err) => {
+                        response.result_code = ERROR_CODE;
+                        response.error_code = ERROR_CODE;
+                        response.message = Some(err.to_string());
+                    }
                 }
                 Ok(HandlerResult::success(PayloadUtils::build_payload(
                     "ConfigQueryResponse",
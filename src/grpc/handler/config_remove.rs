@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use crate::console::NamespaceUtils;
 use crate::grpc::HandlerResult;
 use crate::{
     common::appdata::AppShareData,
@@ -40,7 +41,16 @@ impl PayloadHandler for ConfigRemoveRequestHandler {
             &request.group,
             &request.tenant,
         ));
-        match self.app_data.config_route.del_config(req).await {
+        let result = match NamespaceUtils::check_config_publish(
+            &self.app_data.config_addr,
+            &req.config_key.tenant,
+        )
+        .await
+        {
+            Ok(_) => self.app_data.config_route.del_config(req).await,
+            Err(err) => Err(err),
+        };
+        match result {
             Ok(_res) => {
                 let mut response = BaseResponse::build_success_response();
                 response.request_id = request.request_id;
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
+use crate::audit::core::AuditLogReq;
+use crate::audit::model::{AuditLogDo, AuditLogUtils, AuditRequestParam};
 use crate::common::appdata::AppShareData;
+use crate::common::model::privilege::NamespaceGroupParam;
 
 use self::{
     config_change_batch_listen::ConfigChangeBatchListenRequestHandler,
@@ -13,7 +16,7 @@ use self::{
 };
 
 use super::{
-    api_model::{BaseResponse, ServerCheckResponse, SUCCESS_CODE},
+    api_model::{BaseResponse, ServerCheckResponse, ERROR_CODE, SUCCESS_CODE},
     nacos_proto::Payload,
     HandlerResult, PayloadHandler, PayloadUtils, RequestMeta,
 };
@@ -21,6 +24,7 @@ use crate::grpc::handler::raft_append::RaftAppendRequestHandler;
 use crate::grpc::handler::raft_snapshot::RaftSnapshotRequestHandler;
 use crate::grpc::handler::raft_vote::RaftVoteRequestHandler;
 use async_trait::async_trait;
+use serde::Deserialize;
 
 pub mod config_change_batch_listen;
 pub mod config_publish;
@@ -60,6 +64,13 @@ pub(crate) const SUBSCRIBE_SERVICE_REQUEST: &str = "SubscribeServiceRequest";
 pub(crate) const SERVICE_QUERY_REQUEST: &str = "ServiceQueryRequest";
 pub(crate) const SERVICE_LIST_REQUEST: &str = "ServiceListRequest";
 
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchListenPrivilegeParam {
+    #[serde(default)]
+    config_listen_contexts: Vec<NamespaceGroupParam>,
+}
+
 pub struct InvokerHandler {
     app: Arc<AppShareData>,
     handlers: Vec<(String, Box<dyn PayloadHandler + Send + Sync + 'static>)>,
@@ -123,6 +134,151 @@ impl InvokerHandler {
             || NAMING_ROUTE_REQUEST.eq(t)
     }
 
+    fn is_write_request(&self, t: &str) -> bool {
+        CONFIG_PUBLISH_REQUEST.eq(t)
+            || CONFIG_REMOVE_REQUEST.eq(t)
+            || INSTANCE_REQUEST.eq(t)
+            || BATCH_INSTANCE_REQUEST.eq(t)
+    }
+
+    ///
+    /// 记录审计日志的写请求
+    fn is_audit_request(&self, t: &str) -> bool {
+        CONFIG_PUBLISH_REQUEST.eq(t) || CONFIG_REMOVE_REQUEST.eq(t) || INSTANCE_REQUEST.eq(t)
+    }
+
+    fn build_audit_log(
+        &self,
+        url: &str,
+        payload: &Payload,
+        request_meta: &RequestMeta,
+    ) -> Option<AuditLogDo> {
+        if !self.is_audit_request(url) {
+            return None;
+        }
+        if self.app.sys_config.openapi_enable_auth && request_meta.token_session.is_none() {
+            AuditLogUtils::log_unauthorized(&request_meta.client_ip, "GRPC", url);
+            return None;
+        }
+        let body = payload
+            .body
+            .as_ref()
+            .map(|e| e.value.as_slice())
+            .unwrap_or_default();
+        let param = serde_json::from_slice::<AuditRequestParam>(body).unwrap_or_default();
+        let op_user = request_meta
+            .token_session
+            .as_ref()
+            .map(|e| e.username.clone());
+        Some(AuditLogUtils::build_grpc_log(
+            op_user,
+            Arc::new(request_meta.client_ip.clone()),
+            url,
+            &param,
+        ))
+    }
+
+    ///
+    /// 审计日志状态:处理成功为200,否则为响应中的错误码
+    fn get_audit_status(result: &anyhow::Result<HandlerResult>) -> u32 {
+        let result = match result {
+            Ok(v) => v,
+            Err(_) => return ERROR_CODE as u32,
+        };
+        let body = result
+            .payload
+            .body
+            .as_ref()
+            .map(|e| e.value.as_slice())
+            .unwrap_or_default();
+        match serde_json::from_slice::<BaseResponse>(body) {
+            Ok(response) if response.result_code == SUCCESS_CODE => SUCCESS_CODE as u32,
+            Ok(response) => response.error_code as u32,
+            Err(_) => ERROR_CODE as u32,
+        }
+    }
+
+    async fn do_handle(
+        &self,
+        url: &str,
+        request_payload: Payload,
+        request_meta: RequestMeta,
+    ) -> anyhow::Result<HandlerResult> {
+        if self.app.sys_config.openapi_enable_auth
+            && !self.ignore_auth(url)
+            && request_meta.token_session.is_none()
+        {
+            //开启鉴权，但取不到用户会话信息
+            return Ok(HandlerResult::error(403u16, "unknown user!".to_string()));
+        } else if !self.app.sys_config.cluster_token.is_empty()
+            && self.is_cluster_request(url)
+            && !request_meta.cluster_token_is_valid
+        {
+            //集群请求key校验不通过
+            return Ok(HandlerResult::error(
+                500u16,
+                "request cluster token is invalid".to_string(),
+            ));
+        } else if !self.check_privilege(url, &request_payload, &request_meta) {
+            return Ok(HandlerResult::error(
+                403u16,
+                "no namespace privilege!".to_string(),
+            ));
+        }
+        //println!("InvokerHandler type:{}",url);
+        if let Some(handler) = self.match_handler(url) {
+            return handler.handle(request_payload, request_meta).await;
+        }
+        log::warn!("InvokerHandler not fund handler,type:{}", url);
+        Ok(HandlerResult::error(
+            302u16,
+            format!("{} RequestHandler Not Found", url),
+        ))
+    }
+
+    fn is_read_request(&self, t: &str) -> bool {
+        CONFIG_QUERY_REQUEST.eq(t)
+            || SUBSCRIBE_SERVICE_REQUEST.eq(t)
+            || SERVICE_QUERY_REQUEST.eq(t)
+            || SERVICE_LIST_REQUEST.eq(t)
+    }
+
+    ///
+    /// 校验用户对请求中命名空间与分组的权限
+    fn check_privilege(&self, url: &str, payload: &Payload, request_meta: &RequestMeta) -> bool {
+        let privilege = match request_meta
+            .token_session
+            .as_ref()
+            .and_then(|e| e.namespace_privilege.as_ref())
+        {
+            Some(v) => v,
+            None => return true,
+        };
+        let body = payload
+            .body
+            .as_ref()
+            .map(|e| e.value.as_slice())
+            .unwrap_or_default();
+        if CONFIG_BATCH_LISTEN_REQUEST.eq(url) {
+            return match serde_json::from_slice::<BatchListenPrivilegeParam>(body) {
+                Ok(param) => param
+                    .config_listen_contexts
+                    .iter()
+                    .all(|e| e.check(privilege, false)),
+                Err(_) => false,
+            };
+        }
+        let write = self.is_write_request(url);
+        if !write && !self.is_read_request(url) {
+            return true;
+        }
+        match serde_json::from_slice::<NamespaceGroupParam>(body) {
+            Ok(param) if SERVICE_LIST_REQUEST.eq(url) => param.check_list(privilege, write),
+            Ok(param) => param.check(privilege, write),
+            Err(_) => false,
+        }
+    }
+
     pub fn add_raft_handler(&mut self, app_data: &Arc<AppShareData>) {
         self.add_handler(
             RAFT_APPEND_REQUEST,
@@ -208,31 +364,16 @@ impl PayloadHandler for InvokerHandler {
                     serde_json::to_string(&response)?,
                 )));
             }
-            if self.app.sys_config.openapi_enable_auth
-                && !self.ignore_auth(url)
-                && request_meta.token_session.is_none()
-            {
-                //开启鉴权，但取不到用户会话信息
-                return Ok(HandlerResult::error(403u16, "unknown user!".to_string()));
-            } else if !self.app.sys_config.cluster_token.is_empty()
-                && self.is_cluster_request(url)
-                && !request_meta.cluster_token_is_valid
-            {
-                //集群请求key校验不通过
-                return Ok(HandlerResult::error(
-                    500u16,
-                    "request cluster token is invalid".to_string(),
-                ));
-            }
-            //println!("InvokerHandler type:{}",url);
-            if let Some(handler) = self.match_handler(url) {
-                return handler.handle(request_payload, request_meta).await;
+            let url = url.to_owned();
+            let audit_log = self.build_audit_log(&url, &request_payload, &request_meta);
+            let result = self.do_handle(&url, request_payload, request_meta).await;
+            if let Some(mut audit_log) = audit_log {
+                audit_log.status = Self::get_audit_status(&result);
+                self.app
+                    .audit_log_manager
+                    .do_send(AuditLogReq::Add(audit_log));
             }
-            log::warn!("InvokerHandler not fund handler,type:{}", url);
-            return Ok(HandlerResult::error(
-                302u16,
-                format!("{} RequestHandler Not Found", url),
-            ));
+            return result;
         }
         Ok(HandlerResult::error(302u16, "empty type url".to_owned()))
     }
@@ -109,24 +109,42 @@ impl PayloadHandler for InstanceRequestHandler {
             }
         }
         let instance = Self::convert_to_instance(request, request_meta.connection_id)?;
-        let cmd = if is_de_register {
-            NamingCmd::Delete(instance)
+        let update_tag = InstanceUpdateTag {
+            weight: instance.weight != 1.0f32,
+            metadata: true,
+            enabled: !instance.enabled,
+            ephemeral: false,
+            from_update: false,
+        };
+        let res = if !instance.ephemeral {
+            //持久化实例不与连接绑定,通过raft写入
+            if is_de_register {
+                self.app_data.naming_route.delete_instance(instance).await
+            } else {
+                self.app_data
+                    .naming_route
+                    .update_instance(instance, Some(update_tag))
+                    .await
+            }
         } else {
-            let update_tag = InstanceUpdateTag {
-                weight: instance.weight != 1.0f32,
-                metadata: true,
-                enabled: !instance.enabled,
-                ephemeral: false,
-                from_update: false,
+            let cmd = if is_de_register {
+                NamingCmd::Delete(instance)
+            } else {
+                NamingCmd::Update(instance, Some(update_tag))
             };
-            NamingCmd::Update(instance, Some(update_tag))
+            self.app_data
+                .naming_addr
+                .send(cmd)
+                .await
+                .map(|_| ())
+                .map_err(anyhow::Error::from)
         };
         let mut response = InstanceResponse {
             request_id,
             ..Default::default()
         };
-        match self.app_data.naming_addr.send(cmd).await {
-            Ok(_res) => {
+        match res {
+            Ok(_) => {
                 //let res:ConfigResult = res.unwrap();
                 response.result_code = SUCCESS_CODE;
                 if is_de_register {
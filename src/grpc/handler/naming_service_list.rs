@@ -37,7 +37,7 @@ impl PayloadHandler for ServiceListRequestHandler {
     async fn handle(
         &self,
         request_payload: crate::grpc::nacos_proto::Payload,
-        _request_meta: crate::grpc::RequestMeta,
+        request_meta: crate::grpc::RequestMeta,
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ServiceListRequest = serde_json::from_slice(&body_vec)?;
@@ -57,8 +57,15 @@ impl PayloadHandler for ServiceListRequestHandler {
             &request.group_name.unwrap_or_default(),
             &request.service_name.unwrap_or_default(),
         );
-        let cmd =
-            NamingCmd::QueryServicePage(key, request.page_size as usize, request.page_no as usize);
+        let privilege = request_meta
+            .token_session
+            .and_then(|e| e.namespace_privilege.clone());
+        let cmd = NamingCmd::QueryServicePage(
+            key,
+            request.page_size as usize,
+            request.page_no as usize,
+            privilege,
+        );
         match self.app_data.naming_addr.send(cmd).await {
             Ok(res) => {
                 let result: NamingResult = res.unwrap();
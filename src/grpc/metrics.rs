@@ -7,6 +7,7 @@ impl Handler<MetricsQuery> for BiStreamManage {
     type Result = anyhow::Result<Vec<MetricsItem>>;
 
     fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let push_counter = self.config_push_tracker.take_counter();
         let list = vec![
             MetricsItem {
                 metrics_type: MetricsKey::GrpcConnSize,
@@ -20,6 +21,26 @@ impl Handler<MetricsQuery> for BiStreamManage {
                 metrics_type: MetricsKey::GrpcConnResponseTimeoutSetItemSize,
                 record: MetricsRecord::Gauge(self.response_time_set.item_size() as f32),
             },
+            MetricsItem {
+                metrics_type: MetricsKey::GrpcConfigPushPendingSize,
+                record: MetricsRecord::Gauge(self.config_push_tracker.pending_size() as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::GrpcConfigPushCount,
+                record: MetricsRecord::CounterInc(push_counter.push_count),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::GrpcConfigPushAckCount,
+                record: MetricsRecord::CounterInc(push_counter.ack_count),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::GrpcConfigPushRetryCount,
+                record: MetricsRecord::CounterInc(push_counter.retry_count),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::GrpcConfigPushFailCount,
+                record: MetricsRecord::CounterInc(push_counter.fail_count),
+            },
         ];
         Ok(list)
     }
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 pub mod api_model;
 pub mod bistream_conn;
 pub mod bistream_manage;
+pub mod envoy_xds_proto;
 pub mod handler;
 pub mod metrics;
 pub mod nacos_proto;
@@ -22,6 +23,17 @@ pub struct RequestMeta {
     pub cluster_token_is_valid: bool,
 }
 
+impl RequestMeta {
+    ///
+    /// 是否可以读取cipher-配置的明文;未开启鉴权(没有会话)时不限制
+    pub fn can_decrypt_config(&self) -> bool {
+        self.token_session
+            .as_ref()
+            .map(|e| e.config_decrypt)
+            .unwrap_or(true)
+    }
+}
+
 pub struct HandlerResult {
     pub success: bool,
     pub payload: nacos_proto::Payload,
@@ -2,18 +2,24 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 use crate::common::appdata::AppShareData;
-use crate::common::constant::{ACCESS_TOKEN_HEADER, AUTHORIZATION_HEADER, EMPTY_ARC_STRING};
+use crate::common::constant::{
+    ACCESS_TOKEN_HEADER, AUTHORIZATION_HEADER, EMPTY_ARC_STRING, SIGN_TIMESTAMP_HEADER,
+    SPAS_ACCESS_KEY_HEADER, SPAS_SIGNATURE_HEADER,
+};
+use crate::common::model::privilege::NamespaceGroupParam;
 use crate::common::model::TokenSession;
 use actix::prelude::*;
 //use tokio_stream::StreamExt;
 
+use crate::grpc::api_model::{BaseResponse, ERROR_CODE, SUCCESS_CODE};
 use crate::grpc::bistream_manage::BiStreamManageResult;
 use crate::grpc::nacos_proto::{request_server, Payload};
 use crate::grpc::{PayloadHandler, PayloadUtils, RequestMeta};
-use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{LabelMetricsItem, MetricsItem, MetricsRecord, MetricsRequest};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
+use crate::user::access_key::{AccessKeyReq, AccessKeyResult, AccessKeySignInfo};
 
 use super::bistream_conn::BiStreamConn;
 use super::bistream_manage::{BiStreamManage, BiStreamManageCmd};
@@ -54,6 +60,15 @@ impl RequestServerImpl {
             {
                 request_meta.token_session = Some(session);
             }
+        } else if let Some(sign_info) = self.get_access_key_sign_info(payload) {
+            if let Ok(Ok(AccessKeyResult::Session(session))) = self
+                .app
+                .access_key_manager
+                .send(AccessKeyReq::CheckSign(sign_info))
+                .await
+            {
+                request_meta.token_session = session;
+            }
         } else if !self.app.sys_config.cluster_token.is_empty() {
             if let Some(Some(token)) = payload
                 .metadata
@@ -67,19 +82,62 @@ impl RequestServerImpl {
         Ok(())
     }
 
-    fn record_req_metrics(&self, duration: f64, _success: bool) {
+    ///
+    /// 开启鉴权时从请求头中获取访问密钥签名信息
+    fn get_access_key_sign_info(&self, payload: &Payload) -> Option<AccessKeySignInfo> {
+        if !self.app.sys_config.openapi_enable_auth {
+            return None;
+        }
+        let headers = &payload.metadata.as_ref()?.headers;
+        let access_key = headers.get(SPAS_ACCESS_KEY_HEADER)?;
+        let resource = payload
+            .body
+            .as_ref()
+            .and_then(|body| serde_json::from_slice::<NamespaceGroupParam>(&body.value).ok())
+            .and_then(|param| AccessKeySignInfo::build_resource(&param));
+        Some(AccessKeySignInfo {
+            access_key: Arc::new(access_key.to_owned()),
+            timestamp: headers
+                .get(SIGN_TIMESTAMP_HEADER)
+                .cloned()
+                .unwrap_or_default(),
+            signature: headers
+                .get(SPAS_SIGNATURE_HEADER)
+                .cloned()
+                .unwrap_or_default(),
+            resource,
+        })
+    }
+
+    fn record_req_metrics(&self, request_type: &str, duration: f64, code: u16) {
+        let rt = duration as f32 * 1000f32;
         self.app
             .metrics_manager
             .do_send(MetricsRequest::BatchRecord(vec![
                 MetricsItem::new(
                     MetricsKey::GrpcRequestHandleRtHistogram,
-                    MetricsRecord::HistogramRecord(duration as f32 * 1000f32),
+                    MetricsRecord::HistogramRecord(rt),
                 ),
                 MetricsItem::new(
                     MetricsKey::GrpcRequestTotalCount,
                     MetricsRecord::CounterInc(1),
                 ),
             ]));
+        let request_type_label = Label::new("request_type", request_type.to_owned());
+        self.app
+            .metrics_manager
+            .do_send(MetricsRequest::LabelBatchRecord(vec![
+                LabelMetricsItem::new(
+                    MetricsKey::GrpcRequestRtHistogram,
+                    vec![request_type_label.clone()],
+                    MetricsRecord::HistogramRecord(rt),
+                ),
+                LabelMetricsItem::new(
+                    MetricsKey::GrpcRequestCount,
+                    vec![request_type_label, Label::new("code", code.to_string())],
+                    MetricsRecord::CounterInc(1),
+                ),
+            ]));
     }
 }
 
@@ -99,12 +157,12 @@ impl request_server::Request for RequestServerImpl {
         };
         //debug
         //log::info!( "client request: {}", PayloadUtils::get_payload_string(&payload));
-        let request_type = PayloadUtils::get_payload_type(&payload).unwrap();
+        let request_type = PayloadUtils::get_payload_type(&payload).unwrap().to_owned();
         let request_log_info = format!(
             "|grpc|client_request|{}|{}",
             &request_meta.connection_id, &request_type
         );
-        let ignore_active_err = self.invoker.ignore_active_err(request_type);
+        let ignore_active_err = self.invoker.ignore_active_err(&request_type);
         //self.bistream_manage_addr.do_send(BiStreamManageCmd::ActiveClinet(request_meta.connection_id.clone()));
         let active_result = self
             .app
@@ -128,7 +186,7 @@ impl request_server::Request for RequestServerImpl {
                                 .unwrap_or_default()
                                 .as_secs_f64();
                             log::error!("{}|err|{}|{}", request_log_info, duration, &err_msg);
-                            self.record_req_metrics(duration, false);
+                            self.record_req_metrics(&request_type, duration, 301);
                             return Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                                 301, err_msg,
                             )));
@@ -146,7 +204,7 @@ impl request_server::Request for RequestServerImpl {
                         .unwrap_or_default()
                         .as_secs_f64();
                     log::error!("{}|err|{}|{}", request_log_info, duration, &err_msg);
-                    self.record_req_metrics(duration, false);
+                    self.record_req_metrics(&request_type, duration, 301);
                     return Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                         301, err_msg,
                     )));
@@ -173,14 +231,21 @@ impl request_server::Request for RequestServerImpl {
                         ""
                     };
                     log::error!("{}|err|{}|{}", request_log_info, duration, msg);
-                    self.record_req_metrics(duration, false);
+                    let code = res
+                        .payload
+                        .body
+                        .as_ref()
+                        .and_then(|e| serde_json::from_slice::<BaseResponse>(&e.value).ok())
+                        .map(|e| e.error_code)
+                        .unwrap_or(ERROR_CODE);
+                    self.record_req_metrics(&request_type, duration, code);
                 } else if duration < 1f64 {
                     log::info!("{}|ok|{}", request_log_info, duration);
-                    self.record_req_metrics(duration, true);
+                    self.record_req_metrics(&request_type, duration, SUCCESS_CODE);
                 } else {
                     //slow request handle
                     log::warn!("{}|ok|{}", request_log_info, duration);
-                    self.record_req_metrics(duration, true);
+                    self.record_req_metrics(&request_type, duration, SUCCESS_CODE);
                 }
                 Ok(tonic::Response::new(res.payload))
             }
@@ -188,7 +253,7 @@ impl request_server::Request for RequestServerImpl {
                 //Err(tonic::Status::aborted(e.to_string()))
                 //log::error!("request_server handler error:{:?}",e);
                 log::error!("{}|err|{}|{}", request_log_info, duration, e);
-                self.record_req_metrics(duration, false);
+                self.record_req_metrics(&request_type, duration, ERROR_CODE);
                 Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                     500u16,
                     e.to_string(),
@@ -235,7 +300,7 @@ impl BiRequestStream for BiRequestStreamServerImpl {
     }
 }
 
-async fn get_user_session(
+pub(crate) async fn get_user_session(
     cache_manager: &Addr<CacheManager>,
     req: CacheManagerReq,
 ) -> anyhow::Result<Option<Arc<TokenSession>>> {
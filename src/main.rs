@@ -8,12 +8,15 @@ use rnacos::common::AppSysConfig;
 use rnacos::config::core::{ConfigActor, ConfigCmd};
 use rnacos::console::middle::login_middle::CheckLogin;
 use rnacos::grpc::bistream_manage::BiStreamManage;
+use rnacos::grpc::envoy_xds_proto::aggregated_discovery_service_server::AggregatedDiscoveryServiceServer;
 use rnacos::grpc::handler::InvokerHandler;
 use rnacos::grpc::nacos_proto::bi_request_stream_server::BiRequestStreamServer;
 use rnacos::grpc::nacos_proto::request_server::RequestServer;
 use rnacos::grpc::server::BiRequestStreamServerImpl;
 use rnacos::grpc::PayloadUtils;
 use rnacos::naming::core::{NamingCmd, NamingResult};
+use rnacos::naming::dns::server::{run_dns_server, DnsResolver};
+use rnacos::naming::xds::server::AggregatedDiscoveryServiceImpl;
 use rnacos::raft::cluster::model::RouterRequest;
 use rnacos::raft::cluster::route::{ConfigRoute, RaftAddrRouter};
 use rnacos::raft::network::core::RaftRouter;
@@ -85,14 +88,38 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let request_server = RequestServerImpl::new(grpc_app_data.clone(), invoker);
         let bi_request_stream_server =
             BiRequestStreamServerImpl::new(grpc_app_data.bi_stream_manage.clone());
+        let xds_server = if grpc_app_data.sys_config.xds_enable {
+            log::info!("xds ads server enabled");
+            Some(AggregatedDiscoveryServiceServer::new(
+                AggregatedDiscoveryServiceImpl::new(grpc_app_data.clone()),
+            ))
+        } else {
+            None
+        };
         Server::builder()
             .add_service(RequestServer::new(request_server))
             .add_service(BiRequestStreamServer::new(bi_request_stream_server))
+            .add_optional_service(xds_server)
             .serve(addr)
             .await
             .unwrap();
     });
 
+    if sys_config.dns_port > 0 {
+        let dns_addr = sys_config.get_dns_addr();
+        log::info!("dns server addr:{}", &dns_addr);
+        let resolver = Arc::new(DnsResolver::new(
+            app_data.naming_addr.clone(),
+            &sys_config.dns_domain,
+            sys_config.dns_ttl,
+        ));
+        tokio::spawn(async move {
+            if let Err(err) = run_dns_server(dns_addr, resolver).await {
+                log::error!("dns server start error,{}", err);
+            }
+        });
+    }
+
     if sys_config.http_console_port > 0 {
         let app_console_data = app_data.clone();
 
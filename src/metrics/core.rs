@@ -4,21 +4,43 @@ use crate::grpc::bistream_manage::BiStreamManage;
 use crate::metrics::counter::CounterManager;
 use crate::metrics::gauge::GaugeManager;
 use crate::metrics::histogram::HistogramManager;
+use crate::metrics::label::LabelMetricsManager;
+use crate::metrics::metrics_key::Label;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{
-    MetricsItem, MetricsQuery, MetricsRecord, MetricsRequest, MetricsResponse,
+    LabelMetricsItem, LabelMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord, MetricsRequest,
+    MetricsResponse,
 };
 use crate::metrics::summary::SummaryManager;
 use crate::metrics::timeline::core::MetricsTimelineManager;
 use crate::metrics::timeline::model::{MetricsSnapshot, TimelineGroupType};
+use crate::naming::cluster::node_manage::InnerNodeManage;
 use crate::naming::core::NamingActor;
 use crate::now_millis;
+use crate::raft::filestore::raftlog::RaftLogManager;
+use crate::raft::filestore::raftsnapshot::RaftSnapshotManager;
+use crate::raft::network::core::RaftReplicationTracker;
+use crate::raft::NacosRaft;
 use actix::prelude::*;
+use async_raft_ext::{RaftMetrics, State};
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use bytes::BytesMut;
 use std::sync::Arc;
 use std::time::Duration;
 use sysinfo::{Pid, System};
+use tokio::sync::watch;
+
+///
+/// 定时采集指标的来源
+#[derive(Debug, Clone, Default)]
+struct PeekMetricsSource {
+    naming_actor: Option<Addr<NamingActor>>,
+    config_actor: Option<Addr<ConfigActor>>,
+    bi_stream_manage: Option<Addr<BiStreamManage>>,
+    naming_node_manage: Option<Addr<InnerNodeManage>>,
+    raft_log_manager: Option<Addr<RaftLogManager>>,
+    raft_snapshot_manager: Option<Addr<RaftSnapshotManager>>,
+}
 
 #[bean(inject)]
 #[derive(Debug)]
@@ -27,10 +49,12 @@ pub struct MetricsManager {
     gauge_manager: GaugeManager,
     histogram_manager: HistogramManager,
     summary_manager: SummaryManager,
+    label_manager: LabelMetricsManager,
     summary_key_config: Vec<(MetricsKey, MetricsKey)>,
-    naming_actor: Option<Addr<NamingActor>>,
-    config_actor: Option<Addr<ConfigActor>>,
-    bi_stream_manage: Option<Addr<BiStreamManage>>,
+    peek_source: PeekMetricsSource,
+    raft_metrics: Option<watch::Receiver<RaftMetrics>>,
+    replication_tracker: Option<Arc<RaftReplicationTracker>>,
+    last_raft_leader: Option<u64>,
     metrics_timeline_manager: MetricsTimelineManager,
     system: System,
     current_process_id: u32,
@@ -63,10 +87,12 @@ impl MetricsManager {
             gauge_manager,
             histogram_manager: Default::default(),
             summary_manager: Default::default(),
+            label_manager: Default::default(),
             summary_key_config: Default::default(),
-            naming_actor: None,
-            config_actor: None,
-            bi_stream_manage: None,
+            peek_source: Default::default(),
+            raft_metrics: None,
+            replication_tracker: None,
+            last_raft_leader: None,
             metrics_timeline_manager: MetricsTimelineManager::new(),
             system,
             current_process_id,
@@ -108,6 +134,20 @@ impl MetricsManager {
             &[0.5f32, 0.6f32, 0.7f32, 0.8f32, 0.9f32, 0.95f32, 1f32],
         );
 
+        // 单位毫秒ms
+        self.label_manager.init_histogram(
+            MetricsKey::GrpcRequestRtHistogram,
+            &[
+                0.25f32, 0.5f32, 1f32, 3f32, 5f32, 10f32, 25f32, 50f32, 100f32, 300f32, 500f32,
+            ],
+        );
+        self.label_manager.init_histogram(
+            MetricsKey::HttpRequestRtHistogram,
+            &[
+                0.25f32, 0.5f32, 1f32, 3f32, 5f32, 10f32, 25f32, 50f32, 100f32, 300f32, 500f32,
+            ],
+        );
+
         //summary from histogram
         self.summary_key_config.push((
             MetricsKey::HttpRequestHandleRtSummary,
@@ -129,31 +169,63 @@ impl MetricsManager {
     }
 
     async fn do_peek_metrics(
-        naming_actor: Option<Addr<NamingActor>>,
-        config_actor: Option<Addr<ConfigActor>>,
-        bi_stream_manage: Option<Addr<BiStreamManage>>,
-    ) -> anyhow::Result<Vec<MetricsItem>> {
+        source: PeekMetricsSource,
+    ) -> anyhow::Result<(Vec<MetricsItem>, Vec<LabelMetricsItem>)> {
         let mut list = vec![];
+        let mut label_list = vec![];
+        let PeekMetricsSource {
+            naming_actor,
+            config_actor,
+            bi_stream_manage,
+            naming_node_manage,
+            raft_log_manager,
+            raft_snapshot_manager,
+        } = source;
         if let Some(naming_actor) = naming_actor {
             let mut t = naming_actor.send(MetricsQuery).await??;
             list.append(&mut t);
+            let mut t = naming_actor.send(LabelMetricsQuery).await??;
+            label_list.append(&mut t);
         }
         if let Some(config_actor) = config_actor {
             let mut t = config_actor.send(MetricsQuery).await??;
             list.append(&mut t);
+            let mut t = config_actor.send(LabelMetricsQuery).await??;
+            label_list.append(&mut t);
         }
         if let Some(bi_stream_manage) = bi_stream_manage {
             let mut t = bi_stream_manage.send(MetricsQuery).await??;
             list.append(&mut t);
         }
-        Ok(list)
+        if let Some(naming_node_manage) = naming_node_manage {
+            let mut t = naming_node_manage.send(MetricsQuery).await??;
+            list.append(&mut t);
+            let mut t = naming_node_manage.send(LabelMetricsQuery).await??;
+            label_list.append(&mut t);
+        }
+        if let Some(raft_log_manager) = raft_log_manager {
+            let mut t = raft_log_manager.send(MetricsQuery).await??;
+            list.append(&mut t);
+        }
+        if let Some(raft_snapshot_manager) = raft_snapshot_manager {
+            let mut t = raft_snapshot_manager.send(MetricsQuery).await??;
+            list.append(&mut t);
+        }
+        Ok((list, label_list))
     }
 
-    fn update_peek_metrics(&mut self, r: anyhow::Result<Vec<MetricsItem>>) {
-        if let Ok(list) = r {
+    fn update_peek_metrics(
+        &mut self,
+        r: anyhow::Result<(Vec<MetricsItem>, Vec<LabelMetricsItem>)>,
+    ) {
+        if let Ok((list, label_list)) = r {
             for item in list {
                 self.update_item_record(item);
             }
+            self.label_manager.clear_gauge();
+            for item in label_list {
+                self.label_manager.record(item);
+            }
         }
     }
 
@@ -204,6 +276,63 @@ impl MetricsManager {
         self.last_collect_time = now_millis();
     }
 
+    fn load_raft_metrics(&mut self) {
+        let raft_metrics = if let Some(raft_metrics) = &self.raft_metrics {
+            raft_metrics.borrow().clone()
+        } else {
+            return;
+        };
+        if self.last_raft_leader.is_some() && self.last_raft_leader != raft_metrics.current_leader {
+            self.counter_manager
+                .increment(MetricsKey::RaftLeaderChangeCount, 1);
+            if let Some(replication_tracker) = &self.replication_tracker {
+                replication_tracker.clear();
+            }
+        }
+        self.last_raft_leader = raft_metrics.current_leader;
+        let is_leader = raft_metrics.state == State::Leader;
+        self.gauge_manager.set(
+            MetricsKey::RaftCurrentTerm,
+            raft_metrics.current_term as f32,
+        );
+        self.gauge_manager.set(
+            MetricsKey::RaftIsLeader,
+            if is_leader { 1f32 } else { 0f32 },
+        );
+        self.gauge_manager.set(
+            MetricsKey::RaftLastLogIndex,
+            raft_metrics.last_log_index as f32,
+        );
+        self.gauge_manager.set(
+            MetricsKey::RaftLastAppliedIndex,
+            raft_metrics.last_applied as f32,
+        );
+        self.gauge_manager.set(
+            MetricsKey::RaftApplyLag,
+            raft_metrics
+                .last_log_index
+                .saturating_sub(raft_metrics.last_applied) as f32,
+        );
+        let members = raft_metrics.membership_config.all_nodes();
+        self.gauge_manager
+            .set(MetricsKey::RaftMembershipSize, members.len() as f32);
+        //只有leader记录各follower的同步延迟
+        if let (true, Some(replication_tracker)) = (is_leader, &self.replication_tracker) {
+            for node_id in members {
+                if node_id == raft_metrics.id {
+                    continue;
+                }
+                self.label_manager.record(LabelMetricsItem::new(
+                    MetricsKey::RaftReplicationLag,
+                    vec![Label::new("node_id", node_id.to_string())],
+                    MetricsRecord::Gauge(
+                        replication_tracker.get_lag(node_id, raft_metrics.last_log_index) as f32,
+                    ),
+                ));
+            }
+        }
+    }
+
     fn print_sys_metrics(&self) {
         let cpu_usage = self
             .gauge_manager
@@ -231,6 +360,7 @@ impl MetricsManager {
     fn after_peek_metrics(&mut self) {
         self.reset_summary();
         self.load_sys_metrics();
+        self.load_raft_metrics();
         self.print_metrics();
         let now = now_millis();
         self.record_timeline_snapshot(now, TimelineGroupType::Least);
@@ -239,10 +369,8 @@ impl MetricsManager {
     }
 
     fn load_metrics(&mut self, ctx: &mut Context<Self>) {
-        let naming_actor = self.naming_actor.clone();
-        let config_actor = self.config_actor.clone();
-        let bi_stream_manage = self.bi_stream_manage.clone();
-        async move { Self::do_peek_metrics(naming_actor, config_actor, bi_stream_manage).await }
+        let source = self.peek_source.clone();
+        async move { Self::do_peek_metrics(source).await }
             .into_actor(self)
             .map(|r, act, ctx| {
                 //Self::log_metrics(&r);
@@ -287,6 +415,7 @@ impl MetricsManager {
         self.histogram_manager.export(&mut bytes_mut)?;
         self.reset_summary();
         self.summary_manager.export(&mut bytes_mut)?;
+        self.label_manager.export(&mut bytes_mut)?;
         Ok(String::from_utf8(bytes_mut.to_vec())?)
     }
 }
@@ -307,9 +436,17 @@ impl Inject for MetricsManager {
         _factory: BeanFactory,
         ctx: &mut Self::Context,
     ) {
-        self.naming_actor = factory_data.get_actor();
-        self.config_actor = factory_data.get_actor();
-        self.bi_stream_manage = factory_data.get_actor();
+        self.peek_source = PeekMetricsSource {
+            naming_actor: factory_data.get_actor(),
+            config_actor: factory_data.get_actor(),
+            bi_stream_manage: factory_data.get_actor(),
+            naming_node_manage: factory_data.get_actor(),
+            raft_log_manager: factory_data.get_actor(),
+            raft_snapshot_manager: factory_data.get_actor(),
+        };
+        let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
+        self.raft_metrics = raft.map(|e| e.metrics());
+        self.replication_tracker = factory_data.get_bean();
         let sys_config: Option<Arc<AppSysConfig>> = factory_data.get_bean();
         if let Some(sys_config) = sys_config {
             self.metrics_enable = sys_config.metrics_enable;
@@ -348,6 +485,12 @@ impl Handler<MetricsRequest> for MetricsManager {
                 }
                 Ok(MetricsResponse::None)
             }
+            MetricsRequest::LabelBatchRecord(items) => {
+                for item in items {
+                    self.label_manager.record(item);
+                }
+                Ok(MetricsResponse::None)
+            }
             MetricsRequest::Export => {
                 let v = self.export()?;
                 Ok(MetricsResponse::ExportInfo(v))
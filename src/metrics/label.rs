@@ -0,0 +1,233 @@
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{
+    CounterValue, GaugeValue, HistogramValue, LabelMetricsItem, MetricsRecord, MetricsType,
+};
+use bytes::BytesMut;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// 单个指标最多保留的标签组合数,避免标签值过多导致内存膨胀
+const MAX_SERIES_SIZE: usize = 2000;
+
+type LabelMap<V> = HashMap<MetricsKey, BTreeMap<Vec<Label>, V>>;
+
+///
+/// 带标签的指标,同一个指标按标签组合区分多条时间序列
+#[derive(Default, Debug)]
+pub struct LabelMetricsManager {
+    counter_map: LabelMap<CounterValue>,
+    gauge_map: LabelMap<GaugeValue>,
+    histogram_map: LabelMap<HistogramValue>,
+    histogram_bounds: HashMap<MetricsKey, Vec<f32>>,
+}
+
+impl LabelMetricsManager {
+    pub fn init_histogram(&mut self, key: MetricsKey, bounds: &[f32]) {
+        self.histogram_bounds.insert(key, bounds.to_vec());
+    }
+
+    pub fn record(&mut self, item: LabelMetricsItem) {
+        let key = item.metrics_type;
+        let labels = item.labels;
+        match item.record {
+            MetricsRecord::CounterInc(v) => {
+                if let Some(value) =
+                    Self::get_or_insert(&mut self.counter_map, key, labels, Default::default)
+                {
+                    value.increment(v);
+                }
+            }
+            MetricsRecord::Gauge(v) => {
+                if let Some(value) =
+                    Self::get_or_insert(&mut self.gauge_map, key, labels, Default::default)
+                {
+                    value.set(v);
+                }
+            }
+            MetricsRecord::HistogramRecord(v) => {
+                if let Some(value) = self.get_or_insert_histogram(key, labels) {
+                    value.record(v);
+                }
+            }
+            MetricsRecord::HistogramRecords(batch_value) => {
+                if let Some(value) = self.get_or_insert_histogram(key, labels) {
+                    value.record_many(&batch_value);
+                }
+            }
+        }
+    }
+
+    ///
+    /// 周期采集的gauge每次重新设置,清除已不存在的标签组合
+    pub fn clear_gauge(&mut self) {
+        self.gauge_map.clear();
+    }
+
+    fn get_or_insert_histogram(
+        &mut self,
+        key: MetricsKey,
+        labels: Vec<Label>,
+    ) -> Option<&mut HistogramValue> {
+        let bounds = self.histogram_bounds.get(&key)?;
+        let value = HistogramValue::new(bounds)?;
+        Self::get_or_insert(&mut self.histogram_map, key, labels, || value)
+    }
+
+    fn get_or_insert<V>(
+        map: &mut LabelMap<V>,
+        key: MetricsKey,
+        labels: Vec<Label>,
+        f: impl FnOnce() -> V,
+    ) -> Option<&mut V> {
+        let series = map.entry(key).or_default();
+        let size = series.len();
+        match series.entry(labels) {
+            Entry::Occupied(e) => Some(e.into_mut()),
+            Entry::Vacant(e) => {
+                if size >= MAX_SERIES_SIZE {
+                    None
+                } else {
+                    Some(e.insert(f()))
+                }
+            }
+        }
+    }
+
+    pub fn export(&self, bytes_mut: &mut BytesMut) -> anyhow::Result<()> {
+        for (key, series) in &self.counter_map {
+            write_header(bytes_mut, key, MetricsType::Counter)?;
+            for (labels, value) in series {
+                writeln!(
+                    bytes_mut,
+                    "{}{} {}",
+                    key.get_key(),
+                    format_labels(labels, None),
+                    value.0
+                )?;
+            }
+        }
+        for (key, series) in &self.gauge_map {
+            write_header(bytes_mut, key, MetricsType::Gauge)?;
+            for (labels, value) in series {
+                writeln!(
+                    bytes_mut,
+                    "{}{} {:.3}",
+                    key.get_key(),
+                    format_labels(labels, None),
+                    value.0
+                )?;
+            }
+        }
+        for (key, series) in &self.histogram_map {
+            write_header(bytes_mut, key, MetricsType::Histogram)?;
+            let key_name = key.get_key();
+            for (labels, value) in series {
+                for (k, v) in value.buckets() {
+                    let le = k.to_string();
+                    writeln!(
+                        bytes_mut,
+                        "{}_bucket{} {}",
+                        key_name,
+                        format_labels(labels, Some(&le)),
+                        v
+                    )?;
+                }
+                writeln!(
+                    bytes_mut,
+                    "{}_bucket{} {}",
+                    key_name,
+                    format_labels(labels, Some("+Inf")),
+                    value.count
+                )?;
+                let label_str = format_labels(labels, None);
+                writeln!(bytes_mut, "{}_sum{} {:.3}", key_name, &label_str, value.sum)?;
+                writeln!(
+                    bytes_mut,
+                    "{}_count{} {}",
+                    key_name, &label_str, value.count
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_header(
+    bytes_mut: &mut BytesMut,
+    key: &MetricsKey,
+    metrics_type: MetricsType,
+) -> std::fmt::Result {
+    let key_name = key.get_key();
+    writeln!(
+        bytes_mut,
+        "# HELP {} {}\n# TYPE {} {}",
+        key_name,
+        key.get_describe(),
+        key_name,
+        metrics_type.get_name()
+    )
+}
+
+///
+/// 输出格式: {label_key="label_value",le="0.5"}
+fn format_labels(labels: &[Label], le: Option<&str>) -> String {
+    let mut items: Vec<String> = labels
+        .iter()
+        .map(|Label(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect();
+    if let Some(le) = le {
+        items.push(format!("le=\"{}\"", le));
+    }
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", items.join(","))
+    }
+}
+
+fn escape_label_value(v: &str) -> String {
+    v.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_metrics_export() {
+        let mut manager = LabelMetricsManager::default();
+        manager.init_histogram(MetricsKey::GrpcRequestRtHistogram, &[1f32, 10f32]);
+        let labels = vec![Label::new("request_type", "ConfigQueryRequest".to_owned())];
+        manager.record(LabelMetricsItem::new(
+            MetricsKey::GrpcRequestCount,
+            labels.clone(),
+            MetricsRecord::CounterInc(2),
+        ));
+        manager.record(LabelMetricsItem::new(
+            MetricsKey::GrpcRequestRtHistogram,
+            labels,
+            MetricsRecord::HistogramRecord(5f32),
+        ));
+        manager.record(LabelMetricsItem::new(
+            MetricsKey::NamespaceConfigSize,
+            vec![Label::new("namespace", "a\"b".to_owned())],
+            MetricsRecord::Gauge(3f32),
+        ));
+        let mut bytes_mut = BytesMut::new();
+        manager.export(&mut bytes_mut).unwrap();
+        let text = String::from_utf8(bytes_mut.to_vec()).unwrap();
+        assert!(text.contains("grpc_request_count{request_type=\"ConfigQueryRequest\"} 2"));
+        assert!(text.contains(
+            "grpc_request_rt_histogram_bucket{request_type=\"ConfigQueryRequest\",le=\"10\"} 1"
+        ));
+        assert!(text.contains("namespace_config_size{namespace=\"a\\\"b\"} 3.000"));
+        manager.clear_gauge();
+        let mut bytes_mut = BytesMut::new();
+        manager.export(&mut bytes_mut).unwrap();
+        let text = String::from_utf8(bytes_mut.to_vec()).unwrap();
+        assert!(!text.contains("namespace_config_size"));
+    }
+}
@@ -3,9 +3,15 @@ use std::collections::HashMap;
 //use crate::metrics::model::MetricsType;
 use lazy_static::lazy_static;
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash, PartialOrd, Ord)]
 pub struct Label(pub Cow<'static, str>, pub Cow<'static, str>);
 
+impl Label {
+    pub fn new(key: &'static str, value: String) -> Self {
+        Self(Cow::Borrowed(key), Cow::Owned(value))
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum MetricsKey {
     //app
@@ -49,6 +55,26 @@ pub enum MetricsKey {
     GrpcConnSize,
     GrpcConnActiveTimeoutSetItemSize,
     GrpcConnResponseTimeoutSetItemSize,
+    GrpcConfigPushPendingSize,
+    GrpcConfigPushCount,
+    GrpcConfigPushAckCount,
+    GrpcConfigPushRetryCount,
+    GrpcConfigPushFailCount,
+    //raft
+    RaftCurrentTerm,
+    RaftIsLeader,
+    RaftLeaderChangeCount,
+    RaftLastLogIndex,
+    RaftLastAppliedIndex,
+    RaftApplyLag,
+    RaftMembershipSize,
+    RaftLogFileCount,
+    RaftSnapshotBuildCount,
+    RaftSnapshotBuildRt,
+    RaftSnapshotFileSize,
+    //naming cluster
+    NamingClusterNodeSize,
+    NamingClusterValidNodeSize,
     //grpc request
     GrpcRequestHandleRtHistogram,
     GrpcRequestHandleRtSummary,
@@ -57,6 +83,16 @@ pub enum MetricsKey {
     HttpRequestHandleRtHistogram,
     HttpRequestHandleRtSummary,
     HttpRequestTotalCount,
+    //label metrics
+    GrpcRequestCount,
+    GrpcRequestRtHistogram,
+    HttpRequestCount,
+    HttpRequestRtHistogram,
+    NamespaceConfigSize,
+    NamespaceServiceSize,
+    NamespaceInstanceSize,
+    RaftReplicationLag,
+    NamingClusterNodeStatus,
 }
 
 lazy_static! {
@@ -102,6 +138,26 @@ lazy_static! {
         MetricsKey::GrpcConnSize,
         MetricsKey::GrpcConnActiveTimeoutSetItemSize,
         MetricsKey::GrpcConnResponseTimeoutSetItemSize,
+        MetricsKey::GrpcConfigPushPendingSize,
+        MetricsKey::GrpcConfigPushCount,
+        MetricsKey::GrpcConfigPushAckCount,
+        MetricsKey::GrpcConfigPushRetryCount,
+        MetricsKey::GrpcConfigPushFailCount,
+        //raft
+        MetricsKey::RaftCurrentTerm,
+        MetricsKey::RaftIsLeader,
+        MetricsKey::RaftLeaderChangeCount,
+        MetricsKey::RaftLastLogIndex,
+        MetricsKey::RaftLastAppliedIndex,
+        MetricsKey::RaftApplyLag,
+        MetricsKey::RaftMembershipSize,
+        MetricsKey::RaftLogFileCount,
+        MetricsKey::RaftSnapshotBuildCount,
+        MetricsKey::RaftSnapshotBuildRt,
+        MetricsKey::RaftSnapshotFileSize,
+        //naming cluster
+        MetricsKey::NamingClusterNodeSize,
+        MetricsKey::NamingClusterValidNodeSize,
         //grpc request
         MetricsKey::GrpcRequestHandleRtHistogram,
         MetricsKey::GrpcRequestHandleRtSummary,
@@ -167,12 +223,39 @@ impl MetricsKey {
             MetricsKey::GrpcConnResponseTimeoutSetItemSize => {
                 "grpc_conn_response_timeout_set_item_size"
             }
+            MetricsKey::GrpcConfigPushPendingSize => "grpc_config_push_pending_size",
+            MetricsKey::GrpcConfigPushCount => "grpc_config_push_count",
+            MetricsKey::GrpcConfigPushAckCount => "grpc_config_push_ack_count",
+            MetricsKey::GrpcConfigPushRetryCount => "grpc_config_push_retry_count",
+            MetricsKey::GrpcConfigPushFailCount => "grpc_config_push_fail_count",
+            MetricsKey::RaftCurrentTerm => "raft_current_term",
+            MetricsKey::RaftIsLeader => "raft_is_leader",
+            MetricsKey::RaftLeaderChangeCount => "raft_leader_change_count",
+            MetricsKey::RaftLastLogIndex => "raft_last_log_index",
+            MetricsKey::RaftLastAppliedIndex => "raft_last_applied_index",
+            MetricsKey::RaftApplyLag => "raft_apply_lag",
+            MetricsKey::RaftMembershipSize => "raft_membership_size",
+            MetricsKey::RaftLogFileCount => "raft_log_file_count",
+            MetricsKey::RaftSnapshotBuildCount => "raft_snapshot_build_count",
+            MetricsKey::RaftSnapshotBuildRt => "raft_snapshot_build_rt",
+            MetricsKey::RaftSnapshotFileSize => "raft_snapshot_file_size",
+            MetricsKey::NamingClusterNodeSize => "naming_cluster_node_size",
+            MetricsKey::NamingClusterValidNodeSize => "naming_cluster_valid_node_size",
             MetricsKey::GrpcRequestHandleRtHistogram => "grpc_request_handle_rt_histogram",
             MetricsKey::GrpcRequestHandleRtSummary => "grpc_request_handle_rt_summary",
             MetricsKey::GrpcRequestTotalCount => "grpc_request_total_count",
             MetricsKey::HttpRequestHandleRtHistogram => "http_request_handle_rt_histogram",
             MetricsKey::HttpRequestHandleRtSummary => "http_request_handle_rt_summary",
             MetricsKey::HttpRequestTotalCount => "http_request_total_count",
+            MetricsKey::GrpcRequestCount => "grpc_request_count",
+            MetricsKey::GrpcRequestRtHistogram => "grpc_request_rt_histogram",
+            MetricsKey::HttpRequestCount => "http_request_count",
+            MetricsKey::HttpRequestRtHistogram => "http_request_rt_histogram",
+            MetricsKey::NamespaceConfigSize => "namespace_config_size",
+            MetricsKey::NamespaceServiceSize => "namespace_service_size",
+            MetricsKey::NamespaceInstanceSize => "namespace_instance_size",
+            MetricsKey::RaftReplicationLag => "raft_replication_lag",
+            MetricsKey::NamingClusterNodeStatus => "naming_cluster_node_status",
         }
     }
 
@@ -241,6 +324,24 @@ impl MetricsKey {
             MetricsKey::GrpcConnResponseTimeoutSetItemSize => {
                 "Grpc conn response timeout set item size"
             }
+            MetricsKey::GrpcConfigPushPendingSize => "Grpc config push waiting for ack size",
+            MetricsKey::GrpcConfigPushCount => "Grpc config push count",
+            MetricsKey::GrpcConfigPushAckCount => "Grpc config push acked count",
+            MetricsKey::GrpcConfigPushRetryCount => "Grpc config push retry count",
+            MetricsKey::GrpcConfigPushFailCount => "Grpc config push failed count after retries",
+            MetricsKey::RaftCurrentTerm => "Raft current term",
+            MetricsKey::RaftIsLeader => "Raft node is leader,1 is leader",
+            MetricsKey::RaftLeaderChangeCount => "Raft leader change count",
+            MetricsKey::RaftLastLogIndex => "Raft last log index",
+            MetricsKey::RaftLastAppliedIndex => "Raft last applied index",
+            MetricsKey::RaftApplyLag => "Raft log size of not applied",
+            MetricsKey::RaftMembershipSize => "Raft membership size",
+            MetricsKey::RaftLogFileCount => "Raft log file count",
+            MetricsKey::RaftSnapshotBuildCount => "Raft snapshot build count",
+            MetricsKey::RaftSnapshotBuildRt => "Raft last snapshot build rt,unit is ms",
+            MetricsKey::RaftSnapshotFileSize => "Raft last snapshot file size,unit is byte",
+            MetricsKey::NamingClusterNodeSize => "Naming cluster node size",
+            MetricsKey::NamingClusterValidNodeSize => "Naming cluster valid node size",
             MetricsKey::GrpcRequestHandleRtHistogram => {
                 "Grpc request handle rt histogram,unit is ms"
             }
@@ -251,6 +352,19 @@ impl MetricsKey {
             }
             MetricsKey::HttpRequestHandleRtSummary => "Http request handle rt summary,unit is ms",
             MetricsKey::HttpRequestTotalCount => "Http request total count",
+            MetricsKey::GrpcRequestCount => "Grpc request count by request type and code",
+            MetricsKey::GrpcRequestRtHistogram => {
+                "Grpc request handle rt histogram by request type,unit is ms"
+            }
+            MetricsKey::HttpRequestCount => "Http request count by route,method and code",
+            MetricsKey::HttpRequestRtHistogram => {
+                "Http request handle rt histogram by route,unit is ms"
+            }
+            MetricsKey::NamespaceConfigSize => "Config size by namespace",
+            MetricsKey::NamespaceServiceSize => "Naming service size by namespace",
+            MetricsKey::NamespaceInstanceSize => "Naming instance size by namespace",
+            MetricsKey::RaftReplicationLag => "Raft log size of not replicated by follower node",
+            MetricsKey::NamingClusterNodeStatus => "Naming cluster node status,1 is valid",
             //default describe
             //_ => "Some help info",
         }
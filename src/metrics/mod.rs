@@ -2,6 +2,7 @@ pub mod core;
 pub mod counter;
 pub mod gauge;
 pub mod histogram;
+pub mod label;
 pub mod metrics_key;
 pub mod model;
 pub mod summary;
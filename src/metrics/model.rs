@@ -1,4 +1,4 @@
-use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::metrics_key::{Label, MetricsKey};
 use crate::metrics::timeline::model::{TimelineQueryParam, TimelineQueryResponse};
 use actix::prelude::*;
 use std::cmp::Ordering;
@@ -434,11 +434,18 @@ impl Display for SummaryValueFmtWrap<'_> {
 #[rtype(result = "anyhow::Result<Vec<MetricsItem>>")]
 pub struct MetricsQuery;
 
+///
+/// 查询带标签的指标,如按命名空间统计的数量
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<Vec<LabelMetricsItem>>")]
+pub struct LabelMetricsQuery;
+
 #[derive(Message, Clone, Debug)]
 #[rtype(result = "anyhow::Result<MetricsResponse>")]
 pub enum MetricsRequest {
     Record(MetricsItem),
     BatchRecord(Vec<MetricsItem>),
+    LabelBatchRecord(Vec<LabelMetricsItem>),
     TimelineQuery(TimelineQueryParam),
     Export,
 }
@@ -473,6 +480,23 @@ impl MetricsItem {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct LabelMetricsItem {
+    pub metrics_type: MetricsKey,
+    pub labels: Vec<Label>,
+    pub record: MetricsRecord,
+}
+
+impl LabelMetricsItem {
+    pub fn new(metrics_type: MetricsKey, labels: Vec<Label>, record: MetricsRecord) -> Self {
+        Self {
+            metrics_type,
+            labels,
+            record,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::metrics::model::{HistogramValue, SummaryValue};
@@ -8,6 +8,10 @@ use std::{
 use actix::prelude::*;
 use bean_factory::{bean, Inject};
 
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{
+    LabelMetricsItem, LabelMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+};
 use crate::{
     naming::core::{NamingActor, NamingCmd},
     now_millis,
@@ -539,3 +543,40 @@ impl NodeManage {
             .do_send(NodeManageRequest::ActiveNode(node_id))
     }
 }
+
+impl Handler<MetricsQuery> for InnerNodeManage {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let valid_size = self.all_nodes.values().filter(|e| e.is_valid()).count();
+        Ok(vec![
+            MetricsItem::new(
+                MetricsKey::NamingClusterNodeSize,
+                MetricsRecord::Gauge(self.all_nodes.len() as f32),
+            ),
+            MetricsItem::new(
+                MetricsKey::NamingClusterValidNodeSize,
+                MetricsRecord::Gauge(valid_size as f32),
+            ),
+        ])
+    }
+}
+
+impl Handler<LabelMetricsQuery> for InnerNodeManage {
+    type Result = anyhow::Result<Vec<LabelMetricsItem>>;
+
+    fn handle(&mut self, _msg: LabelMetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let list = self
+            .all_nodes
+            .values()
+            .map(|node| {
+                LabelMetricsItem::new(
+                    MetricsKey::NamingClusterNodeStatus,
+                    vec![Label::new("node_id", node.id.to_string())],
+                    MetricsRecord::Gauge(if node.is_valid() { 1f32 } else { 0f32 }),
+                )
+            })
+            .collect();
+        Ok(list)
+    }
+}
@@ -1,12 +1,16 @@
 use std::sync::Arc;
 
 use crate::{
+    common::constant::NAMING_INSTANCE_TREE_NAME,
     grpc::PayloadUtils,
     naming::{
         core::{NamingActor, NamingCmd, NamingResult},
-        model::{Instance, InstanceUpdateTag},
+        model::{Instance, InstanceDo, InstanceUpdateTag},
+    },
+    raft::{
+        db::{route::TableRoute, table::TableManagerReq},
+        network::factory::RaftClusterRequestSender,
     },
-    raft::network::factory::RaftClusterRequestSender,
 };
 
 use super::{
@@ -17,11 +21,12 @@ use super::{
 use crate::grpc::handler::NAMING_ROUTE_REQUEST;
 use actix::prelude::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct NamingRoute {
     naming_addr: Addr<NamingActor>,
     node_manage: Arc<NodeManage>,
     cluster_sender: Arc<RaftClusterRequestSender>,
+    table_route: Arc<TableRoute>,
 }
 
 impl NamingRoute {
@@ -29,11 +34,13 @@ impl NamingRoute {
         naming_addr: Addr<NamingActor>,
         node_manage: Arc<NodeManage>,
         cluster_sender: Arc<RaftClusterRequestSender>,
+        table_route: Arc<TableRoute>,
     ) -> Self {
         Self {
             naming_addr,
             node_manage,
             cluster_sender,
+            table_route,
         }
     }
 
@@ -42,6 +49,9 @@ impl NamingRoute {
         instance: Instance,
         tag: Option<InstanceUpdateTag>,
     ) -> anyhow::Result<()> {
+        if !instance.ephemeral {
+            return self.update_persistent_instance(instance, tag).await;
+        }
         let key = instance.get_service_key();
         match self.node_manage.route_addr(&key).await {
             NamingRouteAddr::Local(_) => {
@@ -108,7 +118,66 @@ impl NamingRoute {
         Ok(())
     }
 
+    ///
+    /// 持久化实例通过raft写入,由状态机同步到各节点的NamingActor
+    async fn update_persistent_instance(
+        &self,
+        mut instance: Instance,
+        tag: Option<InstanceUpdateTag>,
+    ) -> anyhow::Result<()> {
+        if let Some(tag) = tag {
+            if let Some(old_instance) = self.query_local_instance(&instance).await? {
+                if !tag.weight {
+                    instance.weight = old_instance.weight;
+                }
+                if !tag.enabled {
+                    instance.enabled = old_instance.enabled;
+                }
+                if !tag.metadata {
+                    instance.metadata = old_instance.metadata.clone();
+                }
+            }
+        }
+        instance.init();
+        instance.from_grpc = false;
+        instance.from_cluster = 0;
+        let req = TableManagerReq::Set {
+            table_name: NAMING_INSTANCE_TREE_NAME.clone(),
+            key: instance.get_instance_key().to_db_key(),
+            value: InstanceDo::from(&instance).to_bytes(),
+            last_seq_id: None,
+        };
+        self.table_route.request(req).await
+    }
+
+    async fn query_local_instance(
+        &self,
+        instance: &Instance,
+    ) -> anyhow::Result<Option<Arc<Instance>>> {
+        match self
+            .naming_addr
+            .send(NamingCmd::Query(instance.clone()))
+            .await??
+        {
+            NamingResult::Instance(v) => Ok(Some(v)),
+            _ => Ok(None),
+        }
+    }
+
     pub async fn delete_instance(&self, instance: Instance) -> anyhow::Result<()> {
+        let is_persistent = !instance.ephemeral
+            || self
+                .query_local_instance(&instance)
+                .await?
+                .map(|e| !e.ephemeral)
+                .unwrap_or(false);
+        if is_persistent {
+            let req = TableManagerReq::Remove {
+                table_name: NAMING_INSTANCE_TREE_NAME.clone(),
+                key: instance.get_instance_key().to_db_key(),
+            };
+            return self.table_route.request(req).await;
+        }
         let key = instance.get_service_key();
         match self.node_manage.route_addr(&key).await {
             NamingRouteAddr::Local(_) => {
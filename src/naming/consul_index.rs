@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use actix::prelude::*;
+use tokio::sync::oneshot;
+
+use super::model::ServiceKey;
+
+///
+/// 服务从未变更时的索引,consul要求索引大于0
+const BASE_INDEX: u64 = 1;
+
+struct IndexWaiter {
+    key: Option<ServiceKey>,
+    sender: oneshot::Sender<u64>,
+}
+
+///
+/// 维护consul阻塞查询使用的变更索引;key为None时表示服务列表
+pub struct ConsulIndexManage {
+    index: u64,
+    service_index: HashMap<ServiceKey, u64>,
+    waiters: Vec<IndexWaiter>,
+}
+
+impl Default for ConsulIndexManage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsulIndexManage {
+    pub fn new() -> Self {
+        Self {
+            index: BASE_INDEX,
+            service_index: Default::default(),
+            waiters: Default::default(),
+        }
+    }
+
+    pub fn get_index(&self, key: &Option<ServiceKey>) -> u64 {
+        match key {
+            Some(key) => self.service_index.get(key).cloned().unwrap_or(BASE_INDEX),
+            None => self.index,
+        }
+    }
+
+    fn change(&mut self, key: ServiceKey) {
+        self.index += 1;
+        let index = self.index;
+        self.service_index.insert(key.clone(), index);
+        let waiters = std::mem::take(&mut self.waiters);
+        for waiter in waiters {
+            match &waiter.key {
+                Some(v) if v != &key => self.waiters.push(waiter),
+                _ => {
+                    waiter.sender.send(index).ok();
+                }
+            }
+        }
+    }
+
+    fn heartbeat(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_millis(10000), |act, ctx| {
+            act.waiters.retain(|e| !e.sender.is_closed());
+            act.heartbeat(ctx);
+        });
+    }
+}
+
+impl Actor for ConsulIndexManage {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        log::info!("ConsulIndexManage started");
+        self.heartbeat(ctx);
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "anyhow::Result<ConsulIndexResult>")]
+pub enum ConsulIndexCmd {
+    ServiceChanged(ServiceKey),
+    QueryIndex(Option<ServiceKey>),
+    ///
+    /// 索引与当前值相同时等待下一次变更
+    Wait(Option<ServiceKey>, u64),
+}
+
+pub enum ConsulIndexResult {
+    Index(u64),
+    Wait(oneshot::Receiver<u64>),
+}
+
+impl Handler<ConsulIndexCmd> for ConsulIndexManage {
+    type Result = anyhow::Result<ConsulIndexResult>;
+
+    fn handle(&mut self, msg: ConsulIndexCmd, _ctx: &mut Context<Self>) -> Self::Result {
+        match msg {
+            ConsulIndexCmd::ServiceChanged(key) => {
+                self.change(key);
+                Ok(ConsulIndexResult::Index(self.index))
+            }
+            ConsulIndexCmd::QueryIndex(key) => Ok(ConsulIndexResult::Index(self.get_index(&key))),
+            ConsulIndexCmd::Wait(key, index) => {
+                let current = self.get_index(&key);
+                if current != index {
+                    return Ok(ConsulIndexResult::Index(current));
+                }
+                let (sender, receiver) = oneshot::channel();
+                self.waiters.push(IndexWaiter { key, sender });
+                Ok(ConsulIndexResult::Wait(receiver))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consul_index_change() {
+        let mut manage = ConsulIndexManage::new();
+        let key = ServiceKey::new("public", "DEFAULT_GROUP", "svc01");
+        let other_key = ServiceKey::new("public", "DEFAULT_GROUP", "svc02");
+        let (sender, mut service_receiver) = oneshot::channel();
+        manage.waiters.push(IndexWaiter {
+            key: Some(key.clone()),
+            sender,
+        });
+        let (sender, mut all_receiver) = oneshot::channel();
+        manage.waiters.push(IndexWaiter { key: None, sender });
+
+        manage.change(other_key.clone());
+        assert_eq!(all_receiver.try_recv().unwrap(), 2);
+        assert!(service_receiver.try_recv().is_err());
+        manage.change(key.clone());
+        assert_eq!(service_receiver.try_recv().unwrap(), 3);
+        assert!(manage.waiters.is_empty());
+
+        assert_eq!(manage.get_index(&Some(other_key)), 2);
+        assert_eq!(manage.get_index(&Some(key)), 3);
+        assert_eq!(
+            manage.get_index(&Some(ServiceKey::new("public", "DEFAULT_GROUP", "svc03"))),
+            BASE_INDEX
+        );
+        assert_eq!(manage.get_index(&None), 3);
+    }
+}
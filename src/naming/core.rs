@@ -14,7 +14,10 @@ use super::cluster::model::{
     NamingRouteRequest, ProcessRange, SnapshotForReceive, SnapshotForSend,
 };
 use super::cluster::node_manage::{InnerNodeManage, NodeManageRequest};
+use super::consul_index::ConsulIndexManage;
+use super::eureka_delta::EurekaDeltaManage;
 use super::filter::InstanceFilterUtils;
+use super::health_check::{HealthCheckConfig, HealthCheckItem};
 use super::listener::{InnerNamingListener, ListenerItem, NamingListenerCmd};
 use super::model::Instance;
 use super::model::InstanceKey;
@@ -33,6 +36,7 @@ use super::service::ServiceInfoDto;
 use super::service::ServiceMetadata;
 use super::service_index::NamespaceIndex;
 use super::service_index::ServiceQueryParam;
+use super::xds::core::XdsManage;
 use super::NamingUtils;
 use crate::common::delay_notify;
 use crate::common::hash_utils::get_hash_value;
@@ -55,6 +59,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::common::constant::EMPTY_ARC_STRING;
+use crate::common::model::privilege::PrivilegeGroup;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
 use actix::prelude::*;
@@ -102,6 +107,15 @@ impl Inject for NamingActor {
         if let Some(notify_addr) = self.delay_notify_addr.as_ref() {
             self.subscriber.set_notify_addr(notify_addr.clone());
         }
+        if let Some(xds_manage) = factory_data.get_actor::<XdsManage>() {
+            self.subscriber.set_xds_notify_addr(xds_manage);
+        }
+        if let Some(consul_index_manage) = factory_data.get_actor::<ConsulIndexManage>() {
+            self.subscriber.set_consul_index_addr(consul_index_manage);
+        }
+        if let Some(eureka_delta_manage) = factory_data.get_actor::<EurekaDeltaManage>() {
+            self.subscriber.set_eureka_delta_addr(eureka_delta_manage);
+        }
         self.cluster_node_manage = factory_data.get_actor();
         self.cluster_delay_notify = factory_data.get_actor();
         log::info!("NamingActor inject complete");
@@ -325,7 +339,7 @@ impl NamingActor {
             self.empty_service_set
                 .add(now + self.sys_config.service_time_out_millis, key.clone());
         }
-        let remove_instance = old_instance.filter(|e| !e.is_from_cluster());
+        let remove_instance = old_instance.filter(|e| !e.is_from_cluster() && e.ephemeral);
         self.do_notify(&tag, key.clone(), remove_instance);
         if let Some(client_id) = real_client_id {
             if !client_id.as_ref().is_empty() {
@@ -385,9 +399,10 @@ impl NamingActor {
         }
         if !is_from_from_cluster {
             //change notify
+            //持久化实例通过raft同步,不需要再通知其它节点
             let instance = service
                 .get_instance(&instance_short_key)
-                .filter(|e| !e.is_from_cluster());
+                .filter(|e| !e.is_from_cluster() && e.ephemeral);
             self.do_notify(&tag, key.clone(), instance);
         }
         tag
@@ -509,14 +524,12 @@ impl NamingActor {
 
     pub fn time_check(&mut self) {
         let current_time = Local::now().timestamp_millis();
-        let healthy_time = current_time - 15000;
-        let offline_time = current_time - 30000;
         let mut size = 0;
         let now = now_millis();
         let mut change_list = vec![];
         for item in self.service_map.values_mut() {
             let service_key = item.get_service_key();
-            let (rlist, ulist) = item.time_check(healthy_time, offline_time);
+            let (rlist, ulist) = item.time_check(current_time);
             size += rlist.len() + ulist.len();
             if !rlist.is_empty() {
                 for short_key in &rlist {
@@ -613,6 +626,7 @@ impl NamingActor {
         page_size: usize,
         page_index: usize,
         key: &ServiceKey,
+        privilege: Option<Arc<PrivilegeGroup>>,
     ) -> (usize, Vec<Arc<String>>) {
         let offset = if page_index == 0 {
             0
@@ -624,6 +638,7 @@ impl NamingActor {
             limit: page_size,
             namespace_id: Some(key.namespace_id.clone()),
             group: Some(key.group_name.clone()),
+            privilege,
             ..Default::default()
         };
         let (size, list) = self.namespace_index.query_service_page(&param);
@@ -837,9 +852,10 @@ pub enum NamingCmd {
     QueryAllInstanceList(ServiceKey),
     QueryListString(ServiceKey, String, bool, Option<SocketAddr>),
     QueryServiceInfo(ServiceKey, String, bool),
-    QueryServicePage(ServiceKey, usize, usize),
+    QueryServicePage(ServiceKey, usize, usize, Option<Arc<PrivilegeGroup>>),
     //查询服务实际信息列表
     QueryServiceInfoPage(ServiceQueryParam),
+    QueryServiceKeyList(ServiceQueryParam),
     //CreateService(ServiceDetailDto),
     UpdateService(ServiceDetailDto),
     UpdateServiceFromCluster(ServiceDetailDto),
@@ -851,6 +867,10 @@ pub enum NamingCmd {
     RemoveClient(Arc<String>),
     RemoveClientFromCluster(Arc<String>),
     QueryClientInstanceCount,
+    QueryClientInstanceList(Arc<String>),
+    QueryClientSubscribeList(Arc<String>),
+    QueryServiceSubscriberList(ServiceKey),
+    QueryPersistentInstances,
     QueryDalAddr,
     QuerySnapshot(Vec<ProcessRange>),
     ClusterRefreshProcessRange(ProcessRange),
@@ -866,6 +886,9 @@ pub enum NamingResult {
     ServicePage((usize, Vec<Arc<String>>)),
     ServiceInfoPage((usize, Vec<ServiceInfoDto>)),
     ClientInstanceCount(Vec<(Arc<String>, usize)>),
+    ClientIdList(Vec<Arc<String>>),
+    ServiceKeyList(Vec<ServiceKey>),
+    PersistentInstances(Vec<HealthCheckItem>),
     RewriteToCluster(u64, Instance),
     Snapshot(SnapshotForSend),
 }
@@ -943,16 +966,20 @@ impl Handler<NamingCmd> for NamingActor {
                 let service_info = self.get_service_info(&service_key, cluster_str, only_healthy);
                 Ok(NamingResult::ServiceInfo(service_info))
             }
-            NamingCmd::QueryServicePage(service_key, page_size, page_index) => {
+            NamingCmd::QueryServicePage(service_key, page_size, page_index, privilege) => {
                 Ok(NamingResult::ServicePage(self.get_service_list(
                     page_size,
                     page_index,
                     &service_key,
+                    privilege,
                 )))
             }
             NamingCmd::QueryServiceInfoPage(param) => Ok(NamingResult::ServiceInfoPage(
                 self.get_service_info_page(param),
             )),
+            NamingCmd::QueryServiceKeyList(param) => Ok(NamingResult::ServiceKeyList(
+                self.namespace_index.query_service_page(&param).1,
+            )),
             NamingCmd::PeekListenerTimeout => {
                 self.time_check();
                 //self.notify_check();
@@ -1033,6 +1060,39 @@ impl Handler<NamingCmd> for NamingActor {
                 }
                 Ok(NamingResult::ClientInstanceCount(client_instance_count))
             }
+            NamingCmd::QueryClientInstanceList(client_id) => {
+                let mut list = vec![];
+                if let Some(set) = self.client_instance_set.get(&client_id) {
+                    for key in set {
+                        if let Some(instance) =
+                            self.get_instance(&key.get_service_key(), &key.get_short_key())
+                        {
+                            list.push(instance);
+                        }
+                    }
+                }
+                Ok(NamingResult::InstanceList(list))
+            }
+            NamingCmd::QueryClientSubscribeList(client_id) => Ok(NamingResult::ServiceKeyList(
+                self.subscriber.get_client_service_keys(&client_id),
+            )),
+            NamingCmd::QueryServiceSubscriberList(key) => Ok(NamingResult::ClientIdList(
+                self.subscriber.get_service_client_ids(&key),
+            )),
+            NamingCmd::QueryPersistentInstances => {
+                let mut list = vec![];
+                for service in self.service_map.values() {
+                    if !HealthCheckConfig::is_enable_by_metadata(&service.metadata) {
+                        continue;
+                    }
+                    for instance in service.instances.values() {
+                        if !instance.ephemeral {
+                            list.push((service.metadata.clone(), instance.clone()));
+                        }
+                    }
+                }
+                Ok(NamingResult::PersistentInstances(list))
+            }
             NamingCmd::QuerySnapshot(ranges) => {
                 let res = self.build_snapshot_data(ranges);
                 Ok(NamingResult::Snapshot(res))
@@ -1151,3 +1211,26 @@ fn test_remove_has_instance_service() {
     assert!(naming.remove_empty_service(service_key.clone()).is_ok());
     assert!(naming.namespace_index.service_size == 0);
 }
+
+#[test]
+fn test_persistent_instance_not_timeout() {
+    use super::model::InstanceDo;
+    use super::*;
+    let mut naming = NamingActor::new();
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = Arc::new("public".to_owned());
+    instance.service_name = Arc::new("foo".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.ephemeral = false;
+    instance.init();
+    let service_key = instance.get_service_key();
+    naming.update_instance(&service_key, instance.clone(), None);
+    assert!(naming.get_healthy_timeout_set_item_size() == 0);
+
+    let instance_do = InstanceDo::from(&instance);
+    let restore_instance: Instance = InstanceDo::from_bytes(&instance_do.to_bytes())
+        .unwrap()
+        .into();
+    assert!(!restore_instance.ephemeral);
+    assert!(restore_instance.get_instance_key() == instance.get_instance_key());
+}
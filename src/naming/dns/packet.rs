@@ -0,0 +1,343 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+pub const TYPE_OPT: u16 = 41;
+pub const TYPE_ANY: u16 = 255;
+pub const CLASS_IN: u16 = 1;
+pub const CLASS_ANY: u16 = 255;
+
+pub const RCODE_NO_ERROR: u16 = 0;
+pub const RCODE_FORMAT_ERROR: u16 = 1;
+pub const RCODE_SERVER_FAILURE: u16 = 2;
+pub const RCODE_NAME_ERROR: u16 = 3;
+pub const RCODE_NOT_IMPLEMENTED: u16 = 4;
+pub const RCODE_REFUSED: u16 = 5;
+
+const HEADER_SIZE: usize = 12;
+const FLAG_QR: u16 = 0x8000;
+const FLAG_AA: u16 = 0x0400;
+const FLAG_TC: u16 = 0x0200;
+const FLAG_RD: u16 = 0x0100;
+const OPCODE_MASK: u16 = 0x7800;
+const MAX_NAME_SIZE: usize = 255;
+/// 未携带EDNS时udp响应的最大长度
+pub const MAX_UDP_SIZE: usize = 512;
+/// 问题域名在报文中的偏移,用于压缩应答中的域名
+const QUESTION_NAME_POINTER: u16 = 0xC000 | HEADER_SIZE as u16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsQuestion {
+    pub name: String,
+    pub qtype: u16,
+    pub qclass: u16,
+}
+
+///
+/// dns查询请求,只支持单个问题的标准查询
+#[derive(Debug, Clone)]
+pub struct DnsRequest {
+    pub id: u16,
+    pub flags: u16,
+    pub question: DnsQuestion,
+    /// EDNS声明的udp报文大小
+    pub udp_size: Option<u16>,
+}
+
+impl DnsRequest {
+    ///
+    /// 解析请求;报文头可读但内容不支持时返回对应错误码的响应
+    pub fn parse(buf: &[u8]) -> Result<Self, Option<DnsResponse>> {
+        if buf.len() < HEADER_SIZE {
+            return Err(None);
+        }
+        let id = read_u16(buf, 0);
+        let flags = read_u16(buf, 2);
+        let error = |rcode| Some(DnsResponse::error(id, flags, None, rcode));
+        if flags & FLAG_QR != 0 {
+            return Err(None);
+        }
+        if flags & OPCODE_MASK != 0 {
+            return Err(error(RCODE_NOT_IMPLEMENTED));
+        }
+        if read_u16(buf, 4) != 1 {
+            return Err(error(RCODE_FORMAT_ERROR));
+        }
+        let additional_count = read_u16(buf, 10);
+        let mut offset = HEADER_SIZE;
+        let name = read_name(buf, &mut offset).ok_or_else(|| error(RCODE_FORMAT_ERROR))?;
+        if buf.len() < offset + 4 {
+            return Err(error(RCODE_FORMAT_ERROR));
+        }
+        let question = DnsQuestion {
+            name,
+            qtype: read_u16(buf, offset),
+            qclass: read_u16(buf, offset + 2),
+        };
+        offset += 4;
+        let mut udp_size = None;
+        if additional_count > 0 && read_u16(buf, 6) == 0 && read_u16(buf, 8) == 0 {
+            //只识别紧跟问题之后的OPT记录
+            if buf.get(offset) == Some(&0)
+                && buf.len() >= offset + 5
+                && read_u16(buf, offset + 1) == TYPE_OPT
+            {
+                udp_size = Some(read_u16(buf, offset + 3));
+            }
+        }
+        Ok(Self {
+            id,
+            flags,
+            question,
+            udp_size,
+        })
+    }
+
+    pub fn get_max_udp_size(&self) -> usize {
+        self.udp_size
+            .map(|v| (v as usize).max(MAX_UDP_SIZE))
+            .unwrap_or(MAX_UDP_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsRecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub name: String,
+    pub ttl: u32,
+    pub data: DnsRecordData,
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsResponse {
+    pub id: u16,
+    pub request_flags: u16,
+    pub rcode: u16,
+    pub question: Option<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+    pub additionals: Vec<DnsRecord>,
+}
+
+impl DnsResponse {
+    pub fn new(request: &DnsRequest, rcode: u16) -> Self {
+        Self {
+            id: request.id,
+            request_flags: request.flags,
+            rcode,
+            question: Some(request.question.clone()),
+            answers: vec![],
+            additionals: vec![],
+        }
+    }
+
+    pub fn error(id: u16, request_flags: u16, question: Option<DnsQuestion>, rcode: u16) -> Self {
+        Self {
+            id,
+            request_flags,
+            rcode,
+            question,
+            answers: vec![],
+            additionals: vec![],
+        }
+    }
+
+    ///
+    /// 编码响应,超过max_size时截断记录并设置TC标记
+    pub fn to_bytes(&self, max_size: usize) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(MAX_UDP_SIZE);
+        let mut flags = FLAG_QR | FLAG_AA | (self.request_flags & FLAG_RD) | (self.rcode & 0x0f);
+        buf.extend_from_slice(&self.id.to_be_bytes());
+        buf.extend_from_slice(&flags.to_be_bytes());
+        buf.extend_from_slice(&(self.question.is_some() as u16).to_be_bytes());
+        buf.extend_from_slice(&[0u8; 6]);
+        let question_name = self.question.as_ref().map(|e| e.name.as_str());
+        if let Some(question) = &self.question {
+            write_name(&mut buf, &question.name, None);
+            buf.extend_from_slice(&question.qtype.to_be_bytes());
+            buf.extend_from_slice(&question.qclass.to_be_bytes());
+        }
+        let mut answer_count = 0u16;
+        let mut additional_count = 0u16;
+        let mut truncated = false;
+        for record in &self.answers {
+            let len = buf.len();
+            write_record(&mut buf, record, question_name);
+            if buf.len() > max_size {
+                buf.truncate(len);
+                truncated = true;
+                break;
+            }
+            answer_count += 1;
+        }
+        if !truncated {
+            for record in &self.additionals {
+                let len = buf.len();
+                write_record(&mut buf, record, question_name);
+                if buf.len() > max_size {
+                    //附加记录不完整时不需要设置TC
+                    buf.truncate(len);
+                    break;
+                }
+                additional_count += 1;
+            }
+        }
+        if truncated {
+            flags |= FLAG_TC;
+            buf[2..4].copy_from_slice(&flags.to_be_bytes());
+        }
+        buf[6..8].copy_from_slice(&answer_count.to_be_bytes());
+        buf[10..12].copy_from_slice(&additional_count.to_be_bytes());
+        buf
+    }
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([buf[offset], buf[offset + 1]])
+}
+
+///
+/// 读取问题中的域名,不支持压缩指针
+fn read_name(buf: &[u8], offset: &mut usize) -> Option<String> {
+    let mut labels: Vec<String> = vec![];
+    let mut size = 0;
+    loop {
+        let len = *buf.get(*offset)? as usize;
+        *offset += 1;
+        if len == 0 {
+            break;
+        }
+        if len > 63 {
+            return None;
+        }
+        let label = buf.get(*offset..*offset + len)?;
+        *offset += len;
+        size += len + 1;
+        if size > MAX_NAME_SIZE {
+            return None;
+        }
+        labels.push(String::from_utf8(label.to_vec()).ok()?);
+    }
+    Some(labels.join("."))
+}
+
+///
+/// 写入域名;与问题域名相同或以其结尾时使用压缩指针
+fn write_name(buf: &mut Vec<u8>, name: &str, question_name: Option<&str>) {
+    let name = name.trim_end_matches('.');
+    let prefix = match question_name {
+        Some(q) if !q.is_empty() && name == q => Some(""),
+        Some(q) if !q.is_empty() => name
+            .strip_suffix(q)
+            .and_then(|e| e.strip_suffix('.'))
+            .filter(|e| !e.is_empty()),
+        _ => None,
+    };
+    let labels = prefix.unwrap_or(name);
+    for label in labels.split('.').filter(|e| !e.is_empty()) {
+        let label = &label.as_bytes()[..label.len().min(63)];
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label);
+    }
+    if prefix.is_some() {
+        buf.extend_from_slice(&QUESTION_NAME_POINTER.to_be_bytes());
+    } else {
+        buf.push(0);
+    }
+}
+
+fn write_record(buf: &mut Vec<u8>, record: &DnsRecord, question_name: Option<&str>) {
+    write_name(buf, &record.name, question_name);
+    let record_type = match &record.data {
+        DnsRecordData::A(_) => TYPE_A,
+        DnsRecordData::Aaaa(_) => TYPE_AAAA,
+        DnsRecordData::Srv { .. } => TYPE_SRV,
+    };
+    buf.extend_from_slice(&record_type.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    buf.extend_from_slice(&record.ttl.to_be_bytes());
+    let len_offset = buf.len();
+    buf.extend_from_slice(&[0u8; 2]);
+    match &record.data {
+        DnsRecordData::A(ip) => buf.extend_from_slice(&ip.octets()),
+        DnsRecordData::Aaaa(ip) => buf.extend_from_slice(&ip.octets()),
+        DnsRecordData::Srv {
+            priority,
+            weight,
+            port,
+            target,
+        } => {
+            buf.extend_from_slice(&priority.to_be_bytes());
+            buf.extend_from_slice(&weight.to_be_bytes());
+            buf.extend_from_slice(&port.to_be_bytes());
+            //rfc2782要求SRV的target不压缩
+            write_name(buf, target, None);
+        }
+    }
+    let data_len = (buf.len() - len_offset - 2) as u16;
+    buf[len_offset..len_offset + 2].copy_from_slice(&data_len.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut buf = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        write_name(&mut buf, name, None);
+        buf.extend_from_slice(&qtype.to_be_bytes());
+        buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+        buf
+    }
+
+    #[test]
+    fn dns_packet() {
+        let query = build_query("foo.DEFAULT_GROUP.public.rnacos.local", TYPE_A);
+        let request = DnsRequest::parse(&query).unwrap();
+        assert_eq!(request.id, 0x1234);
+        assert_eq!(
+            request.question.name,
+            "foo.DEFAULT_GROUP.public.rnacos.local"
+        );
+        assert_eq!(request.question.qtype, TYPE_A);
+        assert_eq!(request.get_max_udp_size(), MAX_UDP_SIZE);
+        assert!(DnsRequest::parse(&query[..8]).unwrap_err().is_none());
+
+        let mut response = DnsResponse::new(&request, RCODE_NO_ERROR);
+        response.answers.push(DnsRecord {
+            name: request.question.name.clone(),
+            ttl: 5,
+            data: DnsRecordData::A(Ipv4Addr::new(10, 0, 0, 1)),
+        });
+        let bytes = response.to_bytes(MAX_UDP_SIZE);
+        assert_eq!(read_u16(&bytes, 2), 0x8500);
+        assert_eq!(read_u16(&bytes, 6), 1);
+        //问题部分之后为压缩指针+类型+类+ttl+长度+ip
+        let answer = &bytes[query.len()..];
+        assert_eq!(read_u16(answer, 0), QUESTION_NAME_POINTER);
+        assert_eq!(read_u16(answer, 2), TYPE_A);
+        assert_eq!(&answer[10..], &[0, 4, 10, 0, 0, 1]);
+
+        for i in 0..100 {
+            response.answers.push(DnsRecord {
+                name: request.question.name.clone(),
+                ttl: 5,
+                data: DnsRecordData::A(Ipv4Addr::new(10, 0, 1, i)),
+            });
+        }
+        let bytes = response.to_bytes(MAX_UDP_SIZE);
+        assert!(bytes.len() <= MAX_UDP_SIZE);
+        assert_eq!(read_u16(&bytes, 2) & FLAG_TC, FLAG_TC);
+    }
+}
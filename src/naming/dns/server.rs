@@ -0,0 +1,305 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use actix::Addr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+
+use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::NamingUtils;
+
+use super::packet::{
+    DnsRecord, DnsRecordData, DnsRequest, DnsResponse, CLASS_ANY, CLASS_IN, RCODE_NAME_ERROR,
+    RCODE_NOT_IMPLEMENTED, RCODE_NO_ERROR, RCODE_REFUSED, RCODE_SERVER_FAILURE, TYPE_A, TYPE_AAAA,
+    TYPE_ANY, TYPE_SRV,
+};
+
+const MAX_UDP_PACKET_SIZE: usize = 4096;
+const MAX_TCP_PACKET_SIZE: usize = u16::MAX as usize;
+const SRV_WEIGHT_SCALE: f32 = 100f32;
+
+///
+/// 把`<service>.<group>.<namespace>.<domain>`解析为注册中心中健康、启用的实例;
+/// 实例域名为`<ip>.<service>.<group>.<namespace>.<domain>`,ip中的`.`与`:`替换为`-`
+pub struct DnsResolver {
+    naming_addr: Addr<NamingActor>,
+    domain: String,
+    ttl: u32,
+}
+
+impl DnsResolver {
+    pub fn new(naming_addr: Addr<NamingActor>, domain: &str, ttl: u32) -> Self {
+        Self {
+            naming_addr,
+            domain: domain.trim_matches('.').to_lowercase(),
+            ttl,
+        }
+    }
+
+    pub async fn resolve_bytes(&self, buf: &[u8], is_udp: bool) -> Option<Vec<u8>> {
+        match DnsRequest::parse(buf) {
+            Ok(request) => {
+                let max_size = if is_udp {
+                    request.get_max_udp_size()
+                } else {
+                    MAX_TCP_PACKET_SIZE
+                };
+                let response = self.resolve(&request).await;
+                Some(response.to_bytes(max_size))
+            }
+            Err(Some(response)) => Some(response.to_bytes(MAX_TCP_PACKET_SIZE)),
+            Err(None) => None,
+        }
+    }
+
+    pub async fn resolve(&self, request: &DnsRequest) -> DnsResponse {
+        let question = &request.question;
+        if question.qclass != CLASS_IN && question.qclass != CLASS_ANY {
+            return DnsResponse::new(request, RCODE_NOT_IMPLEMENTED);
+        }
+        let labels = match self.get_labels(&question.name) {
+            Some(v) => v,
+            None => return DnsResponse::new(request, RCODE_REFUSED),
+        };
+        let key = match Self::build_service_key(&labels) {
+            Some(v) => v,
+            None => return DnsResponse::new(request, RCODE_NAME_ERROR),
+        };
+        let instances = match self.query_instances(key).await {
+            Ok(v) => v,
+            Err(err) => {
+                log::warn!("dns query instances error,{}", err);
+                return DnsResponse::new(request, RCODE_SERVER_FAILURE);
+            }
+        };
+        if !instances.is_empty() {
+            return self.build_service_response(request, &instances);
+        }
+        //按实例域名查询
+        if let (Some(ip), Some(key)) = (
+            labels.first().and_then(|e| Self::decode_ip(e)),
+            Self::build_service_key(&labels[1..]),
+        ) {
+            match self.query_instances(key).await {
+                Ok(instances) => {
+                    if instances.iter().any(|e| Self::parse_ip(e) == Some(ip)) {
+                        let mut response = DnsResponse::new(request, RCODE_NO_ERROR);
+                        let record = self.build_ip_record(&question.name, ip);
+                        if Self::match_type(question.qtype, &record.data) {
+                            response.answers.push(record);
+                        }
+                        return response;
+                    }
+                }
+                Err(err) => {
+                    log::warn!("dns query instances error,{}", err);
+                    return DnsResponse::new(request, RCODE_SERVER_FAILURE);
+                }
+            }
+        }
+        DnsResponse::new(request, RCODE_NAME_ERROR)
+    }
+
+    fn build_service_response(
+        &self,
+        request: &DnsRequest,
+        instances: &[Arc<Instance>],
+    ) -> DnsResponse {
+        let question = &request.question;
+        let mut response = DnsResponse::new(request, RCODE_NO_ERROR);
+        let mut ip_set = HashSet::new();
+        for instance in instances {
+            let ip = Self::parse_ip(instance);
+            if let Some(ip) = ip {
+                let record = self.build_ip_record(&question.name, ip);
+                if ip_set.insert(ip) && Self::match_type(question.qtype, &record.data) {
+                    response.answers.push(record);
+                }
+            }
+            if question.qtype != TYPE_SRV && question.qtype != TYPE_ANY {
+                continue;
+            }
+            let target = match ip {
+                Some(ip) => format!("{}.{}", Self::encode_ip(&ip), &question.name),
+                None => instance.ip.as_ref().to_owned(),
+            };
+            let weight = (instance.weight * SRV_WEIGHT_SCALE)
+                .round()
+                .clamp(0f32, u16::MAX as f32) as u16;
+            response.answers.push(DnsRecord {
+                name: question.name.clone(),
+                ttl: self.ttl,
+                data: DnsRecordData::Srv {
+                    priority: 0,
+                    weight,
+                    port: instance.port as u16,
+                    target: target.clone(),
+                },
+            });
+            if let Some(ip) = ip {
+                let record = self.build_ip_record(&target, ip);
+                if !response.additionals.contains(&record) {
+                    response.additionals.push(record);
+                }
+            }
+        }
+        response
+    }
+
+    fn build_ip_record(&self, name: &str, ip: IpAddr) -> DnsRecord {
+        let data = match ip {
+            IpAddr::V4(v) => DnsRecordData::A(v),
+            IpAddr::V6(v) => DnsRecordData::Aaaa(v),
+        };
+        DnsRecord {
+            name: name.to_owned(),
+            ttl: self.ttl,
+            data,
+        }
+    }
+
+    fn match_type(qtype: u16, data: &DnsRecordData) -> bool {
+        matches!(
+            (qtype, data),
+            (TYPE_ANY, _) | (TYPE_A, DnsRecordData::A(_)) | (TYPE_AAAA, DnsRecordData::Aaaa(_))
+        )
+    }
+
+    async fn query_instances(&self, key: ServiceKey) -> anyhow::Result<Vec<Arc<Instance>>> {
+        let cmd = NamingCmd::QueryList(key, "".to_owned(), true, None);
+        match self.naming_addr.send(cmd).await?? {
+            NamingResult::InstanceList(list) => Ok(list),
+            _ => Ok(vec![]),
+        }
+    }
+
+    ///
+    /// 去掉域名后缀,返回前面的标签;不属于本域名时返回None
+    fn get_labels<'a>(&self, name: &'a str) -> Option<Vec<&'a str>> {
+        let name = name.trim_end_matches('.');
+        if name.len() <= self.domain.len() + 1
+            || !name.is_char_boundary(name.len() - self.domain.len())
+        {
+            return None;
+        }
+        let (prefix, suffix) = name.split_at(name.len() - self.domain.len());
+        if !suffix.eq_ignore_ascii_case(&self.domain) {
+            return None;
+        }
+        let prefix = prefix.strip_suffix('.')?;
+        Some(prefix.split('.').collect())
+    }
+
+    fn build_service_key(labels: &[&str]) -> Option<ServiceKey> {
+        let len = labels.len();
+        if len < 3 || labels.iter().any(|e| e.is_empty()) {
+            return None;
+        }
+        let namespace_id = NamingUtils::default_namespace(labels[len - 1].to_owned());
+        Some(ServiceKey::new(
+            &namespace_id,
+            labels[len - 2],
+            &labels[..len - 2].join("."),
+        ))
+    }
+
+    fn parse_ip(instance: &Instance) -> Option<IpAddr> {
+        instance.ip.parse().ok()
+    }
+
+    fn encode_ip(ip: &IpAddr) -> String {
+        ip.to_string().replace(['.', ':'], "-")
+    }
+
+    fn decode_ip(label: &str) -> Option<IpAddr> {
+        label
+            .replace('-', ".")
+            .parse()
+            .ok()
+            .or_else(|| label.replace('-', ":").parse().ok())
+    }
+}
+
+///
+/// 启动dns服务,同时监听udp与tcp
+pub async fn run_dns_server(addr: String, resolver: Arc<DnsResolver>) -> anyhow::Result<()> {
+    let udp_socket = Arc::new(UdpSocket::bind(&addr).await?);
+    let tcp_listener = TcpListener::bind(&addr).await?;
+    let udp_resolver = resolver.clone();
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; MAX_UDP_PACKET_SIZE];
+        loop {
+            let (len, peer) = match udp_socket.recv_from(&mut buf).await {
+                Ok(v) => v,
+                Err(err) => {
+                    log::warn!("dns udp recv error,{}", err);
+                    continue;
+                }
+            };
+            let data = buf[..len].to_vec();
+            let socket = udp_socket.clone();
+            let resolver = udp_resolver.clone();
+            tokio::spawn(async move {
+                if let Some(response) = resolver.resolve_bytes(&data, true).await {
+                    socket.send_to(&response, peer).await.ok();
+                }
+            });
+        }
+    });
+    loop {
+        match tcp_listener.accept().await {
+            Ok((stream, _)) => {
+                let resolver = resolver.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_tcp_conn(stream, resolver).await {
+                        log::debug!("dns tcp conn closed,{}", err);
+                    }
+                });
+            }
+            Err(err) => log::warn!("dns tcp accept error,{}", err),
+        }
+    }
+}
+
+///
+/// tcp报文前两个字节为长度
+async fn handle_tcp_conn(mut stream: TcpStream, resolver: Arc<DnsResolver>) -> anyhow::Result<()> {
+    loop {
+        let len = stream.read_u16().await? as usize;
+        let mut buf = vec![0u8; len];
+        stream.read_exact(&mut buf).await?;
+        match resolver.resolve_bytes(&buf, false).await {
+            Some(response) => {
+                stream.write_u16(response.len() as u16).await?;
+                stream.write_all(&response).await?;
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_name() {
+        let labels = vec!["a", "b", "svc", "DEFAULT_GROUP", "public"];
+        let key = DnsResolver::build_service_key(&labels).unwrap();
+        assert_eq!(key.service_name.as_str(), "a.b.svc");
+        assert_eq!(key.group_name.as_str(), "DEFAULT_GROUP");
+        assert_eq!(key.namespace_id.as_str(), "public");
+        assert!(DnsResolver::build_service_key(&labels[3..]).is_none());
+
+        for ip in ["10.0.0.1", "fd00::1"] {
+            let ip: IpAddr = ip.parse().unwrap();
+            assert_eq!(
+                DnsResolver::decode_ip(&DnsResolver::encode_ip(&ip)),
+                Some(ip)
+            );
+        }
+        assert_eq!(DnsResolver::decode_ip("svc"), None);
+    }
+}
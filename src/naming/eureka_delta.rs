@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+
+use crate::common::AppSysConfig;
+use crate::now_millis_i64;
+
+use super::core::{NamingActor, NamingCmd, NamingResult};
+use super::model::{Instance, ServiceKey};
+
+///
+/// 增量变更保留时长,与eureka默认的retentionTimeInMSInDeltaQueue一致
+const DELTA_RETENTION_MILLIS: i64 = 3 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EurekaActionType {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl EurekaActionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EurekaActionType::Added => "ADDED",
+            EurekaActionType::Modified => "MODIFIED",
+            EurekaActionType::Deleted => "DELETED",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EurekaDeltaItem {
+    pub action_type: EurekaActionType,
+    pub instance: Arc<Instance>,
+    pub time: i64,
+}
+
+///
+/// 记录eureka映射分组下的实例变更,供eureka客户端增量拉取
+#[bean(inject)]
+pub struct EurekaDeltaManage {
+    naming_addr: Option<Addr<NamingActor>>,
+    enable: bool,
+    namespace_id: Arc<String>,
+    group_name: Arc<String>,
+    snapshots: HashMap<ServiceKey, HashMap<Arc<String>, Arc<Instance>>>,
+    changed_keys: HashSet<ServiceKey>,
+    delta_items: VecDeque<EurekaDeltaItem>,
+    version: u64,
+}
+
+impl Default for EurekaDeltaManage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EurekaDeltaManage {
+    pub fn new() -> Self {
+        Self {
+            naming_addr: None,
+            enable: false,
+            namespace_id: Default::default(),
+            group_name: Default::default(),
+            snapshots: Default::default(),
+            changed_keys: Default::default(),
+            delta_items: Default::default(),
+            version: 0,
+        }
+    }
+
+    fn is_watch_key(&self, key: &ServiceKey) -> bool {
+        self.enable && key.namespace_id == self.namespace_id && key.group_name == self.group_name
+    }
+
+    ///
+    /// 对比服务实例快照,记录新增、修改与删除的实例
+    fn update_snapshot(&mut self, key: ServiceKey, instances: Vec<Arc<Instance>>, now: i64) {
+        let old_snapshot = self.snapshots.remove(&key).unwrap_or_default();
+        let mut snapshot = HashMap::with_capacity(instances.len());
+        let mut delta_items = vec![];
+        for instance in instances {
+            let id = instance.id.clone();
+            match old_snapshot.get(&id) {
+                Some(old) if !old.update_info(&instance, None) => {}
+                Some(_) => delta_items.push((EurekaActionType::Modified, instance.clone())),
+                None => delta_items.push((EurekaActionType::Added, instance.clone())),
+            }
+            snapshot.insert(id, instance);
+        }
+        for (id, instance) in old_snapshot {
+            if !snapshot.contains_key(&id) {
+                delta_items.push((EurekaActionType::Deleted, instance));
+            }
+        }
+        if !snapshot.is_empty() {
+            self.snapshots.insert(key, snapshot);
+        }
+        if delta_items.is_empty() {
+            return;
+        }
+        self.version += 1;
+        for (action_type, instance) in delta_items {
+            self.delta_items.push_back(EurekaDeltaItem {
+                action_type,
+                instance,
+                time: now,
+            });
+        }
+    }
+
+    fn clear_timeout_items(&mut self, now: i64) {
+        while let Some(item) = self.delta_items.front() {
+            if item.time + DELTA_RETENTION_MILLIS > now {
+                break;
+            }
+            self.delta_items.pop_front();
+        }
+    }
+
+    fn flush_changed(&mut self, ctx: &mut Context<Self>) {
+        let naming_addr = match &self.naming_addr {
+            Some(v) => v.clone(),
+            None => return,
+        };
+        let keys: Vec<ServiceKey> = self.changed_keys.drain().collect();
+        async move {
+            let mut list = Vec::with_capacity(keys.len());
+            for key in keys {
+                match naming_addr
+                    .send(NamingCmd::QueryAllInstanceList(key.clone()))
+                    .await
+                {
+                    Ok(Ok(NamingResult::InstanceList(instances))) => list.push((key, instances)),
+                    Ok(Ok(_)) => list.push((key, vec![])),
+                    _ => log::warn!("eureka delta query instances error"),
+                }
+            }
+            list
+        }
+        .into_actor(self)
+        .map(|list, act, _| {
+            let now = now_millis_i64();
+            for (key, instances) in list {
+                act.update_snapshot(key, instances, now);
+            }
+        })
+        .wait(ctx);
+    }
+
+    fn heartbeat(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_millis(1000), |act, ctx| {
+            act.clear_timeout_items(now_millis_i64());
+            if !act.changed_keys.is_empty() {
+                act.flush_changed(ctx);
+            }
+            act.heartbeat(ctx);
+        });
+    }
+}
+
+impl Actor for EurekaDeltaManage {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("EurekaDeltaManage started");
+    }
+}
+
+impl Inject for EurekaDeltaManage {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.naming_addr = factory_data.get_actor();
+        if let Some(sys_config) = factory_data.get_bean::<AppSysConfig>() {
+            self.enable = sys_config.eureka_api_enable;
+            self.namespace_id = sys_config.eureka_namespace.clone();
+            self.group_name = sys_config.eureka_group.clone();
+        }
+        if self.enable {
+            self.heartbeat(ctx);
+        }
+        log::info!("EurekaDeltaManage inject complete");
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "anyhow::Result<EurekaDeltaResult>")]
+pub enum EurekaDeltaCmd {
+    ServiceChanged(ServiceKey),
+    QueryDelta,
+}
+
+pub enum EurekaDeltaResult {
+    None,
+    Delta(u64, Vec<EurekaDeltaItem>),
+}
+
+impl Handler<EurekaDeltaCmd> for EurekaDeltaManage {
+    type Result = anyhow::Result<EurekaDeltaResult>;
+
+    fn handle(&mut self, msg: EurekaDeltaCmd, _ctx: &mut Context<Self>) -> Self::Result {
+        match msg {
+            EurekaDeltaCmd::ServiceChanged(key) => {
+                if self.is_watch_key(&key) {
+                    self.changed_keys.insert(key);
+                }
+                Ok(EurekaDeltaResult::None)
+            }
+            EurekaDeltaCmd::QueryDelta => {
+                self.clear_timeout_items(now_millis_i64());
+                let items = self.delta_items.iter().cloned().collect();
+                Ok(EurekaDeltaResult::Delta(self.version, items))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eureka_delta_snapshot() {
+        let mut manage = EurekaDeltaManage::new();
+        let key = ServiceKey::new("public", "DEFAULT_GROUP", "app01");
+        let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+        instance.generate_key();
+        let other = {
+            let mut v = Instance::new("127.0.0.2".to_owned(), 8080);
+            v.generate_key();
+            v
+        };
+        manage.update_snapshot(
+            key.clone(),
+            vec![Arc::new(instance.clone()), Arc::new(other.clone())],
+            0,
+        );
+        assert_eq!(manage.version, 1);
+        assert_eq!(manage.delta_items.len(), 2);
+
+        //只有最后修改时间变化时不记录变更
+        let mut renew_instance = instance.clone();
+        renew_instance.last_modified_millis = 100;
+        manage.update_snapshot(
+            key.clone(),
+            vec![Arc::new(renew_instance), Arc::new(other.clone())],
+            100,
+        );
+        assert_eq!(manage.version, 1);
+
+        instance.healthy = false;
+        manage.update_snapshot(key.clone(), vec![Arc::new(instance)], 200);
+        assert_eq!(manage.version, 2);
+        let actions: Vec<_> = manage
+            .delta_items
+            .iter()
+            .skip(2)
+            .map(|e| e.action_type)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![EurekaActionType::Modified, EurekaActionType::Deleted]
+        );
+
+        manage.clear_timeout_items(DELTA_RETENTION_MILLIS + 100);
+        assert_eq!(manage.delta_items.len(), 2);
+        manage.update_snapshot(key.clone(), vec![], 300);
+        assert!(manage.snapshots.is_empty());
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::naming::cluster::route::NamingRoute;
+use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::model::{Instance, InstanceKey, InstanceUpdateTag};
+use crate::now_millis;
+use crate::raft::cluster::model::RouteAddr;
+use crate::raft::cluster::route::RaftAddrRouter;
+
+/// 服务元数据中健康检查配置的前缀
+/// 支持按集群覆盖,如 healthChecker.DEFAULT.type 优先于 healthChecker.type
+pub const HEALTH_CHECKER_PREFIX: &str = "healthChecker.";
+pub const HEALTH_CHECKER_TYPE: &str = "type";
+pub const HEALTH_CHECKER_PATH: &str = "path";
+pub const HEALTH_CHECKER_EXPECTED_CODE: &str = "expectedCode";
+pub const HEALTH_CHECKER_INTERVAL: &str = "interval";
+pub const HEALTH_CHECKER_TIMEOUT: &str = "timeout";
+pub const HEALTH_CHECKER_FAIL_THRESHOLD: &str = "failThreshold";
+pub const HEALTH_CHECKER_CHECK_PORT: &str = "checkPort";
+
+/// 需要主动检查的持久化实例及其所属服务的元数据
+pub type HealthCheckItem = (Arc<HashMap<String, String>>, Arc<Instance>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthCheckType {
+    None,
+    Tcp,
+    Http,
+}
+
+impl HealthCheckType {
+    pub fn new_by_value(v: &str) -> Self {
+        match v.to_uppercase().as_str() {
+            "TCP" => Self::Tcp,
+            "HTTP" => Self::Http,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthCheckConfig {
+    pub check_type: HealthCheckType,
+    pub path: String,
+    pub expected_code: u16,
+    /// 检查间隔,单位毫秒
+    pub interval: u64,
+    /// 检查超时,单位毫秒
+    pub timeout: u64,
+    /// 连续失败多少次后标记为不健康
+    pub fail_threshold: u32,
+    pub check_port: Option<u32>,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            check_type: HealthCheckType::None,
+            path: "/".to_owned(),
+            expected_code: 200,
+            interval: 5000,
+            timeout: 3000,
+            fail_threshold: 2,
+            check_port: None,
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    pub fn is_enable_by_metadata(metadata: &HashMap<String, String>) -> bool {
+        metadata
+            .keys()
+            .any(|k| k.starts_with(HEALTH_CHECKER_PREFIX))
+    }
+
+    pub fn from_metadata(metadata: &HashMap<String, String>, cluster_name: &str) -> Self {
+        let get_value = |key: &str| {
+            metadata
+                .get(&format!(
+                    "{}{}.{}",
+                    HEALTH_CHECKER_PREFIX, cluster_name, key
+                ))
+                .or_else(|| metadata.get(&format!("{}{}", HEALTH_CHECKER_PREFIX, key)))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let mut config = Self::default();
+        if let Some(v) = get_value(HEALTH_CHECKER_TYPE) {
+            config.check_type = HealthCheckType::new_by_value(v);
+        }
+        if let Some(v) = get_value(HEALTH_CHECKER_PATH) {
+            if v.starts_with('/') {
+                config.path = v.to_owned();
+            } else {
+                config.path = format!("/{}", v);
+            }
+        }
+        if let Some(v) = get_value(HEALTH_CHECKER_EXPECTED_CODE).and_then(|v| v.parse().ok()) {
+            config.expected_code = v;
+        }
+        if let Some(v) = get_value(HEALTH_CHECKER_INTERVAL).and_then(|v| v.parse::<u64>().ok()) {
+            config.interval = v.max(1000);
+        }
+        if let Some(v) = get_value(HEALTH_CHECKER_TIMEOUT).and_then(|v| v.parse::<u64>().ok()) {
+            config.timeout = v.max(100);
+        }
+        if let Some(v) = get_value(HEALTH_CHECKER_FAIL_THRESHOLD).and_then(|v| v.parse().ok()) {
+            config.fail_threshold = std::cmp::max(v, 1);
+        }
+        config.check_port = get_value(HEALTH_CHECKER_CHECK_PORT).and_then(|v| v.parse().ok());
+        config
+    }
+}
+
+pub struct HealthCheckUtils;
+
+impl HealthCheckUtils {
+    pub async fn check(instance: &Instance, config: &HealthCheckConfig) -> bool {
+        let port = config.check_port.unwrap_or(instance.port);
+        let addr = Self::build_addr(&instance.ip, port);
+        let timeout = Duration::from_millis(config.timeout);
+        match config.check_type {
+            HealthCheckType::Tcp => Self::tcp_check(&addr, timeout).await,
+            HealthCheckType::Http => {
+                Self::http_check(&addr, &config.path, config.expected_code, timeout).await
+            }
+            HealthCheckType::None => true,
+        }
+    }
+
+    ///
+    /// ip为IPv6时需加方括号,非ip(如域名)直接拼接端口
+    pub fn build_addr(ip: &str, port: u32) -> String {
+        match (ip.parse::<IpAddr>(), u16::try_from(port)) {
+            (Ok(ip), Ok(port)) => SocketAddr::new(ip, port).to_string(),
+            _ => format!("{}:{}", ip, port),
+        }
+    }
+
+    pub async fn tcp_check(addr: &str, timeout: Duration) -> bool {
+        matches!(
+            tokio::time::timeout(timeout, TcpStream::connect(addr)).await,
+            Ok(Ok(_))
+        )
+    }
+
+    pub async fn http_check(addr: &str, path: &str, expected_code: u16, timeout: Duration) -> bool {
+        match tokio::time::timeout(timeout, Self::http_status_code(addr, path)).await {
+            Ok(Ok(code)) => code == expected_code,
+            _ => false,
+        }
+    }
+
+    async fn http_status_code(addr: &str, path: &str) -> anyhow::Result<u16> {
+        let mut stream = TcpStream::connect(addr).await?;
+        let req = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: r-nacos-health-checker\r\nConnection: close\r\n\r\n",
+            path, addr
+        );
+        stream.write_all(req.as_bytes()).await?;
+        let mut buf = Vec::with_capacity(128);
+        let mut tmp = [0u8; 128];
+        //只需要读取状态行
+        while !buf.contains(&b'\n') && buf.len() < 1024 {
+            let n = stream.read(&mut tmp).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&tmp[..n]);
+        }
+        let line = String::from_utf8_lossy(&buf);
+        let code = line
+            .split_whitespace()
+            .nth(1)
+            .ok_or_else(|| anyhow::anyhow!("http response is invalid"))?;
+        Ok(code.parse()?)
+    }
+}
+
+#[derive(Debug, Default)]
+struct CheckState {
+    next_check_time: u64,
+    fail_count: u32,
+    checking: bool,
+}
+
+///
+/// 持久化实例的主动健康检查
+/// 只在raft主节点执行,检查结果通过raft同步到所有节点
+#[bean(inject)]
+pub struct HealthCheckActor {
+    naming_addr: Option<Addr<NamingActor>>,
+    naming_route: Option<Arc<NamingRoute>>,
+    raft_addr_route: Option<Arc<RaftAddrRouter>>,
+    check_states: HashMap<InstanceKey, CheckState>,
+    period: u64,
+}
+
+impl Actor for HealthCheckActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("HealthCheckActor started");
+    }
+}
+
+impl Inject for HealthCheckActor {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.naming_addr = factory_data.get_actor();
+        self.naming_route = factory_data.get_bean();
+        self.raft_addr_route = factory_data.get_bean();
+        self.heartbeat(ctx);
+        log::info!("HealthCheckActor inject complete");
+    }
+}
+
+impl Default for HealthCheckActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthCheckActor {
+    pub fn new() -> Self {
+        Self {
+            naming_addr: None,
+            naming_route: None,
+            raft_addr_route: None,
+            check_states: Default::default(),
+            period: 1000,
+        }
+    }
+
+    fn heartbeat(&self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_millis(self.period), |act, ctx| {
+            act.query_check_instances(ctx);
+            act.heartbeat(ctx);
+        });
+    }
+
+    fn query_check_instances(&mut self, ctx: &mut Context<Self>) {
+        let (naming_addr, raft_addr_route) =
+            match (self.naming_addr.clone(), self.raft_addr_route.clone()) {
+                (Some(a), Some(b)) => (a, b),
+                _ => return,
+            };
+        async move {
+            if let RouteAddr::Local = raft_addr_route.get_route_addr().await? {
+                if let NamingResult::PersistentInstances(list) = naming_addr
+                    .send(NamingCmd::QueryPersistentInstances)
+                    .await??
+                {
+                    return Ok(list);
+                }
+            }
+            Ok(vec![])
+        }
+        .into_actor(self)
+        .map(
+            |result: anyhow::Result<Vec<HealthCheckItem>>, act, ctx| match result {
+                Ok(list) => act.dispatch_check(list, ctx),
+                Err(e) => log::warn!("query health check instances error,{}", e),
+            },
+        )
+        .spawn(ctx);
+    }
+
+    fn dispatch_check(&mut self, list: Vec<HealthCheckItem>, ctx: &mut Context<Self>) {
+        let now = now_millis();
+        let mut keys = HashSet::with_capacity(list.len());
+        for (metadata, instance) in list {
+            let config = HealthCheckConfig::from_metadata(&metadata, &instance.cluster_name);
+            if config.check_type == HealthCheckType::None {
+                continue;
+            }
+            let key = instance.get_instance_key();
+            keys.insert(key.clone());
+            let state = self.check_states.entry(key.clone()).or_default();
+            if state.checking || now < state.next_check_time {
+                continue;
+            }
+            state.checking = true;
+            state.next_check_time = now + config.interval;
+            async move {
+                let success = HealthCheckUtils::check(&instance, &config).await;
+                (instance, config.fail_threshold, success)
+            }
+            .into_actor(self)
+            .map(move |(instance, fail_threshold, success), act, ctx| {
+                act.on_check_result(key, instance, fail_threshold, success, ctx);
+            })
+            .spawn(ctx);
+        }
+        self.check_states.retain(|k, _| keys.contains(k));
+    }
+
+    fn on_check_result(
+        &mut self,
+        key: InstanceKey,
+        instance: Arc<Instance>,
+        fail_threshold: u32,
+        success: bool,
+        ctx: &mut Context<Self>,
+    ) {
+        let state = if let Some(state) = self.check_states.get_mut(&key) {
+            state
+        } else {
+            return;
+        };
+        state.checking = false;
+        let healthy = if success {
+            state.fail_count = 0;
+            true
+        } else {
+            state.fail_count += 1;
+            state.fail_count < fail_threshold
+        };
+        if healthy != instance.healthy {
+            log::info!(
+                "health check change instance healthy,{:?},healthy:{}",
+                &key,
+                healthy
+            );
+            self.update_instance_healthy(instance, healthy, ctx);
+        }
+    }
+
+    fn update_instance_healthy(
+        &mut self,
+        instance: Arc<Instance>,
+        healthy: bool,
+        ctx: &mut Context<Self>,
+    ) {
+        let naming_route = if let Some(naming_route) = self.naming_route.clone() {
+            naming_route
+        } else {
+            return;
+        };
+        let mut instance = instance.as_ref().clone();
+        instance.healthy = healthy;
+        let tag = InstanceUpdateTag {
+            weight: false,
+            metadata: false,
+            enabled: false,
+            ephemeral: false,
+            from_update: false,
+        };
+        async move { naming_route.update_instance(instance, Some(tag)).await }
+            .into_actor(self)
+            .map(|result, _act, _ctx| {
+                if let Err(e) = result {
+                    log::error!("health check update instance error,{}", e);
+                }
+            })
+            .spawn(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_config_from_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert("healthChecker.type".to_owned(), "tcp".to_owned());
+        metadata.insert("healthChecker.c1.type".to_owned(), "HTTP".to_owned());
+        metadata.insert("healthChecker.c1.path".to_owned(), "health".to_owned());
+        metadata.insert("healthChecker.interval".to_owned(), "2000".to_owned());
+        assert!(HealthCheckConfig::is_enable_by_metadata(&metadata));
+        let config = HealthCheckConfig::from_metadata(&metadata, "DEFAULT");
+        assert_eq!(config.check_type, HealthCheckType::Tcp);
+        assert_eq!(config.interval, 2000);
+        let config = HealthCheckConfig::from_metadata(&metadata, "c1");
+        assert_eq!(config.check_type, HealthCheckType::Http);
+        assert_eq!(config.path, "/health");
+        let config = HealthCheckConfig::from_metadata(&HashMap::new(), "DEFAULT");
+        assert_eq!(config.check_type, HealthCheckType::None);
+    }
+
+    #[test]
+    fn test_build_addr() {
+        assert_eq!(
+            HealthCheckUtils::build_addr("127.0.0.1", 8080),
+            "127.0.0.1:8080"
+        );
+        assert_eq!(HealthCheckUtils::build_addr("::1", 8080), "[::1]:8080");
+        assert_eq!(
+            HealthCheckUtils::build_addr("fe80::1:2", 80),
+            "[fe80::1:2]:80"
+        );
+        assert_eq!(
+            HealthCheckUtils::build_addr("localhost", 80),
+            "localhost:80"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tcp_and_http_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                    .await;
+            }
+        });
+        let timeout = Duration::from_millis(1000);
+        assert!(HealthCheckUtils::tcp_check(&addr, timeout).await);
+        assert!(HealthCheckUtils::http_check(&addr, "/", 200, timeout).await);
+        assert!(!HealthCheckUtils::http_check(&addr, "/", 204, timeout).await);
+
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_addr = closed.local_addr().unwrap().to_string();
+        drop(closed);
+        assert!(!HealthCheckUtils::tcp_check(&closed_addr, timeout).await);
+    }
+}
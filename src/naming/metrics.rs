@@ -1,7 +1,12 @@
-use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{
+    LabelMetricsItem, LabelMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+};
 use crate::naming::core::NamingActor;
+use crate::naming::NamingUtils;
 use actix::Handler;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 impl Handler<MetricsQuery> for NamingActor {
     type Result = anyhow::Result<Vec<MetricsItem>>;
@@ -89,3 +94,34 @@ impl Handler<MetricsQuery> for NamingActor {
         Ok(list)
     }
 }
+
+impl Handler<LabelMetricsQuery> for NamingActor {
+    type Result = anyhow::Result<Vec<LabelMetricsItem>>;
+
+    fn handle(&mut self, _: LabelMetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        //namespace -> (service_size,instance_size)
+        let mut namespace_map: HashMap<Arc<String>, (usize, usize)> = HashMap::new();
+        for service in self.service_map.values() {
+            let item = namespace_map
+                .entry(service.namespace_id.clone())
+                .or_default();
+            item.0 += 1;
+            item.1 += service.instances.len();
+        }
+        let mut list = Vec::with_capacity(namespace_map.len() * 2);
+        for (namespace_id, (service_size, instance_size)) in namespace_map {
+            let namespace = NamingUtils::default_namespace(namespace_id.as_ref().to_owned());
+            list.push(LabelMetricsItem::new(
+                MetricsKey::NamespaceServiceSize,
+                vec![Label::new("namespace", namespace.clone())],
+                MetricsRecord::Gauge(service_size as f32),
+            ));
+            list.push(LabelMetricsItem::new(
+                MetricsKey::NamespaceInstanceSize,
+                vec![Label::new("namespace", namespace)],
+                MetricsRecord::Gauge(instance_size as f32),
+            ));
+        }
+        Ok(list)
+    }
+}
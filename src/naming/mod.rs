@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 pub mod api_model;
+pub mod consul_index;
 pub mod core;
+pub mod eureka_delta;
 pub(crate) mod filter;
+pub mod health_check;
 pub mod listener;
 pub mod model;
 pub mod naming_delay_nofity;
@@ -11,9 +14,11 @@ pub mod service;
 pub mod udp_actor;
 //pub(crate) mod dal;
 pub mod cluster;
+pub mod dns;
 pub mod metrics;
 pub mod ops;
 pub mod service_index;
+pub mod xds;
 
 pub struct NamingUtils;
 
@@ -26,6 +31,13 @@ pub const DEFAULT_NAMESPACE: &str = "public";
 pub const DEFAULT_CLUSTER: &str = "DEFAULT";
 pub const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
 
+///
+/// 实例元数据中自定义的心跳超时与删除超时(毫秒),与nacos的保留key一致
+pub const HEART_BEAT_TIMEOUT_KEY: &str = "preserved.heart.beat.timeout";
+pub const IP_DELETE_TIMEOUT_KEY: &str = "preserved.ip.delete.timeout";
+pub const DEFAULT_HEART_BEAT_TIMEOUT: i64 = 15000;
+pub const DEFAULT_IP_DELETE_TIMEOUT: i64 = 30000;
+
 impl NamingUtils {
     pub fn get_group_and_service_name(service_name: &str, group_name: &str) -> String {
         format!("{}@@{}", group_name, service_name)
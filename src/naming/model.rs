@@ -4,6 +4,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::now_millis_i64;
 
+use super::{
+    DEFAULT_HEART_BEAT_TIMEOUT, DEFAULT_IP_DELETE_TIMEOUT, HEART_BEAT_TIMEOUT_KEY,
+    IP_DELETE_TIMEOUT_KEY,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Instance {
@@ -42,8 +47,28 @@ impl Instance {
     }
 
     pub fn is_enable_timeout(&self) -> bool {
-        //grpc 不走过期检查
-        !self.from_grpc && !self.is_from_cluster()
+        //grpc 不走过期检查;持久化实例不走过期检查
+        !self.from_grpc && !self.is_from_cluster() && self.ephemeral
+    }
+
+    ///
+    /// 超过心跳超时未更新的实例标记为不健康
+    pub fn get_heart_beat_timeout(&self) -> i64 {
+        self.get_metadata_millis(HEART_BEAT_TIMEOUT_KEY, DEFAULT_HEART_BEAT_TIMEOUT)
+    }
+
+    ///
+    /// 超过删除超时未更新的实例被删除
+    pub fn get_ip_delete_timeout(&self) -> i64 {
+        self.get_metadata_millis(IP_DELETE_TIMEOUT_KEY, DEFAULT_IP_DELETE_TIMEOUT)
+    }
+
+    fn get_metadata_millis(&self, key: &str, default_value: i64) -> i64 {
+        self.metadata
+            .get(key)
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default_value)
     }
 
     pub fn generate_key(&mut self) {
@@ -266,6 +291,16 @@ impl InstanceKey {
             port: self.port.to_owned(),
         }
     }
+
+    ///
+    /// 持久化实例在raft table中的key
+    pub fn to_db_key(&self) -> Vec<u8> {
+        format!(
+            "{}\x02{}\x02{}\x02{}#{}",
+            &self.namespace_id, &self.group_name, &self.service_name, &self.ip, self.port
+        )
+        .into_bytes()
+    }
 }
 
 #[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
@@ -301,3 +336,94 @@ pub enum UpdateInstanceType {
     ///更新其它节点元信息
     UpdateOtherClusterMetaData(u64, Instance),
 }
+
+///
+/// 持久化实例存储对象
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct InstanceDo {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub ip: String,
+    #[prost(uint32, tag = "3")]
+    pub port: u32,
+    #[prost(float, tag = "4")]
+    pub weight: f32,
+    #[prost(bool, tag = "5")]
+    pub enabled: bool,
+    #[prost(bool, tag = "6")]
+    pub healthy: bool,
+    #[prost(string, tag = "7")]
+    pub cluster_name: String,
+    #[prost(string, tag = "8")]
+    pub service_name: String,
+    #[prost(string, tag = "9")]
+    pub group_name: String,
+    #[prost(string, tag = "10")]
+    pub namespace_id: String,
+    #[prost(map = "string, string", tag = "11")]
+    pub metadata:
+        ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+    #[prost(int64, tag = "12")]
+    pub last_modified_millis: i64,
+    #[prost(string, tag = "13")]
+    pub app_name: String,
+}
+
+impl InstanceDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+}
+
+impl From<&Instance> for InstanceDo {
+    fn from(value: &Instance) -> Self {
+        Self {
+            id: value.id.as_ref().to_owned(),
+            ip: value.ip.as_ref().to_owned(),
+            port: value.port,
+            weight: value.weight,
+            enabled: value.enabled,
+            healthy: value.healthy,
+            cluster_name: value.cluster_name.to_owned(),
+            service_name: value.service_name.as_ref().to_owned(),
+            group_name: value.group_name.as_ref().to_owned(),
+            namespace_id: value.namespace_id.as_ref().to_owned(),
+            metadata: value.metadata.as_ref().to_owned(),
+            last_modified_millis: value.last_modified_millis,
+            app_name: value.app_name.to_owned(),
+        }
+    }
+}
+
+impl From<InstanceDo> for Instance {
+    fn from(value: InstanceDo) -> Self {
+        let mut instance = Self {
+            id: Arc::new(value.id),
+            ip: Arc::new(value.ip),
+            port: value.port,
+            weight: value.weight,
+            enabled: value.enabled,
+            healthy: value.healthy,
+            ephemeral: false,
+            cluster_name: value.cluster_name,
+            service_name: Arc::new(value.service_name),
+            group_name: Arc::new(value.group_name),
+            metadata: Arc::new(value.metadata),
+            last_modified_millis: value.last_modified_millis,
+            namespace_id: Arc::new(value.namespace_id),
+            app_name: value.app_name,
+            ..Default::default()
+        };
+        if instance.id.is_empty() {
+            instance.generate_key();
+        }
+        instance
+    }
+}
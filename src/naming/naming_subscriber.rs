@@ -8,8 +8,11 @@ use std::{
 use actix::prelude::*;
 
 use super::{
+    consul_index::{ConsulIndexCmd, ConsulIndexManage},
+    eureka_delta::{EurekaDeltaCmd, EurekaDeltaManage},
     model::{Instance, ServiceInfo, ServiceKey},
     naming_delay_nofity::{DelayNotifyActor, DelayNotifyCmd},
+    xds::core::{XdsCmd, XdsManage},
 };
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -43,6 +46,9 @@ pub struct Subscriber {
     listener: HashMap<ServiceKey, HashMap<Arc<String>, Option<HashSet<String>>>>,
     client_keys: HashMap<Arc<String>, HashSet<ServiceKey>>,
     notify_addr: Option<Addr<DelayNotifyActor>>,
+    xds_notify_addr: Option<Addr<XdsManage>>,
+    consul_index_addr: Option<Addr<ConsulIndexManage>>,
+    eureka_delta_addr: Option<Addr<EurekaDeltaManage>>,
 }
 
 impl Subscriber {
@@ -51,6 +57,9 @@ impl Subscriber {
             listener: Default::default(),
             client_keys: Default::default(),
             notify_addr: Default::default(),
+            xds_notify_addr: Default::default(),
+            consul_index_addr: Default::default(),
+            eureka_delta_addr: Default::default(),
         }
     }
 
@@ -58,6 +67,18 @@ impl Subscriber {
         self.notify_addr = Some(notify_addr);
     }
 
+    pub fn set_xds_notify_addr(&mut self, xds_notify_addr: Addr<XdsManage>) {
+        self.xds_notify_addr = Some(xds_notify_addr);
+    }
+
+    pub fn set_consul_index_addr(&mut self, consul_index_addr: Addr<ConsulIndexManage>) {
+        self.consul_index_addr = Some(consul_index_addr);
+    }
+
+    pub fn set_eureka_delta_addr(&mut self, eureka_delta_addr: Addr<EurekaDeltaManage>) {
+        self.eureka_delta_addr = Some(eureka_delta_addr);
+    }
+
     pub fn add_subscribe(&mut self, client_id: Arc<String>, items: Vec<NamingListenerItem>) {
         match self.client_keys.get_mut(&client_id) {
             Some(set) => {
@@ -151,6 +172,15 @@ impl Subscriber {
 
     pub fn notify(&self, key: ServiceKey) {
         //log::info!("naming_subscriber notify {:?}",&key);
+        if let Some(xds_notify_addr) = &self.xds_notify_addr {
+            xds_notify_addr.do_send(XdsCmd::ServiceChanged(key.clone()));
+        }
+        if let Some(consul_index_addr) = &self.consul_index_addr {
+            consul_index_addr.do_send(ConsulIndexCmd::ServiceChanged(key.clone()));
+        }
+        if let Some(eureka_delta_addr) = &self.eureka_delta_addr {
+            eureka_delta_addr.do_send(EurekaDeltaCmd::ServiceChanged(key.clone()));
+        }
         if let Some(notify_addr) = &self.notify_addr {
             if let Some(set) = self.listener.get(&key) {
                 let mut client_id_set = HashSet::new();
@@ -162,6 +192,26 @@ impl Subscriber {
         }
     }
 
+    ///
+    /// 查询客户端订阅的服务列表
+    pub fn get_client_service_keys(&self, client_id: &Arc<String>) -> Vec<ServiceKey> {
+        if let Some(set) = self.client_keys.get(client_id) {
+            set.iter().cloned().collect()
+        } else {
+            vec![]
+        }
+    }
+
+    ///
+    /// 查询订阅服务的客户端列表
+    pub fn get_service_client_ids(&self, key: &ServiceKey) -> Vec<Arc<String>> {
+        if let Some(map) = self.listener.get(key) {
+            map.keys().cloned().collect()
+        } else {
+            vec![]
+        }
+    }
+
     pub fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
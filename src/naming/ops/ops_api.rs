@@ -1,5 +1,6 @@
-use actix_web::{http::header, web, HttpResponse, Responder};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
 
+use crate::common::web_utils::get_session_privilege;
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
 
 use super::ops_model::{OpsServiceDto, OpsServiceOptQueryListResponse, OpsServiceQueryListRequest};
@@ -7,10 +8,12 @@ use super::ops_model::{OpsServiceDto, OpsServiceOptQueryListResponse, OpsService
 use actix::prelude::*;
 
 pub async fn query_opt_service_list(
+    req: HttpRequest,
     param: web::Query<OpsServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
-    let serivce_param = param.0.to_param().unwrap();
+    let mut serivce_param = param.0.to_param().unwrap();
+    serivce_param.privilege = get_session_privilege(&req);
     match naming_addr
         .send(NamingCmd::QueryServiceInfoPage(serivce_param))
         .await
@@ -158,7 +158,7 @@ impl Service {
         let new_instance = Arc::new(instance);
         if new_instance.is_enable_timeout() {
             self.healthy_timeout_set.add(
-                new_instance.last_modified_millis as u64,
+                (new_instance.last_modified_millis + new_instance.get_heart_beat_timeout()) as u64,
                 new_instance.get_short_key(),
             );
         }
@@ -186,21 +186,25 @@ impl Service {
             );
              */
             self.healthy_timeout_set.add(
-                instance.last_modified_millis as u64,
+                (instance.last_modified_millis + instance.get_heart_beat_timeout()) as u64,
                 instance.get_short_key(),
             );
         }
     }
 
+    ///
+    /// 超时集合按实例的超时时间点排序,实例可通过元数据自定义超时时长
     pub(crate) fn time_check(
         &mut self,
-        healthy_time: i64,
-        offline_time: i64,
+        current_time: i64,
     ) -> (Vec<InstanceShortKey>, Vec<InstanceShortKey>) {
         let mut remove_list = vec![];
-        for key in self.unhealthy_timeout_set.timeout(offline_time as u64) {
+        for key in self.unhealthy_timeout_set.timeout(current_time as u64) {
             if let Some(instance) = self.instances.get(&key) {
-                if !instance.is_enable_timeout() || instance.last_modified_millis > offline_time {
+                if !instance.is_enable_timeout()
+                    || instance.last_modified_millis + instance.get_ip_delete_timeout()
+                        > current_time
+                {
                     continue;
                 }
             }
@@ -208,9 +212,12 @@ impl Service {
             remove_list.push(key);
         }
         let mut update_list = vec![];
-        for key in self.healthy_timeout_set.timeout(healthy_time as u64) {
+        for key in self.healthy_timeout_set.timeout(current_time as u64) {
             if let Some(instance) = self.instances.get(&key) {
-                if !instance.is_enable_timeout() || instance.last_modified_millis > healthy_time {
+                if !instance.is_enable_timeout()
+                    || instance.last_modified_millis + instance.get_heart_beat_timeout()
+                        > current_time
+                {
                     continue;
                 }
             }
@@ -254,8 +261,10 @@ impl Service {
             }
             let mut i = i.as_ref().clone();
             i.healthy = false;
-            self.unhealthy_timeout_set
-                .add(i.last_modified_millis as u64, instance_id.clone());
+            self.unhealthy_timeout_set.add(
+                (i.last_modified_millis + i.get_ip_delete_timeout()) as u64,
+                instance_id.clone(),
+            );
             self.instances.insert(instance_id.clone(), Arc::new(i));
         }
     }
@@ -342,9 +351,10 @@ impl Service {
     }
 
     pub fn get_owner_http_instances(&self) -> Vec<Arc<Instance>> {
+        //持久化实例通过raft同步,不需要放到集群镜像中
         self.instances
             .values()
-            .filter(|x| x.client_id.is_empty())
+            .filter(|x| x.client_id.is_empty() && x.ephemeral)
             .cloned()
             .collect::<Vec<_>>()
     }
@@ -3,6 +3,7 @@ use std::{
     sync::Arc,
 };
 
+use crate::common::model::privilege::PrivilegeGroup;
 use crate::common::string_utils::StringUtils;
 
 use super::model::ServiceKey;
@@ -14,6 +15,8 @@ pub struct ServiceQueryParam {
     pub service: Option<Arc<String>>,
     pub like_group: Option<String>,
     pub like_service: Option<String>,
+    /// 会话的命名空间权限,只返回有读权限的分组
+    pub privilege: Option<Arc<PrivilegeGroup>>,
     pub offset: usize,
     pub limit: usize,
 }
@@ -28,6 +31,13 @@ impl ServiceQueryParam {
             true
         }
     }
+    pub fn match_privilege(&self, namespace_id: &str, g: &str) -> bool {
+        if let Some(privilege) = &self.privilege {
+            privilege.check(namespace_id, Some(g), false)
+        } else {
+            true
+        }
+    }
     pub fn match_service(&self, s: &Arc<String>) -> bool {
         if let Some(service) = &self.service {
             service.is_empty() || StringUtils::eq(s, service)
@@ -115,7 +125,7 @@ impl ServiceIndex {
         let end_index = param.offset + limit;
         let mut index = 0;
         for (g, set) in &self.group_service {
-            if param.match_group(g) {
+            if param.match_group(g) && param.match_privilege(namespace_id, g) {
                 for s in set {
                     if param.match_service(s) {
                         if index >= param.offset && index < end_index {
@@ -302,3 +312,31 @@ fn query_service() {
     assert!(size == 0);
     assert!(list.is_empty());
 }
+
+#[test]
+fn query_service_by_group_privilege() {
+    use crate::common::model::privilege::NamespacePrivilege;
+    let mut index = NamespaceIndex::new();
+    index.insert_service(ServiceKey::new("dev", "team_a", "1"));
+    index.insert_service(ServiceKey::new("dev", "team_b", "1"));
+    index.insert_service(ServiceKey::new("dev", "team_b", "2"));
+
+    let privilege = PrivilegeGroup {
+        enabled: true,
+        config_decrypt: false,
+        items: vec![NamespacePrivilege {
+            namespace_id: Arc::new("dev".to_owned()),
+            groups: vec![Arc::new("team_a".to_owned())],
+            writable: false,
+        }],
+    };
+    let param = ServiceQueryParam {
+        namespace_id: Some(Arc::new("dev".to_owned())),
+        privilege: Some(Arc::new(privilege)),
+        limit: 0xffff_ffff,
+        ..ServiceQueryParam::default()
+    };
+    let (size, list) = index.query_service_page(&param);
+    assert_eq!(size, 1);
+    assert_eq!(list[0].group_name.as_str(), "team_a");
+}
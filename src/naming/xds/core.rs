@@ -0,0 +1,381 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+
+use crate::common::AppSysConfig;
+use crate::grpc::envoy_xds_proto::{
+    Any, ClusterLoadAssignment, DiscoveryRequest, DiscoveryResponse,
+};
+use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::model::ServiceKey;
+use crate::naming::service_index::ServiceQueryParam;
+
+use super::model::{XdsModelUtils, CLUSTER_TYPE_URL, ENDPOINT_TYPE_URL};
+
+pub type XdsSender = tokio::sync::mpsc::Sender<Result<DiscoveryResponse, tonic::Status>>;
+
+///
+/// 每隔多少次心跳全量刷新一次集群列表
+const CLUSTER_REFRESH_HEARTBEAT_TIMES: u64 = 10;
+
+struct XdsStream {
+    sender: XdsSender,
+    node_id: String,
+    cds_watch: bool,
+    eds_names: HashSet<String>,
+}
+
+impl XdsStream {
+    fn new(sender: XdsSender) -> Self {
+        Self {
+            sender,
+            node_id: "".to_owned(),
+            cds_watch: false,
+            eds_names: Default::default(),
+        }
+    }
+}
+
+///
+/// envoy ADS连接管理;服务实例变更后合并推送EDS,服务列表变化时推送CDS
+/// 只下发xds映射的命名空间与分组下的服务
+#[bean(inject)]
+pub struct XdsManage {
+    naming_addr: Option<Addr<NamingActor>>,
+    enable: bool,
+    namespace_id: Arc<String>,
+    group_name: Arc<String>,
+    streams: HashMap<u64, XdsStream>,
+    cluster_names: BTreeSet<String>,
+    changed_keys: HashSet<ServiceKey>,
+    version: u64,
+    nonce: u64,
+    heartbeat_times: u64,
+}
+
+impl Default for XdsManage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XdsManage {
+    pub fn new() -> Self {
+        Self {
+            naming_addr: None,
+            enable: false,
+            namespace_id: Default::default(),
+            group_name: Default::default(),
+            streams: Default::default(),
+            cluster_names: Default::default(),
+            changed_keys: Default::default(),
+            version: 0,
+            nonce: 0,
+            heartbeat_times: 0,
+        }
+    }
+
+    fn is_watch_key(&self, key: &ServiceKey) -> bool {
+        self.enable && key.namespace_id == self.namespace_id && key.group_name == self.group_name
+    }
+
+    fn heartbeat(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_millis(500), |act, ctx| {
+            act.heartbeat_times += 1;
+            if act.streams.is_empty() {
+                act.changed_keys.clear();
+            } else {
+                act.flush_changed(ctx);
+            }
+            act.heartbeat(ctx);
+        });
+    }
+
+    fn flush_changed(&mut self, ctx: &mut Context<Self>) {
+        let keys: Vec<ServiceKey> = self.changed_keys.drain().collect();
+        let names: Vec<String> = keys.iter().map(XdsModelUtils::build_cluster_name).collect();
+        let has_new_cluster = names.iter().any(|e| !self.cluster_names.contains(e));
+        if has_new_cluster || self.heartbeat_times >= CLUSTER_REFRESH_HEARTBEAT_TIMES {
+            self.heartbeat_times = 0;
+            self.push_clusters(ctx, None);
+        }
+        let names: Vec<String> = names
+            .into_iter()
+            .filter(|e| self.streams.values().any(|s| s.eds_names.contains(e)))
+            .collect();
+        if !names.is_empty() {
+            self.push_load_assignments(ctx, None, names);
+        }
+    }
+
+    fn handle_request(&mut self, ctx: &mut Context<Self>, stream_id: u64, req: DiscoveryRequest) {
+        let stream = match self.streams.get_mut(&stream_id) {
+            Some(v) => v,
+            None => return,
+        };
+        if let Some(node) = &req.node {
+            stream.node_id = node.id.to_owned();
+        }
+        if let Some(error_detail) = &req.error_detail {
+            log::warn!(
+                "xds nack,node:{},type_url:{},nonce:{},error:{}",
+                &stream.node_id,
+                &req.type_url,
+                &req.response_nonce,
+                &error_detail.message
+            );
+            return;
+        }
+        let is_initial = req.response_nonce.is_empty();
+        match req.type_url.as_str() {
+            CLUSTER_TYPE_URL => {
+                stream.cds_watch = true;
+                if is_initial {
+                    self.push_clusters(ctx, Some(stream_id));
+                }
+            }
+            ENDPOINT_TYPE_URL => {
+                let names: HashSet<String> = req.resource_names.into_iter().collect();
+                let push_names: Vec<String> = if is_initial {
+                    names.iter().cloned().collect()
+                } else {
+                    names.difference(&stream.eds_names).cloned().collect()
+                };
+                stream.eds_names = names;
+                if !push_names.is_empty() {
+                    self.push_load_assignments(ctx, Some(stream_id), push_names);
+                }
+            }
+            _ => {
+                log::debug!("xds unsupported type_url:{}", &req.type_url);
+            }
+        }
+    }
+
+    ///
+    /// stream_id为None时推送给所有订阅CDS的连接,且仅在集群列表变化时推送
+    fn push_clusters(&mut self, ctx: &mut Context<Self>, stream_id: Option<u64>) {
+        let naming_addr = match &self.naming_addr {
+            Some(v) => v.clone(),
+            None => return,
+        };
+        let namespace_id = self.namespace_id.clone();
+        let group_name = self.group_name.clone();
+        async move { Self::query_service_keys(naming_addr, namespace_id, group_name).await }
+            .into_actor(self)
+            .map(move |res, act, _| {
+                let keys = match res {
+                    Ok(v) => v,
+                    Err(err) => {
+                        log::warn!("xds query service list error,{}", err);
+                        return;
+                    }
+                };
+                let cluster_names: BTreeSet<String> =
+                    keys.iter().map(XdsModelUtils::build_cluster_name).collect();
+                if stream_id.is_none() && cluster_names == act.cluster_names {
+                    return;
+                }
+                act.cluster_names = cluster_names;
+                let resources = keys
+                    .iter()
+                    .map(|key| {
+                        XdsModelUtils::to_any(CLUSTER_TYPE_URL, &XdsModelUtils::build_cluster(key))
+                    })
+                    .collect::<Vec<_>>();
+                let stream_ids: Vec<u64> = match stream_id {
+                    Some(id) => vec![id],
+                    None => act
+                        .streams
+                        .iter()
+                        .filter(|(_, s)| s.cds_watch)
+                        .map(|(id, _)| *id)
+                        .collect(),
+                };
+                for id in stream_ids {
+                    act.send_response(id, CLUSTER_TYPE_URL, resources.clone());
+                }
+            })
+            .spawn(ctx);
+    }
+
+    ///
+    /// stream_id为None时推送给所有订阅对应集群端点的连接
+    fn push_load_assignments(
+        &mut self,
+        ctx: &mut Context<Self>,
+        stream_id: Option<u64>,
+        names: Vec<String>,
+    ) {
+        let naming_addr = match &self.naming_addr {
+            Some(v) => v.clone(),
+            None => return,
+        };
+        let namespace_id = self.namespace_id.clone();
+        let group_name = self.group_name.clone();
+        async move {
+            Self::query_load_assignments(naming_addr, names, namespace_id, group_name).await
+        }
+            .into_actor(self)
+            .map(move |list, act, _| {
+                let stream_ids: Vec<u64> = match stream_id {
+                    Some(id) => vec![id],
+                    None => act.streams.keys().cloned().collect(),
+                };
+                for id in stream_ids {
+                    let resources = match act.streams.get(&id) {
+                        Some(stream) => list
+                            .iter()
+                            .filter(|e| stream.eds_names.contains(&e.cluster_name))
+                            .map(|e| XdsModelUtils::to_any(ENDPOINT_TYPE_URL, e))
+                            .collect::<Vec<_>>(),
+                        None => continue,
+                    };
+                    if !resources.is_empty() {
+                        act.send_response(id, ENDPOINT_TYPE_URL, resources);
+                    }
+                }
+            })
+            .spawn(ctx);
+    }
+
+    fn send_response(&mut self, stream_id: u64, type_url: &str, resources: Vec<Any>) {
+        self.version += 1;
+        self.nonce += 1;
+        let response = DiscoveryResponse {
+            version_info: self.version.to_string(),
+            resources,
+            type_url: type_url.to_owned(),
+            nonce: self.nonce.to_string(),
+        };
+        let closed = match self.streams.get(&stream_id) {
+            Some(stream) => match stream.sender.try_send(Ok(response)) {
+                Ok(_) => false,
+                Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                    log::warn!("xds stream is full,node:{}", &stream.node_id);
+                    false
+                }
+                Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => true,
+            },
+            None => false,
+        };
+        if closed {
+            self.streams.remove(&stream_id);
+        }
+    }
+
+    async fn query_service_keys(
+        naming_addr: Addr<NamingActor>,
+        namespace_id: Arc<String>,
+        group_name: Arc<String>,
+    ) -> anyhow::Result<Vec<ServiceKey>> {
+        let param = ServiceQueryParam {
+            namespace_id: Some(namespace_id),
+            group: Some(group_name),
+            limit: usize::MAX,
+            ..Default::default()
+        };
+        match naming_addr
+            .send(NamingCmd::QueryServiceKeyList(param))
+            .await??
+        {
+            NamingResult::ServiceKeyList(list) => Ok(list),
+            _ => Ok(vec![]),
+        }
+    }
+
+    ///
+    /// 没有实例、查询失败或不在映射分组下的集群下发空的端点列表
+    async fn query_load_assignments(
+        naming_addr: Addr<NamingActor>,
+        names: Vec<String>,
+        namespace_id: Arc<String>,
+        group_name: Arc<String>,
+    ) -> Vec<ClusterLoadAssignment> {
+        let mut list = Vec::with_capacity(names.len());
+        for name in names {
+            let instances = match XdsModelUtils::parse_cluster_name(&name) {
+                Some(key) if key.namespace_id == namespace_id && key.group_name == group_name => {
+                    let cmd = NamingCmd::QueryList(key, "".to_owned(), false, None);
+                    match naming_addr.send(cmd).await {
+                        Ok(Ok(NamingResult::InstanceList(v))) => v,
+                        _ => vec![],
+                    }
+                }
+                _ => vec![],
+            };
+            list.push(XdsModelUtils::build_load_assignment(name, &instances));
+        }
+        list
+    }
+}
+
+impl Actor for XdsManage {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("XdsManage started");
+    }
+}
+
+impl Inject for XdsManage {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.naming_addr = factory_data.get_actor();
+        if let Some(sys_config) = factory_data.get_bean::<AppSysConfig>() {
+            self.enable = sys_config.xds_enable;
+            self.namespace_id = sys_config.xds_namespace.clone();
+            self.group_name = sys_config.xds_group.clone();
+        }
+        if self.enable {
+            self.heartbeat(ctx);
+        }
+        log::info!("XdsManage inject complete");
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "anyhow::Result<XdsResult>")]
+pub enum XdsCmd {
+    AddStream(u64, XdsSender),
+    RemoveStream(u64),
+    Request(u64, DiscoveryRequest),
+    ServiceChanged(ServiceKey),
+}
+
+pub enum XdsResult {
+    None,
+}
+
+impl Handler<XdsCmd> for XdsManage {
+    type Result = anyhow::Result<XdsResult>;
+
+    fn handle(&mut self, msg: XdsCmd, ctx: &mut Context<Self>) -> Self::Result {
+        match msg {
+            XdsCmd::AddStream(stream_id, sender) => {
+                self.streams.insert(stream_id, XdsStream::new(sender));
+            }
+            XdsCmd::RemoveStream(stream_id) => {
+                self.streams.remove(&stream_id);
+            }
+            XdsCmd::Request(stream_id, req) => {
+                self.handle_request(ctx, stream_id, req);
+            }
+            XdsCmd::ServiceChanged(key) => {
+                if !self.streams.is_empty() && self.is_watch_key(&key) {
+                    self.changed_keys.insert(key);
+                }
+            }
+        }
+        Ok(XdsResult::None)
+    }
+}
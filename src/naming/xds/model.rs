@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use prost::Message;
+
+use crate::grpc::envoy_xds_proto::{
+    address, cluster, config_source, lb_endpoint, socket_address, value, Address,
+    AggregatedConfigSource, Any, ApiVersion, Cluster, ClusterLoadAssignment, ConfigSource,
+    Duration, Endpoint, HealthStatus, LbEndpoint, Locality, LocalityLbEndpoints, Metadata,
+    SocketAddress, Struct, UInt32Value, Value,
+};
+use crate::naming::model::{Instance, ServiceKey};
+
+pub const CLUSTER_TYPE_URL: &str = "type.googleapis.com/envoy.config.cluster.v3.Cluster";
+pub const ENDPOINT_TYPE_URL: &str =
+    "type.googleapis.com/envoy.config.endpoint.v3.ClusterLoadAssignment";
+
+const CLUSTER_NAME_SPLIT: &str = "@@";
+const CONNECT_TIMEOUT_SECONDS: i64 = 5;
+const WEIGHT_SCALE: f32 = 100f32;
+const LB_FILTER_METADATA_KEY: &str = "envoy.lb";
+const LOCALITY_REGION_KEY: &str = "region";
+const LOCALITY_ZONE_KEY: &str = "zone";
+const LOCALITY_SUB_ZONE_KEY: &str = "sub_zone";
+
+pub struct XdsModelUtils;
+
+impl XdsModelUtils {
+    ///
+    /// 集群名为`<namespace>@@<group>@@<service>`
+    pub fn build_cluster_name(key: &ServiceKey) -> String {
+        format!(
+            "{}{}{}{}{}",
+            &key.namespace_id,
+            CLUSTER_NAME_SPLIT,
+            &key.group_name,
+            CLUSTER_NAME_SPLIT,
+            &key.service_name
+        )
+    }
+
+    pub fn parse_cluster_name(name: &str) -> Option<ServiceKey> {
+        let mut iter = name.splitn(3, CLUSTER_NAME_SPLIT);
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(namespace_id), Some(group), Some(service))
+                if !namespace_id.is_empty() && !group.is_empty() && !service.is_empty() =>
+            {
+                Some(ServiceKey::new(namespace_id, group, service))
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// 服务对应的EDS集群,端点通过ADS下发
+    pub fn build_cluster(key: &ServiceKey) -> Cluster {
+        let name = Self::build_cluster_name(key);
+        let eds_config = ConfigSource {
+            resource_api_version: ApiVersion::V3 as i32,
+            config_source_specifier: Some(config_source::ConfigSourceSpecifier::Ads(
+                AggregatedConfigSource {},
+            )),
+        };
+        Cluster {
+            eds_cluster_config: Some(cluster::EdsClusterConfig {
+                eds_config: Some(eds_config),
+                service_name: name.clone(),
+            }),
+            name,
+            connect_timeout: Some(Duration {
+                seconds: CONNECT_TIMEOUT_SECONDS,
+                nanos: 0,
+            }),
+            lb_policy: cluster::LbPolicy::RoundRobin as i32,
+            cluster_discovery_type: Some(cluster::ClusterDiscoveryType::Type(
+                cluster::DiscoveryType::Eds as i32,
+            )),
+        }
+    }
+
+    ///
+    /// 实例按元数据中的region、zone、sub_zone分组为locality;权重为0的实例不下发
+    pub fn build_load_assignment(
+        cluster_name: String,
+        instances: &[Arc<Instance>],
+    ) -> ClusterLoadAssignment {
+        let mut locality_map: BTreeMap<(String, String, String), Vec<LbEndpoint>> = BTreeMap::new();
+        for instance in instances {
+            let weight = (instance.weight * WEIGHT_SCALE).round();
+            if weight < 1f32 {
+                continue;
+            }
+            let locality = (
+                Self::get_metadata_value(instance, LOCALITY_REGION_KEY),
+                Self::get_metadata_value(instance, LOCALITY_ZONE_KEY),
+                Self::get_metadata_value(instance, LOCALITY_SUB_ZONE_KEY),
+            );
+            locality_map
+                .entry(locality)
+                .or_default()
+                .push(Self::build_lb_endpoint(instance, weight as u32));
+        }
+        let endpoints = locality_map
+            .into_iter()
+            .map(
+                |((region, zone, sub_zone), lb_endpoints)| LocalityLbEndpoints {
+                    locality: Some(Locality {
+                        region,
+                        zone,
+                        sub_zone,
+                    }),
+                    lb_endpoints,
+                    load_balancing_weight: None,
+                    priority: 0,
+                },
+            )
+            .collect();
+        ClusterLoadAssignment {
+            cluster_name,
+            endpoints,
+        }
+    }
+
+    fn build_lb_endpoint(instance: &Instance, weight: u32) -> LbEndpoint {
+        let socket_address = SocketAddress {
+            protocol: socket_address::Protocol::Tcp as i32,
+            address: instance.ip.as_ref().to_owned(),
+            port_specifier: Some(socket_address::PortSpecifier::PortValue(instance.port)),
+        };
+        let endpoint = Endpoint {
+            address: Some(Address {
+                address: Some(address::Address::SocketAddress(socket_address)),
+            }),
+            hostname: "".to_owned(),
+        };
+        let health_status = if instance.healthy {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Unhealthy
+        };
+        let metadata = if instance.metadata.is_empty() {
+            None
+        } else {
+            let fields = instance
+                .metadata
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.to_owned(),
+                        Value {
+                            kind: Some(value::Kind::StringValue(v.to_owned())),
+                        },
+                    )
+                })
+                .collect();
+            let mut filter_metadata = HashMap::new();
+            filter_metadata.insert(LB_FILTER_METADATA_KEY.to_owned(), Struct { fields });
+            Some(Metadata { filter_metadata })
+        };
+        LbEndpoint {
+            health_status: health_status as i32,
+            metadata,
+            load_balancing_weight: Some(UInt32Value { value: weight }),
+            host_identifier: Some(lb_endpoint::HostIdentifier::Endpoint(endpoint)),
+        }
+    }
+
+    fn get_metadata_value(instance: &Instance, key: &str) -> String {
+        instance.metadata.get(key).cloned().unwrap_or_default()
+    }
+
+    pub fn to_any<M: Message>(type_url: &str, message: &M) -> Any {
+        let mut value = Vec::with_capacity(message.encoded_len());
+        message.encode(&mut value).ok();
+        Any {
+            type_url: type_url.to_owned(),
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xds_load_assignment() {
+        let key = ServiceKey::new("public", "DEFAULT_GROUP", "svc@@a");
+        let cluster_name = XdsModelUtils::build_cluster_name(&key);
+        assert_eq!(cluster_name, "public@@DEFAULT_GROUP@@svc@@a");
+        assert_eq!(XdsModelUtils::parse_cluster_name(&cluster_name), Some(key));
+        assert!(XdsModelUtils::parse_cluster_name("public@@svc").is_none());
+
+        let mut instances = vec![];
+        for (ip, weight, zone) in [
+            ("10.0.0.1", 1f32, "z1"),
+            ("10.0.0.2", 0.5f32, "z2"),
+            ("10.0.0.3", 0f32, "z1"),
+        ] {
+            let mut instance = Instance::new(ip.to_owned(), 80);
+            instance.weight = weight;
+            instance.healthy = true;
+            let mut metadata = HashMap::new();
+            metadata.insert(LOCALITY_ZONE_KEY.to_owned(), zone.to_owned());
+            instance.metadata = Arc::new(metadata);
+            instances.push(Arc::new(instance));
+        }
+        let cla = XdsModelUtils::build_load_assignment(cluster_name, &instances);
+        assert_eq!(cla.endpoints.len(), 2);
+        assert_eq!(cla.endpoints[0].locality.as_ref().unwrap().zone, "z1");
+        assert_eq!(cla.endpoints[0].lb_endpoints.len(), 1);
+        let lb_endpoint = &cla.endpoints[1].lb_endpoints[0];
+        assert_eq!(
+            lb_endpoint.load_balancing_weight.as_ref().unwrap().value,
+            50
+        );
+        assert_eq!(lb_endpoint.health_status, HealthStatus::Healthy as i32);
+    }
+}
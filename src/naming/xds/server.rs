@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio_stream::StreamExt;
+
+use crate::common::appdata::AppShareData;
+use crate::common::constant::{ACCESS_TOKEN_HEADER, AUTHORIZATION_HEADER};
+use crate::grpc::envoy_xds_proto::aggregated_discovery_service_server::AggregatedDiscoveryService;
+use crate::grpc::envoy_xds_proto::{DiscoveryRequest, DiscoveryResponse};
+use crate::grpc::server::get_user_session;
+use crate::raft::cache::model::{CacheKey, CacheType};
+use crate::raft::cache::CacheManagerReq;
+
+use super::core::XdsCmd;
+
+pub struct AggregatedDiscoveryServiceImpl {
+    app: Arc<AppShareData>,
+    stream_id: AtomicU64,
+}
+
+impl AggregatedDiscoveryServiceImpl {
+    pub fn new(app: Arc<AppShareData>) -> Self {
+        Self {
+            app,
+            stream_id: AtomicU64::new(1),
+        }
+    }
+
+    ///
+    /// 开启鉴权时校验metadata中的accessToken,及对xds映射命名空间与分组的读权限
+    async fn check_auth(
+        &self,
+        metadata: &tonic::metadata::MetadataMap,
+    ) -> Result<(), tonic::Status> {
+        if !self.app.sys_config.openapi_enable_auth {
+            return Ok(());
+        }
+        let token = metadata
+            .get(ACCESS_TOKEN_HEADER)
+            .or_else(|| metadata.get(AUTHORIZATION_HEADER))
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if token.is_empty() {
+            return Err(tonic::Status::unauthenticated("unknown user!"));
+        }
+        let session = match get_user_session(
+            &self.app.cache_manager,
+            CacheManagerReq::Get(CacheKey::new(
+                CacheType::ApiTokenSession,
+                Arc::new(token.to_owned()),
+            )),
+        )
+        .await
+        {
+            Ok(Some(session)) => session,
+            _ => return Err(tonic::Status::unauthenticated("unknown user!")),
+        };
+        if let Some(privilege) = &session.namespace_privilege {
+            if !privilege.check(
+                &self.app.sys_config.xds_namespace,
+                Some(&self.app.sys_config.xds_group),
+                false,
+            ) {
+                return Err(tonic::Status::permission_denied("no namespace privilege!"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl AggregatedDiscoveryService for AggregatedDiscoveryServiceImpl {
+    type StreamAggregatedResourcesStream =
+        tokio_stream::wrappers::ReceiverStream<Result<DiscoveryResponse, tonic::Status>>;
+
+    async fn stream_aggregated_resources(
+        &self,
+        request: tonic::Request<tonic::Streaming<DiscoveryRequest>>,
+    ) -> Result<tonic::Response<Self::StreamAggregatedResourcesStream>, tonic::Status> {
+        self.check_auth(request.metadata()).await?;
+        let stream_id = self.stream_id.fetch_add(1, Ordering::Relaxed);
+        let mut req_stream = request.into_inner();
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let xds_manage = self.app.xds_manage.clone();
+        xds_manage.do_send(XdsCmd::AddStream(stream_id, tx));
+        tokio::spawn(async move {
+            while let Some(Ok(req)) = req_stream.next().await {
+                xds_manage.do_send(XdsCmd::Request(stream_id, req));
+            }
+            xds_manage.do_send(XdsCmd::RemoveStream(stream_id));
+        });
+        Ok(tonic::Response::new(
+            tokio_stream::wrappers::ReceiverStream::new(rx),
+        ))
+    }
+}
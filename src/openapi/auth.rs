@@ -93,6 +93,8 @@ async fn do_login(
                     + &uuid::Uuid::new_v4().to_string().replace('-', ""),
             );
             let session = Arc::new(TokenSession {
+                namespace_privilege: user.get_session_privilege(),
+                config_decrypt: user.has_config_decrypt(),
                 username: user.username,
                 roles: user.roles.unwrap_or_default(),
                 extend_infos: user.extend_info.unwrap_or_default(),
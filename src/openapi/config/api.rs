@@ -11,15 +11,20 @@ use crate::common::appdata::AppShareData;
 use crate::common::model::ApiResult;
 use crate::common::option_utils::OptionUtils;
 use crate::common::string_utils::StringUtils;
-use crate::common::web_utils::get_req_body;
+use crate::common::web_utils::{
+    can_decrypt_config, get_client_ip, get_req_body, get_session_privilege,
+};
 use crate::config::config_index::ConfigQueryParam;
+use crate::config::config_subscribe::ConfigListenerQueryParam;
 use crate::config::config_type::ConfigType;
 use crate::config::core::{
     ConfigActor, ConfigCmd, ConfigInfoDto, ConfigKey, ConfigResult, ListenerItem, ListenerResult,
 };
+use crate::config::model::ConfigCasConflictError;
 use crate::config::utils::param_utils;
 use crate::config::ConfigUtils;
 use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::console::NamespaceUtils;
 use crate::merge_web_param;
 use crate::openapi::constant::EMPTY;
 use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
@@ -34,7 +39,11 @@ pub(super) fn service() -> Scope {
                 .route(web::put().to(add_config))
                 .route(web::delete().to(del_config)),
         )
-        .service(web::resource("/listener").route(web::post().to(listener_config)))
+        .service(
+            web::resource("/listener")
+                .route(web::get().to(query_config_listener))
+                .route(web::post().to(listener_config)),
+        )
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,6 +55,10 @@ pub struct ConfigWebParams {
     pub content: Option<String>,
     pub desc: Option<String>,
     pub r#type: Option<String>,
+    pub app_name: Option<String>,
+    #[serde(rename = "config_tags")]
+    pub config_tags: Option<String>,
+    pub cas_md5: Option<String>,
     pub search: Option<String>,   //search type
     pub page_no: Option<usize>,   //use at search
     pub page_size: Option<usize>, //use at search
@@ -83,6 +96,7 @@ impl From<ConfigInfoDto> for ConfigInfo {
             data_id: v.data_id,
             content: v.content,
             md5: v.md5,
+            app_name: v.app_name.map(|e| e.as_ref().to_owned()),
             ..Default::default()
         }
     }
@@ -97,6 +111,9 @@ impl ConfigWebParams {
             content: OptionUtils::select(self.content, other.content),
             desc: OptionUtils::select(self.desc, other.desc),
             r#type: OptionUtils::select(self.r#type, other.r#type),
+            app_name: OptionUtils::select(self.app_name, other.app_name),
+            config_tags: OptionUtils::select(self.config_tags, other.config_tags),
+            cas_md5: OptionUtils::select(self.cas_md5, other.cas_md5),
             search: OptionUtils::select(self.search, other.search),
             page_no: OptionUtils::select(self.page_no, other.page_no),
             page_size: OptionUtils::select(self.page_size, other.page_size),
@@ -139,6 +156,8 @@ impl ConfigWebParams {
             offset,
             like_group: self.group,
             like_data_id: self.data_id,
+            like_app_name: self.app_name,
+            config_tags: self.config_tags,
             query_context: true,
             ..Default::default()
         };
@@ -156,6 +175,8 @@ impl ConfigWebParams {
             offset,
             group: self.group.map(Arc::new),
             data_id: self.data_id.map(Arc::new),
+            app_name: self.app_name,
+            config_tags: self.config_tags,
             query_context: true,
             ..Default::default()
         };
@@ -175,6 +196,7 @@ pub struct ConfigWebConfirmedParam {
 }
 
 pub(crate) async fn add_config(
+    req: HttpRequest,
     a: web::Query<ConfigWebParams>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
@@ -201,6 +223,15 @@ pub(crate) async fn add_config(
 
     let config_type = StringUtils::map_not_empty(selected_param.r#type.clone());
     let desc = StringUtils::map_not_empty(selected_param.desc.clone());
+    let app_name = selected_param.app_name.clone().map(Arc::new);
+    let config_tags = selected_param.config_tags.clone().map(Arc::new);
+    //兼容nacos v1客户端通过header传递casMd5
+    let cas_md5 = StringUtils::map_not_empty(selected_param.cas_md5.clone()).or_else(|| {
+        req.headers()
+            .get("casMd5")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| StringUtils::map_not_empty(Some(v.to_owned())))
+    });
     let param = selected_param.to_confirmed_param();
     match param {
         Ok(p) => {
@@ -210,10 +241,22 @@ pub(crate) async fn add_config(
             );
             req.config_type = config_type.map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
             req.desc = desc.map(Arc::new);
+            req.app_name = app_name;
+            req.config_tags = config_tags;
+            req.cas_md5 = cas_md5.map(Arc::new);
+            if let Err(err) =
+                NamespaceUtils::check_config_publish(&appdata.config_addr, &req.config_key.tenant)
+                    .await
+            {
+                return HttpResponse::Forbidden().body(err.to_string());
+            }
             match appdata.config_route.set_config(req).await {
                 Ok(_) => HttpResponse::Ok()
                     .content_type("text/html; charset=utf-8")
                     .body("true"),
+                Err(err) if ConfigCasConflictError::is_conflict(&err) => {
+                    HttpResponse::Conflict().body(err.to_string())
+                }
                 Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
             }
         }
@@ -250,6 +293,12 @@ pub(crate) async fn del_config(
     match param {
         Ok(p) => {
             let req = DelConfigReq::new(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
+            if let Err(err) =
+                NamespaceUtils::check_config_publish(&appdata.config_addr, &req.config_key.tenant)
+                    .await
+            {
+                return HttpResponse::Forbidden().body(err.to_string());
+            }
             match appdata.config_route.del_config(req).await {
                 Ok(_) => HttpResponse::Ok()
                     .content_type("text/html; charset=utf-8")
@@ -262,32 +311,48 @@ pub(crate) async fn del_config(
 }
 
 pub(crate) async fn get_config(
+    req: HttpRequest,
     web_param: web::Query<ConfigWebParams>,
     appdata: web::Data<Arc<AppShareData>>,
 ) -> impl Responder {
     if let Some(search) = web_param.search.as_ref() {
         if search == "blur" {
-            let query_param = web_param.0.build_like_search_param();
+            let mut query_param = web_param.0.build_like_search_param();
+            query_param.decrypt = can_decrypt_config(&req);
+            query_param.privilege = get_session_privilege(&req);
             return do_search_config(query_param, appdata).await;
         } else if search == "accurate" {
-            let query_param = web_param.0.build_search_param();
+            let mut query_param = web_param.0.build_search_param();
+            query_param.decrypt = can_decrypt_config(&req);
+            query_param.privilege = get_session_privilege(&req);
             return do_search_config(query_param, appdata).await;
         }
     };
     let param = web_param.to_confirmed_param();
     match param {
         Ok(p) => {
-            let cmd = ConfigCmd::GET(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
+            let cmd = ConfigCmd::GetByClient(
+                ConfigKey::new(&p.data_id, &p.group, &p.tenant),
+                get_client_ip(&req),
+                can_decrypt_config(&req),
+            );
             match appdata.config_addr.send(cmd).await {
                 Ok(res) => {
-                    let r: ConfigResult = res.unwrap();
+                    let r: ConfigResult = match res {
+                        Ok(r) => r,
+                        Err(err) => {
+                            return HttpResponse::InternalServerError().body(err.to_string())
+                        }
+                    };
                     match r {
                         ConfigResult::Data {
                             value: v,
                             md5,
                             config_type,
+                            beta,
                             ..
                         } => HttpResponse::Ok()
+                            .insert_header(("isBeta", beta.to_string()))
                             .content_type(
                                 config_type
                                     .map(|v| ConfigType::new_by_value(&v))
@@ -397,7 +462,7 @@ pub(super) async fn listener_config(
         }
     }
     //println!("timeout header:{:?},time_out:{}",_req.headers().get("Long-Pulling-Timeout") ,time_out);
-    let cmd = ConfigCmd::LISTENER(list, tx, time_out);
+    let cmd = ConfigCmd::LISTENER(list, tx, time_out, get_client_ip(&_req));
     let _ = config_addr.send(cmd).await;
     let res = rx.await.unwrap();
     let v = match res {
@@ -418,3 +483,108 @@ pub(super) async fn listener_config(
         .content_type("text/html; charset=utf-8")
         .body(v)
 }
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigListenerWebParams {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub tenant: Option<String>,
+    pub ip: Option<String>,
+}
+
+///
+/// 兼容nacos的监听查询结果,按配置查询时key为客户端ip,按客户端查询时key为配置的groupKey
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupkeyListenerStatus {
+    pub collect_status: i32,
+    pub lisenters_groupkey_status: HashMap<String, String>,
+}
+
+///
+/// 查询监听指定配置的客户端及其上报的md5
+pub(super) async fn query_config_listener(
+    web::Query(param): web::Query<ConfigListenerWebParams>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if let Err(err) = param_utils::check_tenant(&param.tenant) {
+        return HttpResponse::BadRequest().body(err.to_string());
+    }
+    let data_id = param.data_id.unwrap_or_default();
+    let group = param.group.unwrap_or_default();
+    let key = ConfigKey::new(
+        &data_id,
+        &group,
+        &ConfigUtils::default_tenant(param.tenant.unwrap_or_default()),
+    );
+    if let Err(err) = key.is_valid() {
+        return HttpResponse::BadRequest().body(err.to_string());
+    }
+    let query_param = ConfigListenerQueryParam {
+        key: Some(key.build_key()),
+        client_id: None,
+        client_ip: StringUtils::map_not_empty(param.ip).map(Arc::new),
+    };
+    match appdata
+        .config_route
+        .query_listener(query_param, &appdata.naming_node_manage)
+        .await
+    {
+        Ok(list) => {
+            let mut result = GroupkeyListenerStatus {
+                collect_status: 200,
+                ..Default::default()
+            };
+            for item in list {
+                result.lisenters_groupkey_status.insert(
+                    item.client_ip.as_ref().to_owned(),
+                    item.md5.as_ref().to_owned(),
+                );
+            }
+            HttpResponse::Ok().json(result)
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 查询指定客户端ip监听的配置及其上报的md5
+pub(super) async fn query_client_listener(
+    web::Query(param): web::Query<ConfigListenerWebParams>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let ip = match StringUtils::map_not_empty(param.ip) {
+        Some(v) => Arc::new(v),
+        None => return HttpResponse::BadRequest().body("the ip is empty"),
+    };
+    let query_param = ConfigListenerQueryParam {
+        key: None,
+        client_id: None,
+        client_ip: Some(ip),
+    };
+    match appdata
+        .config_route
+        .query_listener(query_param, &appdata.naming_node_manage)
+        .await
+    {
+        Ok(list) => {
+            let mut result = GroupkeyListenerStatus {
+                collect_status: 200,
+                ..Default::default()
+            };
+            for item in list {
+                let mut group_key = format!("{}+{}", &item.data_id, &item.group);
+                if !item.tenant.is_empty() {
+                    group_key.push('+');
+                    group_key.push_str(&item.tenant);
+                }
+                result
+                    .lisenters_groupkey_status
+                    .insert(group_key, item.md5.as_ref().to_owned());
+            }
+            HttpResponse::Ok().json(result)
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
@@ -1,16 +1,22 @@
 use actix_web::{web, Scope};
 
-use crate::openapi::constant::CONFIG_V1_BASE_PATH;
+use crate::openapi::constant::{CONFIG_V1_BASE_PATH, CONFIG_V2_BASE_PATH};
 use crate::openapi::RouteConf;
 
 pub mod api;
 pub mod v2;
 
-/// current implement for version 1
+/// current implement for version 1 and version 2
 pub fn openapi_service(conf: RouteConf) -> Vec<Scope> {
-    vec![openapi_v1_route(conf)]
+    vec![openapi_v1_route(conf.clone()), openapi_v2_route(conf)]
 }
 
 pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
-    web::scope(CONFIG_V1_BASE_PATH).service(api::service())
+    web::scope(CONFIG_V1_BASE_PATH)
+        .service(api::service())
+        .service(web::resource("/listener").route(web::get().to(api::query_client_listener)))
+}
+
+pub fn openapi_v2_route(_conf: RouteConf) -> Scope {
+    web::scope(CONFIG_V2_BASE_PATH).service(v2::api::service())
 }
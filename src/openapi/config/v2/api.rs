@@ -1 +1,398 @@
+use std::sync::Arc;
 
+use actix_web::{web, HttpRequest, Responder, Scope};
+use serde::{Deserialize, Serialize};
+
+use crate::common::appdata::AppShareData;
+use crate::common::option_utils::OptionUtils;
+use crate::common::string_utils::StringUtils;
+use crate::common::web_utils::{can_decrypt_config, get_client_ip, get_session_privilege};
+use crate::config::config_index::ConfigQueryParam;
+use crate::config::config_type::ConfigType;
+use crate::config::core::{
+    ConfigCmd, ConfigHistoryCmd, ConfigHistoryInfoDto, ConfigInfoDto, ConfigKey, ConfigResult,
+};
+use crate::config::dal::ConfigHistoryParam;
+use crate::config::model::ConfigCasConflictError;
+use crate::config::utils::param_utils;
+use crate::config::ConfigUtils;
+use crate::console::NamespaceUtils;
+use crate::merge_web_param;
+use crate::naming::DEFAULT_GROUP;
+use crate::openapi::config::api::ConfigSearchPage;
+use crate::openapi::constant::EMPTY;
+use crate::openapi::v2::model::{
+    V2Result, ACCESS_DENIED, PARAMETER_MISSING, PARAMETER_VALIDATE_ERROR, RESOURCE_CONFLICT,
+    RESOURCE_NOT_FOUND, SERVER_ERROR,
+};
+use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
+
+pub(crate) fn service() -> Scope {
+    web::scope(EMPTY)
+        .service(
+            web::resource("/config")
+                .route(web::get().to(get_config))
+                .route(web::post().to(publish_config))
+                .route(web::delete().to(delete_config)),
+        )
+        .service(web::resource("/config/search").route(web::get().to(search_config)))
+        .service(web::resource("/history").route(web::get().to(get_history)))
+        .service(web::resource("/history/list").route(web::get().to(query_history_list)))
+        .service(web::resource("/history/previous").route(web::get().to(get_previous_history)))
+        .service(web::resource("/history/configs").route(web::get().to(query_namespace_configs)))
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigV2Params {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub namespace_id: Option<String>,
+    pub content: Option<String>,
+    pub tag: Option<String>,
+    pub app_name: Option<String>,
+    pub src_user: Option<String>,
+    pub config_tags: Option<String>,
+    pub desc: Option<String>,
+    pub r#type: Option<String>,
+    pub cas_md5: Option<String>,
+}
+
+impl ConfigV2Params {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            data_id: OptionUtils::select(self.data_id, other.data_id),
+            group: OptionUtils::select(self.group, other.group),
+            namespace_id: OptionUtils::select(self.namespace_id, other.namespace_id),
+            content: OptionUtils::select(self.content, other.content),
+            tag: OptionUtils::select(self.tag, other.tag),
+            app_name: OptionUtils::select(self.app_name, other.app_name),
+            src_user: OptionUtils::select(self.src_user, other.src_user),
+            config_tags: OptionUtils::select(self.config_tags, other.config_tags),
+            desc: OptionUtils::select(self.desc, other.desc),
+            r#type: OptionUtils::select(self.r#type, other.r#type),
+            cas_md5: OptionUtils::select(self.cas_md5, other.cas_md5),
+        }
+    }
+
+    fn get_group(&self) -> String {
+        StringUtils::map_not_empty(self.group.clone()).unwrap_or(DEFAULT_GROUP.to_owned())
+    }
+
+    fn get_tenant(&self) -> String {
+        ConfigUtils::default_tenant(self.namespace_id.clone().unwrap_or_default())
+    }
+
+    fn to_config_key(&self) -> Result<ConfigKey, String> {
+        let data_id = self.data_id.clone().unwrap_or_default();
+        if data_id.is_empty() {
+            return Err("Required parameter 'dataId' type String is not present".to_owned());
+        }
+        Ok(ConfigKey::new(
+            &data_id,
+            &self.get_group(),
+            &self.get_tenant(),
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSearchV2Params {
+    pub namespace_id: Option<String>,
+    pub group: Option<String>,
+    pub data_id: Option<String>,
+    pub r#type: Option<String>,
+    pub keyword: Option<String>,
+    pub regex: Option<bool>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl ConfigSearchV2Params {
+    fn into_query_param(self) -> Result<ConfigQueryParam, String> {
+        let keyword = StringUtils::map_not_empty(self.keyword)
+            .ok_or_else(|| "Required parameter 'keyword' type String is not present".to_owned())?;
+        let page_size = self.page_size.unwrap_or(100).max(1);
+        let page_no = self.page_no.unwrap_or(1).max(1);
+        Ok(ConfigQueryParam {
+            tenant: Some(Arc::new(ConfigUtils::default_tenant(
+                self.namespace_id.unwrap_or_default(),
+            ))),
+            group: StringUtils::map_not_empty(self.group).map(Arc::new),
+            like_data_id: self.data_id,
+            config_type: StringUtils::map_not_empty(self.r#type)
+                .map(|v| ConfigType::new_by_value(&v).get_value().as_ref().to_owned()),
+            content_keyword: Some(keyword),
+            content_regex: self.regex.unwrap_or_default(),
+            offset: (page_no - 1) * page_size,
+            limit: page_size,
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryV2Params {
+    pub nid: Option<i64>,
+    pub id: Option<i64>,
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub namespace_id: Option<String>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl HistoryV2Params {
+    fn to_history_param(&self) -> Result<ConfigHistoryParam, String> {
+        let data_id = self.data_id.clone().unwrap_or_default();
+        if data_id.is_empty() {
+            return Err("Required parameter 'dataId' type String is not present".to_owned());
+        }
+        let group =
+            StringUtils::map_not_empty(self.group.clone()).unwrap_or(DEFAULT_GROUP.to_owned());
+        Ok(ConfigHistoryParam {
+            data_id: Some(data_id),
+            group: Some(group),
+            tenant: Some(ConfigUtils::default_tenant(
+                self.namespace_id.clone().unwrap_or_default(),
+            )),
+            offset: Some(0),
+            ..Default::default()
+        })
+    }
+}
+
+pub(crate) async fn get_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let config_key = match param.to_config_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    let cmd = ConfigCmd::GetByClient(config_key, get_client_ip(&req), can_decrypt_config(&req));
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::Data { value, .. })) => V2Result::ok_response(value),
+        Ok(_) => RESOURCE_NOT_FOUND.to_response("config data not exist".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn publish_config(
+    web::Query(param): web::Query<ConfigV2Params>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    let config_key = match param.to_config_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    if let Err(e) = param_utils::check_tenant(&param.namespace_id) {
+        return PARAMETER_VALIDATE_ERROR.to_response(e.to_string());
+    }
+    if let Err(e) = param_utils::check_param(
+        &param.data_id,
+        &Some(param.get_group()),
+        &Some(String::from("datumId")),
+        &param.content,
+    ) {
+        return PARAMETER_VALIDATE_ERROR.to_response(e.to_string());
+    }
+    let mut req = SetConfigReq::new(config_key, Arc::new(param.content.unwrap_or_default()));
+    req.op_user = StringUtils::map_not_empty(param.src_user).map(Arc::new);
+    req.config_type = StringUtils::map_not_empty(param.r#type)
+        .map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
+    req.desc = StringUtils::map_not_empty(param.desc).map(Arc::new);
+    req.app_name = param.app_name.map(Arc::new);
+    req.config_tags = param.config_tags.map(Arc::new);
+    req.cas_md5 = StringUtils::map_not_empty(param.cas_md5).map(Arc::new);
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &req.config_key.tenant).await
+    {
+        return ACCESS_DENIED.to_response(e.to_string());
+    }
+    match appdata.config_route.set_config(req).await {
+        Ok(_) => V2Result::ok_response(true),
+        Err(e) if ConfigCasConflictError::is_conflict(&e) => {
+            RESOURCE_CONFLICT.to_response(e.to_string())
+        }
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn delete_config(
+    web::Query(param): web::Query<ConfigV2Params>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    let config_key = match param.to_config_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    if let Err(e) = param_utils::check_tenant(&param.namespace_id) {
+        return PARAMETER_VALIDATE_ERROR.to_response(e.to_string());
+    }
+    if let Err(e) =
+        NamespaceUtils::check_config_publish(&appdata.config_addr, &config_key.tenant).await
+    {
+        return ACCESS_DENIED.to_response(e.to_string());
+    }
+    match appdata
+        .config_route
+        .del_config(DelConfigReq::new(config_key))
+        .await
+    {
+        Ok(_) => V2Result::ok_response(true),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+async fn query_histories(
+    req: &HttpRequest,
+    appdata: &web::Data<Arc<AppShareData>>,
+    mut param: ConfigHistoryParam,
+) -> anyhow::Result<(usize, Vec<ConfigHistoryInfoDto>)> {
+    param.decrypt = can_decrypt_config(req);
+    let cmd = ConfigHistoryCmd::QueryHistoryPageInfo(Box::new(param));
+    match appdata.config_addr.send(cmd).await?? {
+        ConfigResult::ConfigHistoryInfoPage(total_count, list) => Ok((total_count, list)),
+        _ => Err(anyhow::anyhow!("config history query result type error")),
+    }
+}
+
+pub(crate) async fn query_history_list(
+    req: HttpRequest,
+    web::Query(param): web::Query<HistoryV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let mut history_param = match param.to_history_param() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    let page_no = param.page_no.unwrap_or(1).max(1);
+    let page_size = param.page_size.unwrap_or(100).clamp(1, 500);
+    history_param.offset = Some(((page_no - 1) * page_size) as i64);
+    history_param.limit = Some(page_size as i64);
+    match query_histories(&req, &appdata, history_param).await {
+        Ok((total_count, list)) => V2Result::ok_response(ConfigSearchPage {
+            total_count: Some(total_count),
+            page_number: Some(page_no),
+            pages_available: Some(total_count.div_ceil(page_size)),
+            page_items: Some(list),
+        }),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+///
+/// 按配置内容搜索,返回匹配的配置及行号
+pub(crate) async fn search_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigSearchV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let mut query_param = match param.into_query_param() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    query_param.decrypt = can_decrypt_config(&req);
+    query_param.privilege = get_session_privilege(&req);
+    let page_size = query_param.limit;
+    let page_no = query_param.offset / page_size + 1;
+    let cmd = ConfigCmd::SearchContent(Box::new(query_param));
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::ConfigInfoPage(total_count, list))) => {
+            V2Result::ok_response(ConfigSearchPage {
+                total_count: Some(total_count),
+                page_number: Some(page_no),
+                pages_available: Some(total_count.div_ceil(page_size)),
+                page_items: Some(list),
+            })
+        }
+        Ok(Ok(_)) => V2Result::<ConfigSearchPage<ConfigInfoDto>>::ok_response(ConfigSearchPage {
+            total_count: Some(0),
+            page_number: Some(page_no),
+            pages_available: Some(0),
+            page_items: Some(vec![]),
+        }),
+        Ok(Err(e)) => PARAMETER_VALIDATE_ERROR.to_response(e.to_string()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn get_history(
+    req: HttpRequest,
+    web::Query(param): web::Query<HistoryV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let nid = match param.nid {
+        Some(v) => v,
+        None => {
+            return PARAMETER_MISSING
+                .to_response("Required parameter 'nid' type Long is not present".to_owned())
+        }
+    };
+    let history_param = match param.to_history_param() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    match query_histories(&req, &appdata, history_param).await {
+        Ok((_, list)) => match list.into_iter().find(|e| e.id == Some(nid)) {
+            Some(item) => V2Result::ok_response(item),
+            None => RESOURCE_NOT_FOUND.to_response("certain config history not exist".to_owned()),
+        },
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn get_previous_history(
+    req: HttpRequest,
+    web::Query(param): web::Query<HistoryV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let id = match param.id {
+        Some(v) => v,
+        None => {
+            return PARAMETER_MISSING
+                .to_response("Required parameter 'id' type Long is not present".to_owned())
+        }
+    };
+    let history_param = match param.to_history_param() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    //历史记录按id倒序返回,下一条即为前一个版本
+    match query_histories(&req, &appdata, history_param).await {
+        Ok((_, list)) => match list.into_iter().find(|e| e.id.unwrap_or_default() < id) {
+            Some(item) => V2Result::ok_response(item),
+            None => RESOURCE_NOT_FOUND.to_response("previous config history not exist".to_owned()),
+        },
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_namespace_configs(
+    req: HttpRequest,
+    web::Query(param): web::Query<HistoryV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let query_param = ConfigQueryParam {
+        tenant: Some(Arc::new(ConfigUtils::default_tenant(
+            param.namespace_id.unwrap_or_default(),
+        ))),
+        limit: 0xffff_ffff,
+        privilege: get_session_privilege(&req),
+        ..Default::default()
+    };
+    let cmd = ConfigCmd::QueryPageInfo(Box::new(query_param));
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::ConfigInfoPage(_, list))) => V2Result::ok_response(list),
+        Ok(_) => V2Result::<Vec<ConfigInfoDto>>::ok_response(vec![]),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
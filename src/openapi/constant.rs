@@ -7,8 +7,6 @@ pub(crate) const V1_BASE_PATH: &str = "/v1";
 #[allow(unused)]
 pub(crate) const V2_BASE_PATH: &str = "/v2";
 pub(crate) const CONFIG_V1_BASE_PATH: &str = "/v1/cs";
-#[allow(unused)]
 pub(crate) const CONFIG_V2_BASE_PATH: &str = "/v2/cs";
 pub(crate) const NAMING_V1_BASE_PATH: &str = "/v1/ns";
-#[allow(unused)]
 pub(crate) const NAMING_V2_BASE_PATH: &str = "/v2/ns";
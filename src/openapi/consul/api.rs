@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::http::header;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::common::appdata::AppShareData;
+use crate::common::constant::APP_VERSION;
+use crate::common::model::TokenSession;
+use crate::naming::consul_index::{ConsulIndexCmd, ConsulIndexResult};
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::service_index::ServiceQueryParam;
+use crate::naming::NamingUtils;
+
+use super::model::{ConsulModelUtils, ConsulQueryParam, CONSUL_DATACENTER};
+
+const CONSUL_INDEX_HEADER: &str = "X-Consul-Index";
+const CONSUL_KNOWN_LEADER_HEADER: &str = "X-Consul-KnownLeader";
+const CONSUL_LAST_CONTACT_HEADER: &str = "X-Consul-LastContact";
+const DEFAULT_WAIT_MILLIS: u64 = 5 * 60 * 1000;
+const MAX_WAIT_MILLIS: u64 = 10 * 60 * 1000;
+
+pub async fn agent_self(app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    let node_name = format!("rnacos-{}", app.sys_config.raft_node_id);
+    let v = serde_json::json!({
+        "Config": {
+            "Datacenter": CONSUL_DATACENTER,
+            "NodeName": &node_name,
+            "Server": true,
+            "Version": APP_VERSION,
+        },
+        "Member": {
+            "Name": &node_name,
+        },
+    });
+    HttpResponse::Ok()
+        .insert_header(header::ContentType(mime::APPLICATION_JSON))
+        .body(v.to_string())
+}
+
+///
+/// 返回映射命名空间与分组下的服务名及其实例标签
+pub async fn catalog_services(
+    req: HttpRequest,
+    param: web::Query<ConsulQueryParam>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_read_privilege(
+        &req,
+        &get_namespace(&app, &param),
+        &app.sys_config.consul_group,
+    ) {
+        return forbidden_response();
+    }
+    let index = wait_index(&app, None, &param).await;
+    match query_services(&app, &param).await {
+        Ok(v) => build_response(index, &v),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+pub async fn catalog_service(
+    req: HttpRequest,
+    service: web::Path<String>,
+    param: web::Query<ConsulQueryParam>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = build_service_key(&app, &param, &service);
+    if !check_read_privilege(&req, &key.namespace_id, &key.group_name) {
+        return forbidden_response();
+    }
+    let index = wait_index(&app, Some(key.clone()), &param).await;
+    let tags = get_tag_params(&req);
+    match query_instances(&app, key.clone()).await {
+        Ok(list) => {
+            let list: Vec<_> = list
+                .iter()
+                .filter(|e| ConsulModelUtils::match_tags(e, &tags))
+                .map(|e| ConsulModelUtils::to_catalog_service(e, &key.service_name, index))
+                .collect();
+            build_response(index, &list)
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+pub async fn health_service(
+    req: HttpRequest,
+    service: web::Path<String>,
+    param: web::Query<ConsulQueryParam>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = build_service_key(&app, &param, &service);
+    if !check_read_privilege(&req, &key.namespace_id, &key.group_name) {
+        return forbidden_response();
+    }
+    let index = wait_index(&app, Some(key.clone()), &param).await;
+    let tags = get_tag_params(&req);
+    let passing = param.is_passing();
+    match query_instances(&app, key.clone()).await {
+        Ok(list) => {
+            let list: Vec<_> = list
+                .iter()
+                .filter(|e| (!passing || e.healthy) && ConsulModelUtils::match_tags(e, &tags))
+                .map(|e| ConsulModelUtils::to_service_entry(e, &key.service_name))
+                .collect();
+            build_response(index, &list)
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+fn build_response<T: Serialize>(index: u64, v: &T) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ContentType(mime::APPLICATION_JSON))
+        .insert_header((CONSUL_INDEX_HEADER, index.to_string()))
+        .insert_header((CONSUL_KNOWN_LEADER_HEADER, "true"))
+        .insert_header((CONSUL_LAST_CONTACT_HEADER, "0"))
+        .body(serde_json::to_string(v).unwrap_or_default())
+}
+
+///
+/// 校验会话对映射的命名空间与分组是否有读权限
+fn check_read_privilege(req: &HttpRequest, namespace: &str, group: &str) -> bool {
+    match req
+        .extensions()
+        .get::<Arc<TokenSession>>()
+        .and_then(|e| e.namespace_privilege.clone())
+    {
+        Some(privilege) => privilege.check(namespace, Some(group), false),
+        None => true,
+    }
+}
+
+fn forbidden_response() -> HttpResponse {
+    HttpResponse::Forbidden().body("Permission denied")
+}
+
+fn get_namespace(app: &AppShareData, param: &ConsulQueryParam) -> Arc<String> {
+    match &param.ns {
+        Some(ns) if !ns.is_empty() => Arc::new(NamingUtils::default_namespace(ns.to_owned())),
+        _ => app.sys_config.consul_namespace.clone(),
+    }
+}
+
+fn build_service_key(app: &AppShareData, param: &ConsulQueryParam, service: &str) -> ServiceKey {
+    ServiceKey::new_by_arc(
+        get_namespace(app, param),
+        app.sys_config.consul_group.clone(),
+        Arc::new(service.to_owned()),
+    )
+}
+
+///
+/// tag参数可重复出现,实例需包含全部标签
+fn get_tag_params(req: &HttpRequest) -> Vec<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(req.query_string())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(k, _)| k == "tag")
+        .map(|(_, v)| v)
+        .collect()
+}
+
+fn parse_wait_millis(wait: &Option<String>) -> u64 {
+    let wait = match wait {
+        Some(v) => v.trim(),
+        None => return DEFAULT_WAIT_MILLIS,
+    };
+    let (num, unit) = match wait.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => wait.split_at(i),
+        None => (wait, "s"),
+    };
+    let num: u64 = match num.parse() {
+        Ok(v) => v,
+        Err(_) => return DEFAULT_WAIT_MILLIS,
+    };
+    let millis = match unit {
+        "ms" => num,
+        "s" => num * 1000,
+        "m" => num * 60 * 1000,
+        "h" => num * 60 * 60 * 1000,
+        _ => DEFAULT_WAIT_MILLIS,
+    };
+    millis.min(MAX_WAIT_MILLIS)
+}
+
+///
+/// 阻塞查询:请求索引与当前索引相同时等待变更或超时,返回最新索引
+async fn wait_index(app: &AppShareData, key: Option<ServiceKey>, param: &ConsulQueryParam) -> u64 {
+    let cmd = match param.index {
+        Some(index) if index > 0 => ConsulIndexCmd::Wait(key.clone(), index),
+        _ => ConsulIndexCmd::QueryIndex(key.clone()),
+    };
+    match app.consul_index_manage.send(cmd).await {
+        Ok(Ok(ConsulIndexResult::Index(v))) => v,
+        Ok(Ok(ConsulIndexResult::Wait(receiver))) => {
+            let wait = Duration::from_millis(parse_wait_millis(&param.wait));
+            match tokio::time::timeout(wait, receiver).await {
+                Ok(Ok(v)) => v,
+                _ => query_index(app, key).await,
+            }
+        }
+        _ => query_index(app, key).await,
+    }
+}
+
+async fn query_index(app: &AppShareData, key: Option<ServiceKey>) -> u64 {
+    match app
+        .consul_index_manage
+        .send(ConsulIndexCmd::QueryIndex(key))
+        .await
+    {
+        Ok(Ok(ConsulIndexResult::Index(v))) => v,
+        _ => 1,
+    }
+}
+
+async fn query_services(
+    app: &AppShareData,
+    param: &ConsulQueryParam,
+) -> anyhow::Result<BTreeMap<Arc<String>, BTreeSet<String>>> {
+    let query_param = ServiceQueryParam {
+        namespace_id: Some(get_namespace(app, param)),
+        group: Some(app.sys_config.consul_group.clone()),
+        limit: usize::MAX,
+        ..Default::default()
+    };
+    let keys = match app
+        .naming_addr
+        .send(NamingCmd::QueryServiceKeyList(query_param))
+        .await??
+    {
+        NamingResult::ServiceKeyList(list) => list,
+        _ => vec![],
+    };
+    let mut services = BTreeMap::new();
+    for key in keys {
+        let service_name = key.service_name.clone();
+        let mut tags = BTreeSet::new();
+        for instance in query_instances(app, key).await? {
+            tags.extend(ConsulModelUtils::get_tags(&instance));
+        }
+        services.insert(service_name, tags);
+    }
+    Ok(services)
+}
+
+///
+/// 查询服务下启用的实例,健康状态不做保护阈值处理
+async fn query_instances(
+    app: &AppShareData,
+    key: ServiceKey,
+) -> anyhow::Result<Vec<Arc<Instance>>> {
+    match app
+        .naming_addr
+        .send(NamingCmd::QueryAllInstanceList(key))
+        .await??
+    {
+        NamingResult::InstanceList(list) => Ok(list.into_iter().filter(|e| e.enabled).collect()),
+        _ => Ok(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consul_wait_param() {
+        assert_eq!(parse_wait_millis(&None), DEFAULT_WAIT_MILLIS);
+        assert_eq!(parse_wait_millis(&Some("30s".to_owned())), 30000);
+        assert_eq!(parse_wait_millis(&Some("100ms".to_owned())), 100);
+        assert_eq!(parse_wait_millis(&Some("2m".to_owned())), 120000);
+        assert_eq!(parse_wait_millis(&Some("1h".to_owned())), MAX_WAIT_MILLIS);
+        assert_eq!(parse_wait_millis(&Some("15".to_owned())), 15000);
+    }
+}
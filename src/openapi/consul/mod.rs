@@ -0,0 +1,22 @@
+use actix_web::web;
+
+pub(crate) mod api;
+pub mod model;
+
+///
+/// consul catalog/health接口子集,服务映射到配置的命名空间与分组
+pub fn consul_config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/v1")
+            .service(web::resource("/agent/self").route(web::get().to(api::agent_self)))
+            .service(web::resource("/catalog/services").route(web::get().to(api::catalog_services)))
+            .service(
+                web::resource("/catalog/service/{service}")
+                    .route(web::get().to(api::catalog_service)),
+            )
+            .service(
+                web::resource("/health/service/{service}")
+                    .route(web::get().to(api::health_service)),
+            ),
+    );
+}
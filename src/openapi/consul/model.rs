@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::naming::model::Instance;
+
+pub const CONSUL_DATACENTER: &str = "dc1";
+///
+/// 实例元数据中以逗号分隔的consul标签
+pub const CONSUL_TAGS_METADATA_KEY: &str = "tags";
+pub const HEALTH_PASSING: &str = "passing";
+pub const HEALTH_CRITICAL: &str = "critical";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ConsulQueryParam {
+    pub ns: Option<String>,
+    pub index: Option<u64>,
+    pub wait: Option<String>,
+    pub passing: Option<String>,
+}
+
+impl ConsulQueryParam {
+    pub fn is_passing(&self) -> bool {
+        match &self.passing {
+            Some(v) => v != "false" && v != "0",
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConsulWeights {
+    pub passing: i64,
+    pub warning: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsulCatalogService {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Node")]
+    pub node: Arc<String>,
+    #[serde(rename = "Address")]
+    pub address: Arc<String>,
+    #[serde(rename = "Datacenter")]
+    pub datacenter: String,
+    #[serde(rename = "TaggedAddresses")]
+    pub tagged_addresses: HashMap<String, String>,
+    #[serde(rename = "NodeMeta")]
+    pub node_meta: HashMap<String, String>,
+    #[serde(rename = "ServiceID")]
+    pub service_id: String,
+    #[serde(rename = "ServiceName")]
+    pub service_name: Arc<String>,
+    #[serde(rename = "ServiceTags")]
+    pub service_tags: Vec<String>,
+    #[serde(rename = "ServiceAddress")]
+    pub service_address: Arc<String>,
+    #[serde(rename = "ServiceWeights")]
+    pub service_weights: ConsulWeights,
+    #[serde(rename = "ServiceMeta")]
+    pub service_meta: Arc<HashMap<String, String>>,
+    #[serde(rename = "ServicePort")]
+    pub service_port: u32,
+    #[serde(rename = "ServiceEnableTagOverride")]
+    pub service_enable_tag_override: bool,
+    #[serde(rename = "CreateIndex")]
+    pub create_index: u64,
+    #[serde(rename = "ModifyIndex")]
+    pub modify_index: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsulNode {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Node")]
+    pub node: Arc<String>,
+    #[serde(rename = "Address")]
+    pub address: Arc<String>,
+    #[serde(rename = "Datacenter")]
+    pub datacenter: String,
+    #[serde(rename = "TaggedAddresses")]
+    pub tagged_addresses: HashMap<String, String>,
+    #[serde(rename = "Meta")]
+    pub meta: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsulAgentService {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Service")]
+    pub service: Arc<String>,
+    #[serde(rename = "Tags")]
+    pub tags: Vec<String>,
+    #[serde(rename = "Address")]
+    pub address: Arc<String>,
+    #[serde(rename = "Meta")]
+    pub meta: Arc<HashMap<String, String>>,
+    #[serde(rename = "Port")]
+    pub port: u32,
+    #[serde(rename = "Weights")]
+    pub weights: ConsulWeights,
+    #[serde(rename = "EnableTagOverride")]
+    pub enable_tag_override: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsulHealthCheck {
+    #[serde(rename = "Node")]
+    pub node: Arc<String>,
+    #[serde(rename = "CheckID")]
+    pub check_id: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "Notes")]
+    pub notes: String,
+    #[serde(rename = "Output")]
+    pub output: String,
+    #[serde(rename = "ServiceID")]
+    pub service_id: String,
+    #[serde(rename = "ServiceName")]
+    pub service_name: Arc<String>,
+    #[serde(rename = "ServiceTags")]
+    pub service_tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConsulServiceEntry {
+    pub node: ConsulNode,
+    pub service: ConsulAgentService,
+    pub checks: Vec<ConsulHealthCheck>,
+}
+
+///
+/// 实例到consul模型的转换;节点名与地址使用实例ip,服务id为`ip:port`
+pub struct ConsulModelUtils;
+
+impl ConsulModelUtils {
+    pub fn get_tags(instance: &Instance) -> Vec<String> {
+        match instance.metadata.get(CONSUL_TAGS_METADATA_KEY) {
+            Some(v) => v
+                .split(',')
+                .map(|e| e.trim())
+                .filter(|e| !e.is_empty())
+                .map(|e| e.to_owned())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn match_tags(instance: &Instance, tags: &[String]) -> bool {
+        if tags.is_empty() {
+            return true;
+        }
+        let instance_tags = Self::get_tags(instance);
+        tags.iter().all(|e| instance_tags.contains(e))
+    }
+
+    fn get_service_id(instance: &Instance) -> String {
+        format!("{}:{}", &instance.ip, instance.port)
+    }
+
+    fn get_weights(instance: &Instance) -> ConsulWeights {
+        ConsulWeights {
+            passing: (instance.weight.round() as i64).max(1),
+            warning: 1,
+        }
+    }
+
+    pub fn to_catalog_service(
+        instance: &Instance,
+        service_name: &Arc<String>,
+        index: u64,
+    ) -> ConsulCatalogService {
+        ConsulCatalogService {
+            id: "".to_owned(),
+            node: instance.ip.clone(),
+            address: instance.ip.clone(),
+            datacenter: CONSUL_DATACENTER.to_owned(),
+            tagged_addresses: Default::default(),
+            node_meta: Default::default(),
+            service_id: Self::get_service_id(instance),
+            service_name: service_name.clone(),
+            service_tags: Self::get_tags(instance),
+            service_address: instance.ip.clone(),
+            service_weights: Self::get_weights(instance),
+            service_meta: instance.metadata.clone(),
+            service_port: instance.port,
+            service_enable_tag_override: false,
+            create_index: index,
+            modify_index: index,
+        }
+    }
+
+    pub fn to_service_entry(instance: &Instance, service_name: &Arc<String>) -> ConsulServiceEntry {
+        let service_id = Self::get_service_id(instance);
+        let tags = Self::get_tags(instance);
+        let status = if instance.healthy {
+            HEALTH_PASSING
+        } else {
+            HEALTH_CRITICAL
+        };
+        let check = ConsulHealthCheck {
+            node: instance.ip.clone(),
+            check_id: format!("service:{}", &service_id),
+            name: format!("Service '{}' check", service_name),
+            status: status.to_owned(),
+            notes: "".to_owned(),
+            output: "".to_owned(),
+            service_id: service_id.clone(),
+            service_name: service_name.clone(),
+            service_tags: tags.clone(),
+        };
+        ConsulServiceEntry {
+            node: ConsulNode {
+                id: "".to_owned(),
+                node: instance.ip.clone(),
+                address: instance.ip.clone(),
+                datacenter: CONSUL_DATACENTER.to_owned(),
+                tagged_addresses: Default::default(),
+                meta: Default::default(),
+            },
+            service: ConsulAgentService {
+                id: service_id,
+                service: service_name.clone(),
+                tags,
+                address: instance.ip.clone(),
+                meta: instance.metadata.clone(),
+                port: instance.port,
+                weights: Self::get_weights(instance),
+                enable_tag_override: false,
+            },
+            checks: vec![check],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consul_service_entry() {
+        let mut instance = Instance::new("10.0.0.1".to_owned(), 8080);
+        instance.weight = 0.5;
+        instance.healthy = false;
+        let mut metadata = HashMap::new();
+        metadata.insert(CONSUL_TAGS_METADATA_KEY.to_owned(), "a, b,,".to_owned());
+        instance.metadata = Arc::new(metadata);
+        assert_eq!(ConsulModelUtils::get_tags(&instance), vec!["a", "b"]);
+        assert!(ConsulModelUtils::match_tags(&instance, &["b".to_owned()]));
+        assert!(!ConsulModelUtils::match_tags(&instance, &["c".to_owned()]));
+
+        let service_name = Arc::new("svc01".to_owned());
+        let entry = ConsulModelUtils::to_service_entry(&instance, &service_name);
+        assert_eq!(entry.service.id, "10.0.0.1:8080");
+        assert_eq!(entry.service.weights.passing, 1);
+        assert_eq!(entry.checks[0].status, HEALTH_CRITICAL);
+        let v = serde_json::to_value(&entry).unwrap();
+        assert_eq!(v["Service"]["Port"], 8080);
+        assert_eq!(v["Checks"][0]["CheckID"], "service:10.0.0.1:8080");
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use actix_web::http::header;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::TokenSession;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::eureka_delta::{EurekaDeltaCmd, EurekaDeltaResult};
+use crate::naming::model::{Instance, InstanceUpdateTag, ServiceKey};
+use crate::naming::service_index::ServiceQueryParam;
+
+use super::model::{
+    EurekaApplicationVo, EurekaApplicationsVo, EurekaInstanceRequest, EurekaModelUtils,
+    EurekaStatusParam, STATUS_OUT_OF_SERVICE,
+};
+
+const ACTION_ADDED: &str = "ADDED";
+
+///
+/// 全量拉取,返回映射命名空间与分组下的全部应用
+pub async fn get_apps(req: HttpRequest, app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    if !check_privilege(&req, &app, false) {
+        return forbidden_response();
+    }
+    let version = query_delta_version(&app).await;
+    match query_all_instances(&app).await {
+        Ok(services) => {
+            let apps_hashcode = EurekaModelUtils::build_apps_hashcode(
+                services.values().flatten().map(|e| e.as_ref()),
+            );
+            let application = services
+                .iter()
+                .filter(|(_, list)| !list.is_empty())
+                .map(|(service_name, list)| build_application(service_name, list))
+                .collect();
+            let apps = EurekaApplicationsVo {
+                versions_delta: version.to_string(),
+                apps_hashcode,
+                application,
+            };
+            build_response(&serde_json::json!({ "applications": apps }))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 增量拉取,返回最近变更的实例;apps__hashcode为全量注册表的值,客户端校验不一致时改为全量拉取
+pub async fn get_delta(req: HttpRequest, app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    if !check_privilege(&req, &app, false) {
+        return forbidden_response();
+    }
+    let (version, items) = match app
+        .eureka_delta_manage
+        .send(EurekaDeltaCmd::QueryDelta)
+        .await
+    {
+        Ok(Ok(EurekaDeltaResult::Delta(version, items))) => (version, items),
+        _ => return HttpResponse::InternalServerError().body("query eureka delta error"),
+    };
+    let services = match query_all_instances(&app).await {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+    };
+    let apps_hashcode =
+        EurekaModelUtils::build_apps_hashcode(services.values().flatten().map(|e| e.as_ref()));
+    let mut app_map: BTreeMap<Arc<String>, Vec<_>> = BTreeMap::new();
+    for item in items {
+        app_map
+            .entry(item.instance.service_name.clone())
+            .or_default()
+            .push(EurekaModelUtils::to_instance_vo(
+                &item.instance,
+                item.action_type.as_str(),
+            ));
+    }
+    let application = app_map
+        .into_iter()
+        .map(|(service_name, instance)| EurekaApplicationVo {
+            name: EurekaModelUtils::to_app_name(&service_name),
+            instance,
+        })
+        .collect();
+    let apps = EurekaApplicationsVo {
+        versions_delta: version.to_string(),
+        apps_hashcode,
+        application,
+    };
+    build_response(&serde_json::json!({ "applications": apps }))
+}
+
+pub async fn get_app(
+    req: HttpRequest,
+    app_name: web::Path<String>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, false) {
+        return forbidden_response();
+    }
+    let key = build_service_key(&app, &app_name);
+    match query_instances(&app, key.clone()).await {
+        Ok(list) if list.is_empty() => HttpResponse::NotFound().finish(),
+        Ok(list) => {
+            let application = build_application(&key.service_name, &list);
+            build_response(&serde_json::json!({ "application": application }))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+pub async fn get_instance(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, false) {
+        return forbidden_response();
+    }
+    let (app_name, instance_id) = path.into_inner();
+    match find_instance(&app, &app_name, &instance_id).await {
+        Ok(Some(instance)) => {
+            let instance = EurekaModelUtils::to_instance_vo(&instance, ACTION_ADDED);
+            build_response(&serde_json::json!({ "instance": instance }))
+        }
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 注册实例,重复注册时覆盖原实例信息
+pub async fn register(
+    req: HttpRequest,
+    app_name: web::Path<String>,
+    body: web::Bytes,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, true) {
+        return forbidden_response();
+    }
+    let request: EurekaInstanceRequest = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
+    let instance = match EurekaModelUtils::to_instance(
+        &app_name,
+        request.instance,
+        app.sys_config.eureka_namespace.clone(),
+        app.sys_config.eureka_group.clone(),
+    ) {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    match app.naming_route.update_instance(instance, None).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 续约按心跳处理,保留原有的启用状态、权重与元数据;实例不存在时返回404,客户端会重新注册
+pub async fn renew(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, true) {
+        return forbidden_response();
+    }
+    let (app_name, instance_id) = path.into_inner();
+    let instance = match find_instance(&app, &app_name, &instance_id).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+    };
+    let mut instance = instance.as_ref().clone();
+    instance.healthy = EurekaModelUtils::is_client_up(&instance);
+    let tag = InstanceUpdateTag {
+        weight: false,
+        enabled: false,
+        ephemeral: false,
+        metadata: false,
+        from_update: false,
+    };
+    match app.naming_route.update_instance(instance, Some(tag)).await {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+pub async fn cancel(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, true) {
+        return forbidden_response();
+    }
+    let (app_name, instance_id) = path.into_inner();
+    let instance = match find_instance(&app, &app_name, &instance_id).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+    };
+    match app
+        .naming_route
+        .delete_instance(instance.as_ref().clone())
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 覆盖状态只支持OUT_OF_SERVICE,映射为禁用实例;其它值与删除覆盖状态一样恢复启用
+pub async fn update_status(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    param: web::Query<EurekaStatusParam>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, true) {
+        return forbidden_response();
+    }
+    let (app_name, instance_id) = path.into_inner();
+    let enabled = param.value.as_deref() != Some(STATUS_OUT_OF_SERVICE);
+    set_instance_enabled(&app, &app_name, &instance_id, enabled).await
+}
+
+pub async fn delete_status(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if !check_privilege(&req, &app, true) {
+        return forbidden_response();
+    }
+    let (app_name, instance_id) = path.into_inner();
+    set_instance_enabled(&app, &app_name, &instance_id, true).await
+}
+
+async fn set_instance_enabled(
+    app: &AppShareData,
+    app_name: &str,
+    instance_id: &str,
+    enabled: bool,
+) -> HttpResponse {
+    let instance = match find_instance(app, app_name, instance_id).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+    };
+    let mut instance = instance.as_ref().clone();
+    instance.enabled = enabled;
+    let tag = InstanceUpdateTag {
+        weight: false,
+        enabled: true,
+        ephemeral: false,
+        metadata: false,
+        from_update: false,
+    };
+    match app.naming_route.update_instance(instance, Some(tag)).await {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 校验会话对eureka映射的命名空间与分组是否有读或写权限
+fn check_privilege(req: &HttpRequest, app: &AppShareData, write: bool) -> bool {
+    match req
+        .extensions()
+        .get::<Arc<TokenSession>>()
+        .and_then(|e| e.namespace_privilege.clone())
+    {
+        Some(privilege) => privilege.check(
+            &app.sys_config.eureka_namespace,
+            Some(&app.sys_config.eureka_group),
+            write,
+        ),
+        None => true,
+    }
+}
+
+fn forbidden_response() -> HttpResponse {
+    HttpResponse::Forbidden().finish()
+}
+
+fn build_response<T: Serialize>(v: &T) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ContentType(mime::APPLICATION_JSON))
+        .body(serde_json::to_string(v).unwrap_or_default())
+}
+
+fn build_application(service_name: &str, list: &[Arc<Instance>]) -> EurekaApplicationVo {
+    EurekaApplicationVo {
+        name: EurekaModelUtils::to_app_name(service_name),
+        instance: list
+            .iter()
+            .map(|e| EurekaModelUtils::to_instance_vo(e, ACTION_ADDED))
+            .collect(),
+    }
+}
+
+fn build_service_key(app: &AppShareData, app_name: &str) -> ServiceKey {
+    ServiceKey::new_by_arc(
+        app.sys_config.eureka_namespace.clone(),
+        app.sys_config.eureka_group.clone(),
+        Arc::new(EurekaModelUtils::to_service_name(app_name)),
+    )
+}
+
+async fn query_delta_version(app: &AppShareData) -> u64 {
+    match app
+        .eureka_delta_manage
+        .send(EurekaDeltaCmd::QueryDelta)
+        .await
+    {
+        Ok(Ok(EurekaDeltaResult::Delta(version, _))) => version,
+        _ => 0,
+    }
+}
+
+async fn query_all_instances(
+    app: &AppShareData,
+) -> anyhow::Result<BTreeMap<Arc<String>, Vec<Arc<Instance>>>> {
+    let query_param = ServiceQueryParam {
+        namespace_id: Some(app.sys_config.eureka_namespace.clone()),
+        group: Some(app.sys_config.eureka_group.clone()),
+        limit: usize::MAX,
+        ..Default::default()
+    };
+    let keys = match app
+        .naming_addr
+        .send(NamingCmd::QueryServiceKeyList(query_param))
+        .await??
+    {
+        NamingResult::ServiceKeyList(list) => list,
+        _ => vec![],
+    };
+    let mut services = BTreeMap::new();
+    for key in keys {
+        let service_name = key.service_name.clone();
+        services.insert(service_name, query_instances(app, key).await?);
+    }
+    Ok(services)
+}
+
+async fn query_instances(
+    app: &AppShareData,
+    key: ServiceKey,
+) -> anyhow::Result<Vec<Arc<Instance>>> {
+    match app
+        .naming_addr
+        .send(NamingCmd::QueryAllInstanceList(key))
+        .await??
+    {
+        NamingResult::InstanceList(list) => Ok(list),
+        _ => Ok(vec![]),
+    }
+}
+
+async fn find_instance(
+    app: &AppShareData,
+    app_name: &str,
+    instance_id: &str,
+) -> anyhow::Result<Option<Arc<Instance>>> {
+    let key = build_service_key(app, app_name);
+    Ok(query_instances(app, key)
+        .await?
+        .into_iter()
+        .find(|e| EurekaModelUtils::get_instance_id(e) == instance_id))
+}
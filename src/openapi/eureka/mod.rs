@@ -0,0 +1,37 @@
+use actix_web::web;
+
+pub(crate) mod api;
+pub mod model;
+
+///
+/// eureka v2 rest接口子集,只支持json;应用映射到配置的命名空间与分组下的服务
+pub fn eureka_config(config: &mut web::ServiceConfig) {
+    //带/v2的前缀需先注册,否则会被/eureka匹配
+    config
+        .service(eureka_scope("/eureka/v2"))
+        .service(eureka_scope("/eureka"));
+}
+
+fn eureka_scope(path: &str) -> actix_web::Scope {
+    web::scope(path)
+        .service(web::resource(["/apps", "/apps/"]).route(web::get().to(api::get_apps)))
+        .service(
+            web::resource(["/apps/delta", "/apps/delta/"]).route(web::get().to(api::get_delta)),
+        )
+        .service(
+            web::resource("/apps/{app}")
+                .route(web::get().to(api::get_app))
+                .route(web::post().to(api::register)),
+        )
+        .service(
+            web::resource("/apps/{app}/{instance_id}")
+                .route(web::get().to(api::get_instance))
+                .route(web::put().to(api::renew))
+                .route(web::delete().to(api::cancel)),
+        )
+        .service(
+            web::resource("/apps/{app}/{instance_id}/status")
+                .route(web::put().to(api::update_status))
+                .route(web::delete().to(api::delete_status)),
+        )
+}
@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::naming::model::Instance;
+use crate::naming::{NamingUtils, HEART_BEAT_TIMEOUT_KEY, IP_DELETE_TIMEOUT_KEY};
+
+pub const STATUS_UP: &str = "UP";
+pub const STATUS_DOWN: &str = "DOWN";
+pub const STATUS_OUT_OF_SERVICE: &str = "OUT_OF_SERVICE";
+pub const STATUS_UNKNOWN: &str = "UNKNOWN";
+
+///
+/// eureka实例中无法直接映射到Instance的字段,以该前缀保存在元数据中
+const EUREKA_METADATA_PREFIX: &str = "eureka.";
+const INSTANCE_ID_KEY: &str = "eureka.instanceId";
+const HOST_NAME_KEY: &str = "eureka.hostName";
+const VIP_ADDRESS_KEY: &str = "eureka.vipAddress";
+const SECURE_VIP_ADDRESS_KEY: &str = "eureka.secureVipAddress";
+const HOME_PAGE_URL_KEY: &str = "eureka.homePageUrl";
+const STATUS_PAGE_URL_KEY: &str = "eureka.statusPageUrl";
+const HEALTH_CHECK_URL_KEY: &str = "eureka.healthCheckUrl";
+const SECURE_PORT_KEY: &str = "eureka.securePort";
+const RENEWAL_INTERVAL_KEY: &str = "eureka.renewalIntervalInSecs";
+const CLIENT_STATUS_KEY: &str = "eureka.status";
+
+const DEFAULT_RENEWAL_INTERVAL_SECS: i64 = 30;
+const DEFAULT_DURATION_SECS: i64 = 90;
+const DEFAULT_SECURE_PORT: u32 = 443;
+const DATA_CENTER_CLASS: &str = "com.netflix.appinfo.InstanceInfo$DefaultDataCenterInfo";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EurekaInstanceRequest {
+    pub instance: EurekaInstanceParam,
+}
+
+///
+/// eureka客户端注册时提交的实例信息,数值字段兼容数字与字符串两种格式
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EurekaInstanceParam {
+    pub instance_id: Option<String>,
+    pub host_name: Option<String>,
+    pub app: Option<String>,
+    pub ip_addr: Option<String>,
+    pub status: Option<String>,
+    pub port: Option<EurekaPortParam>,
+    pub secure_port: Option<EurekaPortParam>,
+    pub lease_info: Option<EurekaLeaseInfoParam>,
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+    pub vip_address: Option<String>,
+    pub secure_vip_address: Option<String>,
+    pub home_page_url: Option<String>,
+    pub status_page_url: Option<String>,
+    pub health_check_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EurekaPortParam {
+    #[serde(rename = "$", default)]
+    pub port: serde_json::Value,
+    #[serde(rename = "@enabled", default)]
+    pub enabled: serde_json::Value,
+}
+
+impl EurekaPortParam {
+    fn get_port(&self) -> Option<u32> {
+        match &self.port {
+            serde_json::Value::Number(v) => v.as_u64().map(|v| v as u32),
+            serde_json::Value::String(v) => v.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn is_enabled(&self, default_value: bool) -> bool {
+        match &self.enabled {
+            serde_json::Value::Bool(v) => *v,
+            serde_json::Value::String(v) => v.trim().parse().unwrap_or(default_value),
+            _ => default_value,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EurekaLeaseInfoParam {
+    pub renewal_interval_in_secs: serde_json::Value,
+    pub duration_in_secs: serde_json::Value,
+}
+
+fn get_positive_i64(v: &serde_json::Value) -> Option<i64> {
+    match v {
+        serde_json::Value::Number(v) => v.as_i64(),
+        serde_json::Value::String(v) => v.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|v| *v > 0)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EurekaStatusParam {
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EurekaPortVo {
+    #[serde(rename = "$")]
+    pub port: u32,
+    #[serde(rename = "@enabled")]
+    pub enabled: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EurekaDataCenterInfo {
+    #[serde(rename = "@class")]
+    pub class: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EurekaLeaseInfoVo {
+    pub renewal_interval_in_secs: i64,
+    pub duration_in_secs: i64,
+    pub registration_timestamp: i64,
+    pub last_renewal_timestamp: i64,
+    pub eviction_timestamp: i64,
+    pub service_up_timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EurekaInstanceVo {
+    pub instance_id: String,
+    pub host_name: String,
+    pub app: String,
+    pub ip_addr: String,
+    pub status: String,
+    pub overridden_status: String,
+    pub port: EurekaPortVo,
+    pub secure_port: EurekaPortVo,
+    pub country_id: i32,
+    pub data_center_info: EurekaDataCenterInfo,
+    pub lease_info: EurekaLeaseInfoVo,
+    pub metadata: HashMap<String, String>,
+    pub home_page_url: String,
+    pub status_page_url: String,
+    pub health_check_url: String,
+    pub vip_address: String,
+    pub secure_vip_address: String,
+    pub is_coordinating_discovery_server: String,
+    pub last_updated_timestamp: String,
+    pub last_dirty_timestamp: String,
+    pub action_type: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EurekaApplicationVo {
+    pub name: String,
+    pub instance: Vec<EurekaInstanceVo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EurekaApplicationsVo {
+    #[serde(rename = "versions__delta")]
+    pub versions_delta: String,
+    #[serde(rename = "apps__hashcode")]
+    pub apps_hashcode: String,
+    pub application: Vec<EurekaApplicationVo>,
+}
+
+pub struct EurekaModelUtils;
+
+impl EurekaModelUtils {
+    ///
+    /// eureka应用名不区分大小写,服务名统一使用小写
+    pub fn to_service_name(app: &str) -> String {
+        app.to_lowercase()
+    }
+
+    pub fn to_app_name(service_name: &str) -> String {
+        service_name.to_uppercase()
+    }
+
+    pub fn get_instance_id(instance: &Instance) -> String {
+        match instance.metadata.get(INSTANCE_ID_KEY) {
+            Some(v) => v.to_owned(),
+            None => format!(
+                "{}:{}:{}",
+                &instance.ip, &instance.service_name, &instance.port
+            ),
+        }
+    }
+
+    pub fn get_status(instance: &Instance) -> &'static str {
+        if !instance.enabled {
+            STATUS_OUT_OF_SERVICE
+        } else if !instance.healthy {
+            STATUS_DOWN
+        } else {
+            STATUS_UP
+        }
+    }
+
+    ///
+    /// 客户端注册时上报的状态,续约时据此恢复健康状态
+    pub fn is_client_up(instance: &Instance) -> bool {
+        instance
+            .metadata
+            .get(CLIENT_STATUS_KEY)
+            .map(|e| e == STATUS_UP)
+            .unwrap_or(true)
+    }
+
+    ///
+    /// 把注册请求转为实例;心跳超时取租约时长,删除超时为租约时长的两倍
+    pub fn to_instance(
+        app: &str,
+        param: EurekaInstanceParam,
+        namespace_id: Arc<String>,
+        group_name: Arc<String>,
+    ) -> Result<Instance, String> {
+        let ip = match param.ip_addr.as_ref().filter(|e| !e.is_empty()) {
+            Some(v) => v.to_owned(),
+            None => return Err("ipAddr is empty".to_owned()),
+        };
+        let port_enabled = param
+            .port
+            .as_ref()
+            .map(|e| e.is_enabled(true))
+            .unwrap_or(true);
+        let secure_port_enabled = param
+            .secure_port
+            .as_ref()
+            .map(|e| e.is_enabled(false))
+            .unwrap_or(false);
+        let secure_port = param.secure_port.as_ref().and_then(|e| e.get_port());
+        let port = if port_enabled || !secure_port_enabled {
+            param.port.as_ref().and_then(|e| e.get_port())
+        } else {
+            secure_port
+        };
+        let port = match port.filter(|e| *e > 0) {
+            Some(v) => v,
+            None => return Err("port is invalid".to_owned()),
+        };
+        let service_name = Self::to_service_name(app);
+        let mut metadata: HashMap<String, String> = param
+            .metadata
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(k, _)| !k.starts_with('@') && !k.starts_with(EUREKA_METADATA_PREFIX))
+            .map(|(k, v)| match v {
+                serde_json::Value::String(v) => (k, v),
+                v => (k, v.to_string()),
+            })
+            .collect();
+        let duration_secs = param
+            .lease_info
+            .as_ref()
+            .and_then(|e| get_positive_i64(&e.duration_in_secs))
+            .unwrap_or(DEFAULT_DURATION_SECS);
+        metadata.insert(
+            HEART_BEAT_TIMEOUT_KEY.to_owned(),
+            (duration_secs * 1000).to_string(),
+        );
+        metadata.insert(
+            IP_DELETE_TIMEOUT_KEY.to_owned(),
+            (duration_secs * 2000).to_string(),
+        );
+        if let Some(v) = param
+            .lease_info
+            .as_ref()
+            .and_then(|e| get_positive_i64(&e.renewal_interval_in_secs))
+        {
+            metadata.insert(RENEWAL_INTERVAL_KEY.to_owned(), v.to_string());
+        }
+        if let (Some(v), true) = (secure_port, secure_port_enabled) {
+            metadata.insert(SECURE_PORT_KEY.to_owned(), v.to_string());
+        }
+        for (key, value) in [
+            (INSTANCE_ID_KEY, param.instance_id),
+            (HOST_NAME_KEY, param.host_name),
+            (VIP_ADDRESS_KEY, param.vip_address),
+            (SECURE_VIP_ADDRESS_KEY, param.secure_vip_address),
+            (HOME_PAGE_URL_KEY, param.home_page_url),
+            (STATUS_PAGE_URL_KEY, param.status_page_url),
+            (HEALTH_CHECK_URL_KEY, param.health_check_url),
+        ] {
+            if let Some(value) = value.filter(|e| !e.is_empty()) {
+                metadata.insert(key.to_owned(), value);
+            }
+        }
+        let status = param.status.unwrap_or_else(|| STATUS_UP.to_owned());
+        metadata.insert(CLIENT_STATUS_KEY.to_owned(), status.clone());
+        let mut instance = Instance {
+            ip: Arc::new(ip),
+            port,
+            weight: 1f32,
+            enabled: status != STATUS_OUT_OF_SERVICE,
+            healthy: status == STATUS_UP,
+            ephemeral: true,
+            cluster_name: NamingUtils::default_cluster("".to_owned()),
+            namespace_id,
+            group_name,
+            service_name: Arc::new(service_name),
+            metadata: Arc::new(metadata),
+            ..Default::default()
+        };
+        instance.generate_key();
+        Ok(instance)
+    }
+
+    pub fn to_instance_vo(instance: &Instance, action_type: &str) -> EurekaInstanceVo {
+        let metadata = &instance.metadata;
+        let get_value = |key: &str| metadata.get(key).map(|e| e.to_owned());
+        let secure_port = get_value(SECURE_PORT_KEY).and_then(|e| e.parse::<u32>().ok());
+        let app = Self::to_app_name(&instance.service_name);
+        let home_page_url = get_value(HOME_PAGE_URL_KEY)
+            .unwrap_or_else(|| format!("http://{}:{}/", &instance.ip, instance.port));
+        let vip_address =
+            get_value(VIP_ADDRESS_KEY).unwrap_or_else(|| instance.service_name.as_ref().to_owned());
+        let last_modified_millis = instance.last_modified_millis;
+        EurekaInstanceVo {
+            instance_id: Self::get_instance_id(instance),
+            host_name: get_value(HOST_NAME_KEY).unwrap_or_else(|| instance.ip.as_ref().to_owned()),
+            app,
+            ip_addr: instance.ip.as_ref().to_owned(),
+            status: Self::get_status(instance).to_owned(),
+            overridden_status: if instance.enabled {
+                STATUS_UNKNOWN.to_owned()
+            } else {
+                STATUS_OUT_OF_SERVICE.to_owned()
+            },
+            port: EurekaPortVo {
+                port: instance.port,
+                enabled: "true".to_owned(),
+            },
+            secure_port: EurekaPortVo {
+                port: secure_port.unwrap_or(DEFAULT_SECURE_PORT),
+                enabled: secure_port.is_some().to_string(),
+            },
+            country_id: 1,
+            data_center_info: EurekaDataCenterInfo {
+                class: DATA_CENTER_CLASS.to_owned(),
+                name: "MyOwn".to_owned(),
+            },
+            lease_info: EurekaLeaseInfoVo {
+                renewal_interval_in_secs: get_value(RENEWAL_INTERVAL_KEY)
+                    .and_then(|e| e.parse().ok())
+                    .unwrap_or(DEFAULT_RENEWAL_INTERVAL_SECS),
+                duration_in_secs: instance.get_heart_beat_timeout() / 1000,
+                registration_timestamp: last_modified_millis,
+                last_renewal_timestamp: last_modified_millis,
+                eviction_timestamp: 0,
+                service_up_timestamp: last_modified_millis,
+            },
+            metadata: metadata
+                .iter()
+                .filter(|(k, _)| !k.starts_with(EUREKA_METADATA_PREFIX))
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect(),
+            status_page_url: get_value(STATUS_PAGE_URL_KEY).unwrap_or_default(),
+            health_check_url: get_value(HEALTH_CHECK_URL_KEY).unwrap_or_default(),
+            secure_vip_address: get_value(SECURE_VIP_ADDRESS_KEY)
+                .unwrap_or_else(|| vip_address.clone()),
+            home_page_url,
+            vip_address,
+            is_coordinating_discovery_server: "false".to_owned(),
+            last_updated_timestamp: last_modified_millis.to_string(),
+            last_dirty_timestamp: last_modified_millis.to_string(),
+            action_type: action_type.to_owned(),
+        }
+    }
+
+    ///
+    /// 与eureka的getReconcileHashCode一致,按状态排序拼接`状态_数量_`;客户端用于校验增量结果
+    pub fn build_apps_hashcode<'a>(instances: impl Iterator<Item = &'a Instance>) -> String {
+        let mut status_count: BTreeMap<&str, usize> = BTreeMap::new();
+        for instance in instances {
+            *status_count.entry(Self::get_status(instance)).or_default() += 1;
+        }
+        let mut hashcode = String::new();
+        for (status, count) in status_count {
+            hashcode.push_str(&format!("{}_{}_", status, count));
+        }
+        hashcode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eureka_instance_convert() {
+        let body = r#"{"instance":{"instanceId":"host01:demo-app:8080","hostName":"host01",
+            "app":"DEMO-APP","ipAddr":"10.0.0.1","status":"UP",
+            "port":{"$":8080,"@enabled":"true"},"securePort":{"$":"443","@enabled":"false"},
+            "leaseInfo":{"renewalIntervalInSecs":10,"durationInSecs":30},
+            "metadata":{"@class":"java.util.Collections$EmptyMap","zone":"z1"},
+            "vipAddress":"demo-app"}}"#;
+        let request: EurekaInstanceRequest = serde_json::from_str(body).unwrap();
+        let instance = EurekaModelUtils::to_instance(
+            "DEMO-APP",
+            request.instance,
+            Arc::new("public".to_owned()),
+            Arc::new("DEFAULT_GROUP".to_owned()),
+        )
+        .unwrap();
+        assert_eq!(instance.service_name.as_str(), "demo-app");
+        assert_eq!(instance.port, 8080);
+        assert!(instance.healthy && instance.enabled && instance.ephemeral);
+        assert_eq!(instance.get_heart_beat_timeout(), 30000);
+        assert_eq!(instance.get_ip_delete_timeout(), 60000);
+        assert!(!instance.metadata.contains_key("@class"));
+
+        let vo = EurekaModelUtils::to_instance_vo(&instance, "ADDED");
+        assert_eq!(vo.instance_id, "host01:demo-app:8080");
+        assert_eq!(vo.app, "DEMO-APP");
+        assert_eq!(vo.lease_info.renewal_interval_in_secs, 10);
+        assert_eq!(vo.lease_info.duration_in_secs, 30);
+        assert_eq!(vo.metadata.get("zone").unwrap(), "z1");
+        assert!(!vo.metadata.contains_key(INSTANCE_ID_KEY));
+
+        let mut down = Instance::new("10.0.0.2".to_owned(), 8080);
+        down.healthy = false;
+        let mut out = Instance::new("10.0.0.3".to_owned(), 8080);
+        out.enabled = false;
+        let list = [instance, down, out];
+        assert_eq!(
+            EurekaModelUtils::build_apps_hashcode(list.iter()),
+            "DOWN_1_OUT_OF_SERVICE_1_UP_1_"
+        );
+    }
+}
@@ -1,12 +1,22 @@
+use crate::audit::core::AuditLogReq;
+use crate::audit::model::{AuditLogUtils, AUDIT_STATUS_FORBIDDEN};
 use crate::common::appdata::AppShareData;
-use crate::common::constant::{AUTHORIZATION_HEADER, EMPTY_ARC_STRING};
+use crate::common::constant::{
+    AUTHORIZATION_HEADER, CONSUL_TOKEN_HEADER, EMPTY_ARC_STRING, SIGN_TIMESTAMP_HEADER,
+    SPAS_ACCESS_KEY_HEADER, SPAS_SIGNATURE_HEADER, SPRING_CONFIG_TOKEN_HEADER,
+};
 use crate::common::datetime_utils;
 use crate::common::model::TokenSession;
+use crate::common::web_utils::{
+    build_audit_log, bytes_to_payload, check_request_privilege, get_client_ip,
+    get_namespace_group_param, is_privilege_check_path,
+};
 use crate::metrics::core::MetricsManager;
-use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{LabelMetricsItem, MetricsItem, MetricsRecord, MetricsRequest};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
+use crate::user::access_key::{AccessKeyReq, AccessKeyResult, AccessKeySignInfo};
 use actix::Addr;
 use actix_http::body::EitherBody;
 use actix_http::HttpMessage;
@@ -23,7 +33,7 @@ lazy_static::lazy_static! {
     pub static ref IGNORE_PATH: Vec<&'static str> = vec![
         "/nacos/v1/auth/login", "/nacos/v1/auth/users/login","/nacos/metrics"
     ];
-    pub static ref API_PATH: Regex = Regex::new(r"(?i)/nacos/.*").unwrap();
+    pub static ref API_PATH: Regex = Regex::new(r"(?i)/nacos/.*|^/v1/(agent|catalog|health)/|^/eureka/|^/spring-config/").unwrap();
     pub static ref IGNORE_METRICS_PATH: Vec<&'static str> = vec![
         "/nacos/v1/cs/configs/listener"
     ];
@@ -90,13 +100,23 @@ where
             true
         };
         let ignore_metrics = IGNORE_METRICS_PATH.contains(&path);
+        let is_audit = AuditLogUtils::is_audit_request(request.method().as_str(), path);
+        //使用路由模板作为标签,避免路径参数导致标签过多
+        let route = request
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_owned());
+        let method = request.method().to_string();
         let app_share_data = self.app_share_data.clone();
         let service = self.service.clone();
         Box::pin(async move {
-            let cache_manager = &app_share_data.cache_manager;
             let offset = &app_share_data.timezone_offset;
             let token = if enable_auth && is_check_path {
-                if let Some(v) = request.headers().get(AUTHORIZATION_HEADER) {
+                if let Some(v) = request
+                    .headers()
+                    .get(AUTHORIZATION_HEADER)
+                    .or_else(|| request.headers().get(CONSUL_TOKEN_HEADER))
+                    .or_else(|| request.headers().get(SPRING_CONFIG_TOKEN_HEADER))
+                {
                     Arc::new(v.to_str().unwrap_or_default().to_owned())
                 } else if let Ok(info) =
                     serde_urlencoded::from_str::<AccessInfo>(request.query_string())
@@ -108,42 +128,90 @@ where
             } else {
                 EMPTY_ARC_STRING.clone()
             };
+            let mut no_privilege = false;
             let pass = if !enable_auth || !is_check_path {
                 true
-            } else if token.is_empty() {
-                false
-            } else if let Ok(Some(session)) = get_user_session(
-                cache_manager,
-                CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
-            )
-            .await
+            } else if let Some(session) =
+                get_token_session(&app_share_data, &token, &mut request).await
             {
+                let has_privilege = match &session.namespace_privilege {
+                    Some(privilege) if is_privilege_check_path(request.path()) => {
+                        check_request_privilege(&mut request, privilege).await
+                    }
+                    _ => true,
+                };
                 request.extensions_mut().insert(session);
-                true
+                no_privilege = !has_privilege;
+                has_privilege
             } else {
                 false
             };
             //log::info!( "open api auth: {}|{}|{}|{}|{}|{}", &token, open_auth, is_check_path, pass, request.path(), request.query_string() );
             if pass {
+                let audit_log = if is_audit {
+                    let op_user = request
+                        .extensions()
+                        .get::<Arc<TokenSession>>()
+                        .map(|e| e.username.clone());
+                    Some(build_audit_log(&mut request, op_user).await)
+                } else {
+                    None
+                };
                 let res = service.call(request);
                 // forwarded responses map to "left" body
                 //record_req_metrics(&app_share_data.metrics_manager,duration,false);
                 //res.await.map(ServiceResponse::map_into_left_body)
                 res.await.map(move |item| {
-                    let success = item.response().status().as_u16() < 400;
+                    let code = item.response().status().as_u16();
                     let duration = SystemTime::now()
                         .duration_since(start)
                         .unwrap_or_default()
                         .as_secs_f64();
+                    if let Some(mut audit_log) = audit_log {
+                        audit_log.status = code as u32;
+                        app_share_data
+                            .audit_log_manager
+                            .do_send(AuditLogReq::Add(audit_log));
+                    }
                     if !ignore_metrics {
-                        record_req_metrics(&app_share_data.metrics_manager, duration, success);
+                        record_req_metrics(
+                            &app_share_data.metrics_manager,
+                            duration,
+                            HttpMetricsLabel {
+                                route,
+                                method,
+                                code,
+                            },
+                        );
                     }
                     ServiceResponse::map_into_left_body(item)
                 })
             } else {
                 //没有登录
-                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"unknown user!\",\"path\":\"{}\"}}"
-                                 ,datetime_utils::get_now_timestamp_str(offset),request.path());
+                let message = if no_privilege {
+                    "no namespace privilege!"
+                } else {
+                    "unknown user!"
+                };
+                if is_audit && no_privilege {
+                    let op_user = request
+                        .extensions()
+                        .get::<Arc<TokenSession>>()
+                        .map(|e| e.username.clone());
+                    let mut audit_log = build_audit_log(&mut request, op_user).await;
+                    audit_log.status = AUDIT_STATUS_FORBIDDEN;
+                    app_share_data
+                        .audit_log_manager
+                        .do_send(AuditLogReq::Add(audit_log));
+                } else if is_audit {
+                    AuditLogUtils::log_unauthorized(
+                        &get_client_ip(request.request()),
+                        request.method().as_str(),
+                        request.path(),
+                    );
+                }
+                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"{}\",\"path\":\"{}\"}}"
+                                 ,datetime_utils::get_now_timestamp_str(offset),message,request.path());
                 let response = HttpResponse::Forbidden()
                     .insert_header(("Content-Type", "application/json;charset=UTF-8"))
                     .body(body)
@@ -155,7 +223,15 @@ where
                     .duration_since(start)
                     .unwrap_or_default()
                     .as_secs_f64();
-                record_req_metrics(&app_share_data.metrics_manager, duration, false);
+                record_req_metrics(
+                    &app_share_data.metrics_manager,
+                    duration,
+                    HttpMetricsLabel {
+                        route,
+                        method,
+                        code: 403,
+                    },
+                );
                 Ok(res)
             }
         })
@@ -186,10 +262,49 @@ async fn peek_body_token(request: &mut ServiceRequest) -> Arc<String> {
     result
 }
 
-fn bytes_to_payload(buf: web::Bytes) -> dev::Payload {
-    let (_, mut pl) = actix_http::h1::Payload::create(true);
-    pl.unread_data(buf);
-    dev::Payload::from(pl)
+///
+/// 优先使用登录token,没有token时校验访问密钥签名
+async fn get_token_session(
+    app_share_data: &Arc<AppShareData>,
+    token: &Arc<String>,
+    request: &mut ServiceRequest,
+) -> Option<Arc<TokenSession>> {
+    if !token.is_empty() {
+        return get_user_session(
+            &app_share_data.cache_manager,
+            CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
+        )
+        .await
+        .ok()
+        .flatten();
+    }
+    let headers = request.headers();
+    let get_header = |name: &str| {
+        headers
+            .get(name)
+            .map(|v| v.to_str().unwrap_or_default().to_owned())
+            .unwrap_or_default()
+    };
+    let access_key = get_header(SPAS_ACCESS_KEY_HEADER);
+    if access_key.is_empty() {
+        return None;
+    }
+    let mut sign_info = AccessKeySignInfo {
+        access_key: Arc::new(access_key),
+        timestamp: get_header(SIGN_TIMESTAMP_HEADER),
+        signature: get_header(SPAS_SIGNATURE_HEADER),
+        resource: None,
+    };
+    let param = get_namespace_group_param(request).await;
+    sign_info.resource = AccessKeySignInfo::build_resource(&param);
+    match app_share_data
+        .access_key_manager
+        .send(AccessKeyReq::CheckSign(sign_info))
+        .await
+    {
+        Ok(Ok(AccessKeyResult::Session(session))) => session,
+        _ => None,
+    }
 }
 
 async fn get_user_session(
@@ -202,15 +317,43 @@ async fn get_user_session(
     }
 }
 
-fn record_req_metrics(metrics_manager: &Addr<MetricsManager>, duration: f64, _success: bool) {
+struct HttpMetricsLabel {
+    route: String,
+    method: String,
+    code: u16,
+}
+
+fn record_req_metrics(
+    metrics_manager: &Addr<MetricsManager>,
+    duration: f64,
+    label: HttpMetricsLabel,
+) {
+    let rt = duration as f32 * 1000f32;
     metrics_manager.do_send(MetricsRequest::BatchRecord(vec![
         MetricsItem::new(
             MetricsKey::HttpRequestHandleRtHistogram,
-            MetricsRecord::HistogramRecord(duration as f32 * 1000f32),
+            MetricsRecord::HistogramRecord(rt),
         ),
         MetricsItem::new(
             MetricsKey::HttpRequestTotalCount,
             MetricsRecord::CounterInc(1),
         ),
     ]));
+    let route_label = Label::new("route", label.route);
+    metrics_manager.do_send(MetricsRequest::LabelBatchRecord(vec![
+        LabelMetricsItem::new(
+            MetricsKey::HttpRequestRtHistogram,
+            vec![route_label.clone()],
+            MetricsRecord::HistogramRecord(rt),
+        ),
+        LabelMetricsItem::new(
+            MetricsKey::HttpRequestCount,
+            vec![
+                route_label,
+                Label::new("method", label.method),
+                Label::new("code", label.code.to_string()),
+            ],
+            MetricsRecord::CounterInc(1),
+        ),
+    ]));
 }
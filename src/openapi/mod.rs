@@ -6,9 +6,12 @@ use crate::openapi::constant::NACOS_PREFIX;
 pub(crate) mod auth;
 pub(crate) mod config;
 mod constant;
+pub(crate) mod consul;
+pub(crate) mod eureka;
 pub(crate) mod metrics;
 pub mod middle;
 pub(crate) mod naming;
+pub(crate) mod spring_config;
 pub(crate) mod v1;
 pub(crate) mod v2;
 
@@ -1,7 +1,8 @@
 use actix::Addr;
 use actix_web::http::header;
-use actix_web::{get, web, HttpResponse, Responder, Scope};
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder, Scope};
 
+use crate::common::web_utils::get_session_privilege;
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
 use crate::naming::ops::ops_model::{
     OpsServiceDto, OpsServiceOptQueryListResponse, OpsServiceQueryListRequest,
@@ -13,10 +14,12 @@ pub(super) fn service() -> Scope {
 
 #[get("/services")]
 pub async fn query_opt_service_list(
+    req: HttpRequest,
     param: web::Query<OpsServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
-    let serivce_param = param.0.to_param().unwrap();
+    let mut serivce_param = param.0.to_param().unwrap();
+    serivce_param.privilege = get_session_privilege(&req);
     match naming_addr
         .send(NamingCmd::QueryServiceInfoPage(serivce_param))
         .await
@@ -1,6 +1,6 @@
 use actix_web::{web, Scope};
 
-use crate::openapi::constant::NAMING_V1_BASE_PATH;
+use crate::openapi::constant::{NAMING_V1_BASE_PATH, NAMING_V2_BASE_PATH};
 use crate::openapi::RouteConf;
 
 mod catalog;
@@ -8,10 +8,10 @@ pub(crate) mod instance;
 pub mod model;
 mod operator;
 pub(crate) mod service;
-mod v2;
+pub(crate) mod v2;
 
 pub fn openapi_service(conf: RouteConf) -> Vec<Scope> {
-    vec![openapi_v1_route(conf)]
+    vec![openapi_v1_route(conf.clone()), openapi_v2_route(conf)]
 }
 
 pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
@@ -21,3 +21,7 @@ pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
         .service(operator::service())
         .service(catalog::service())
 }
+
+pub fn openapi_v2_route(_conf: RouteConf) -> Scope {
+    web::scope(NAMING_V2_BASE_PATH).service(v2::api::service())
+}
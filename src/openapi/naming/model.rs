@@ -1,6 +1,6 @@
 #![allow(unused_imports, unused_assignments, unused_variables)]
 use crate::common::option_utils::OptionUtils;
-use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::model::{Instance, InstanceUpdateTag, ServiceKey};
 use crate::naming::NamingUtils;
 use crate::utils::{get_bool_from_string, select_option_by_clone};
 use serde::{Deserialize, Serialize};
@@ -41,6 +41,22 @@ impl InstanceWebParams {
         }
     }
 
+    pub(crate) fn to_update_tag(&self) -> InstanceUpdateTag {
+        InstanceUpdateTag {
+            weight: match &self.weight {
+                Some(v) => *v != 1.0f32,
+                None => false,
+            },
+            metadata: match &self.metadata {
+                Some(v) => !v.is_empty() && v != "{}",
+                None => false,
+            },
+            enabled: self.enabled.is_some(),
+            ephemeral: self.ephemeral.is_some(),
+            from_update: true,
+        }
+    }
+
     pub(crate) fn convert_to_instance(self) -> Result<Instance, String> {
         let mut instance = Instance {
             ip: Arc::new(self.ip.unwrap()),
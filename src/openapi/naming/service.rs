@@ -1,6 +1,7 @@
 use actix::Addr;
-use actix_web::{web, HttpResponse, Responder, Scope};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Scope};
 
+use crate::common::web_utils::get_session_privilege;
 use crate::merge_web_param;
 use crate::naming::api_model::ServiceInfoParam;
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
@@ -70,6 +71,7 @@ pub async fn remove_service(
 }
 
 pub async fn query_service_list(
+    req: HttpRequest,
     param: web::Query<ServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
@@ -90,8 +92,11 @@ pub async fn query_service_list(
             .to_owned(),
     );
     let key = ServiceKey::new(&namespace_id, &group, "");
+    let privilege = get_session_privilege(&req);
     match naming_addr
-        .send(NamingCmd::QueryServicePage(key, page_size, page_index))
+        .send(NamingCmd::QueryServicePage(
+            key, page_size, page_index, privilege,
+        ))
         .await
     {
         Ok(res) => {
@@ -1 +1,419 @@
+use std::sync::Arc;
 
+use actix_web::{web, HttpRequest, Responder, Scope};
+
+use crate::common::appdata::AppShareData;
+use crate::common::web_utils::get_session_privilege;
+use crate::merge_web_param;
+use crate::naming::api_model::{InstanceVO, ServiceInfoParam};
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::model::{InstanceUpdateTag, ServiceKey};
+use crate::naming::service_index::ServiceQueryParam;
+use crate::naming::NamingUtils;
+use crate::openapi::constant::EMPTY;
+use crate::openapi::naming::model::{InstanceWebParams, ServiceQueryListRequest};
+use crate::openapi::naming::v2::model::{
+    is_healthy, ClientInfoV2, ClientInstanceV2, ClientServiceV2, ClientV2Params,
+    InstanceHealthV2Params, InstanceListV2Params, ServiceDetailV2, ServiceListV2,
+};
+use crate::openapi::v2::model::{
+    V2Result, INSTANCE_ERROR, PARAMETER_MISSING, PARAMETER_VALIDATE_ERROR, RESOURCE_NOT_FOUND,
+    SERVER_ERROR, SERVICE_NOT_EXIST,
+};
+use crate::utils::get_bool_from_string;
+
+pub(crate) fn service() -> Scope {
+    web::scope(EMPTY)
+        .service(
+            web::resource("/instance")
+                .route(web::get().to(get_instance))
+                .route(web::post().to(update_instance))
+                .route(web::put().to(update_instance))
+                .route(web::delete().to(del_instance)),
+        )
+        .service(web::resource("/instance/list").route(web::get().to(get_instance_list)))
+        .service(
+            web::resource("/service")
+                .route(web::get().to(get_service))
+                .route(web::post().to(update_service))
+                .route(web::put().to(update_service))
+                .route(web::delete().to(remove_service)),
+        )
+        .service(web::resource("/service/list").route(web::get().to(query_service_list)))
+        .service(web::resource("/health/instance").route(web::put().to(update_instance_health)))
+        .service(web::resource("/client/list").route(web::get().to(query_client_list)))
+        .service(web::resource("/client").route(web::get().to(get_client)))
+        .service(
+            web::resource("/client/publish/list").route(web::get().to(query_client_publish_list)),
+        )
+        .service(
+            web::resource("/client/subscribe/list")
+                .route(web::get().to(query_client_subscribe_list)),
+        )
+        .service(
+            web::resource("/client/service/publisher/list")
+                .route(web::get().to(query_service_publisher_list)),
+        )
+        .service(
+            web::resource("/client/service/subscriber/list")
+                .route(web::get().to(query_service_subscriber_list)),
+        )
+}
+
+async fn send_naming_cmd(
+    appdata: &web::Data<Arc<AppShareData>>,
+    cmd: NamingCmd,
+) -> anyhow::Result<NamingResult> {
+    appdata.naming_addr.send(cmd).await?
+}
+
+fn check_instance_param(param: &InstanceWebParams) -> Result<(), String> {
+    if param.ip.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+        return Err("Required parameter 'ip' type String is not present".to_owned());
+    }
+    if param.port.unwrap_or_default() == 0 {
+        return Err("Required parameter 'port' type Integer is not present".to_owned());
+    }
+    if param
+        .service_name
+        .as_ref()
+        .map(|e| e.is_empty())
+        .unwrap_or(true)
+    {
+        return Err("Required parameter 'serviceName' type String is not present".to_owned());
+    }
+    Ok(())
+}
+
+pub(crate) async fn get_instance(
+    web::Query(param): web::Query<InstanceWebParams>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    if let Err(e) = check_instance_param(&param) {
+        return PARAMETER_MISSING.to_response(e);
+    }
+    let instance = match param.convert_to_instance() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_VALIDATE_ERROR.to_response(e),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::Query(instance)).await {
+        Ok(NamingResult::Instance(v)) => V2Result::ok_response(InstanceVO::from_instance(&v)),
+        Ok(_) => RESOURCE_NOT_FOUND.to_response("instance not exist".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn update_instance(
+    web::Query(param): web::Query<InstanceWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    if let Err(e) = check_instance_param(&param) {
+        return PARAMETER_MISSING.to_response(e);
+    }
+    let update_tag = param.to_update_tag();
+    let instance = match param.convert_to_instance() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_VALIDATE_ERROR.to_response(e),
+    };
+    match appdata
+        .naming_route
+        .update_instance(instance, Some(update_tag))
+        .await
+    {
+        Ok(_) => V2Result::ok_response("ok"),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn del_instance(
+    web::Query(param): web::Query<InstanceWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    if let Err(e) = check_instance_param(&param) {
+        return PARAMETER_MISSING.to_response(e);
+    }
+    let instance = match param.convert_to_instance() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_VALIDATE_ERROR.to_response(e),
+    };
+    match appdata.naming_route.delete_instance(instance).await {
+        Ok(_) => V2Result::ok_response("ok"),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn get_instance_list(
+    web::Query(param): web::Query<InstanceListV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = match param.to_service_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    let only_healthy = get_bool_from_string(&param.healthy_only, false);
+    let cmd =
+        NamingCmd::QueryServiceInfo(key, param.cluster_name.unwrap_or_default(), only_healthy);
+    match send_naming_cmd(&appdata, cmd).await {
+        Ok(NamingResult::ServiceInfo(v)) => V2Result::ok_response(v),
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+///
+/// 只支持更新持久化实例的健康状态,临时实例的健康状态由心跳维护
+pub(crate) async fn update_instance_health(
+    web::Query(param): web::Query<InstanceHealthV2Params>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    let healthy = match is_healthy(&param.healthy) {
+        Some(v) => v,
+        None => {
+            return PARAMETER_MISSING
+                .to_response("Required parameter 'healthy' type Boolean is not present".to_owned())
+        }
+    };
+    let query_instance = match param.to_query_instance() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    let old_instance = match send_naming_cmd(&appdata, NamingCmd::Query(query_instance)).await {
+        Ok(NamingResult::Instance(v)) => v,
+        Ok(_) => return RESOURCE_NOT_FOUND.to_response("instance not exist".to_owned()),
+        Err(e) => return SERVER_ERROR.to_response(e.to_string()),
+    };
+    if old_instance.ephemeral {
+        return INSTANCE_ERROR
+            .to_response("health status of ephemeral instance is maintained by beat".to_owned());
+    }
+    let mut instance = old_instance.as_ref().clone();
+    instance.healthy = healthy;
+    let tag = InstanceUpdateTag {
+        weight: false,
+        enabled: false,
+        ephemeral: false,
+        metadata: false,
+        from_update: false,
+    };
+    match appdata
+        .naming_route
+        .update_instance(instance, Some(tag))
+        .await
+    {
+        Ok(_) => V2Result::ok_response("ok"),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn get_service(
+    web::Query(param): web::Query<ServiceInfoParam>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let service = match param.build_service_info() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e.to_string()),
+    };
+    let query_param = ServiceQueryParam {
+        namespace_id: Some(service.namespace_id.clone()),
+        group: Some(service.group_name.clone()),
+        service: Some(service.service_name.clone()),
+        limit: 1,
+        ..Default::default()
+    };
+    let cmd = NamingCmd::QueryServiceInfoPage(query_param);
+    match send_naming_cmd(&appdata, cmd).await {
+        Ok(NamingResult::ServiceInfoPage((_, list))) => {
+            match list
+                .into_iter()
+                .find(|e| e.service_name == service.service_name)
+            {
+                Some(dto) => {
+                    V2Result::ok_response(ServiceDetailV2::from_dto(service.namespace_id, dto))
+                }
+                None => SERVICE_NOT_EXIST.to_response("service not exist".to_owned()),
+            }
+        }
+        Ok(_) => SERVICE_NOT_EXIST.to_response("service not exist".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn update_service(
+    web::Query(param): web::Query<ServiceInfoParam>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    let service = match param.build_service_info() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e.to_string()),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::UpdateService(service)).await {
+        Ok(_) => V2Result::ok_response("ok"),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn remove_service(
+    web::Query(param): web::Query<ServiceInfoParam>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param, payload);
+    let service = match param.build_service_info() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e.to_string()),
+    };
+    let cmd = NamingCmd::RemoveService(service.to_service_key());
+    match send_naming_cmd(&appdata, cmd).await {
+        Ok(_) => V2Result::ok_response("ok"),
+        Err(e) => INSTANCE_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_service_list(
+    req: HttpRequest,
+    web::Query(param): web::Query<ServiceQueryListRequest>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let page_size = param.page_size.unwrap_or(20);
+    let page_index = param.page_no.unwrap_or(1);
+    let namespace_id = NamingUtils::default_namespace(param.namespace_id.unwrap_or_default());
+    let group = NamingUtils::default_group(param.group_name.unwrap_or_default());
+    let key = ServiceKey::new(&namespace_id, &group, "");
+    let cmd = NamingCmd::QueryServicePage(key, page_size, page_index, get_session_privilege(&req));
+    match send_naming_cmd(&appdata, cmd).await {
+        Ok(NamingResult::ServicePage((count, services))) => {
+            V2Result::ok_response(ServiceListV2 { count, services })
+        }
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_client_list(appdata: web::Data<Arc<AppShareData>>) -> impl Responder {
+    match send_naming_cmd(&appdata, NamingCmd::QueryClientInstanceCount).await {
+        Ok(NamingResult::ClientInstanceCount(list)) => {
+            let client_ids: Vec<Arc<String>> = list.into_iter().map(|(k, _)| k).collect();
+            V2Result::ok_response(client_ids)
+        }
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn get_client(
+    web::Query(param): web::Query<ClientV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let client_id = match param.get_client_id() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    let cmd = NamingCmd::QueryClientInstanceList(client_id.clone());
+    let publish_instance_count = match send_naming_cmd(&appdata, cmd).await {
+        Ok(NamingResult::InstanceList(list)) => list.len(),
+        Ok(_) => 0,
+        Err(e) => return SERVER_ERROR.to_response(e.to_string()),
+    };
+    let cmd = NamingCmd::QueryClientSubscribeList(client_id.clone());
+    let subscribe_service_count = match send_naming_cmd(&appdata, cmd).await {
+        Ok(NamingResult::ServiceKeyList(list)) => list.len(),
+        Ok(_) => 0,
+        Err(e) => return SERVER_ERROR.to_response(e.to_string()),
+    };
+    if publish_instance_count == 0 && subscribe_service_count == 0 {
+        return RESOURCE_NOT_FOUND.to_response("client not exist".to_owned());
+    }
+    V2Result::ok_response(ClientInfoV2 {
+        client_id,
+        ephemeral: true,
+        publish_instance_count,
+        subscribe_service_count,
+    })
+}
+
+pub(crate) async fn query_client_publish_list(
+    web::Query(param): web::Query<ClientV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let client_id = match param.get_client_id() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::QueryClientInstanceList(client_id)).await {
+        Ok(NamingResult::InstanceList(list)) => {
+            let list: Vec<ClientServiceV2> = list
+                .iter()
+                .map(|e| ClientServiceV2::from_instance(e))
+                .collect();
+            V2Result::ok_response(list)
+        }
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_client_subscribe_list(
+    web::Query(param): web::Query<ClientV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let client_id = match param.get_client_id() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::QueryClientSubscribeList(client_id)).await {
+        Ok(NamingResult::ServiceKeyList(list)) => {
+            let list: Vec<ClientServiceV2> = list
+                .into_iter()
+                .map(ClientServiceV2::from_service_key)
+                .collect();
+            V2Result::ok_response(list)
+        }
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_service_publisher_list(
+    web::Query(param): web::Query<ClientV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = match param.to_service_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::QueryAllInstanceList(key)).await {
+        Ok(NamingResult::InstanceList(list)) => {
+            let list: Vec<ClientInstanceV2> = list
+                .iter()
+                .filter(|e| param.match_short_key(&e.get_short_key()))
+                .map(|e| ClientInstanceV2::from_instance(e))
+                .collect();
+            V2Result::ok_response(list)
+        }
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
+
+pub(crate) async fn query_service_subscriber_list(
+    web::Query(param): web::Query<ClientV2Params>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let key = match param.to_service_key() {
+        Ok(v) => v,
+        Err(e) => return PARAMETER_MISSING.to_response(e),
+    };
+    match send_naming_cmd(&appdata, NamingCmd::QueryServiceSubscriberList(key)).await {
+        Ok(NamingResult::ClientIdList(list)) => V2Result::ok_response(list),
+        Ok(_) => SERVER_ERROR.to_response("naming result type error".to_owned()),
+        Err(e) => SERVER_ERROR.to_response(e.to_string()),
+    }
+}
@@ -1 +1,2 @@
-mod api;
+pub(crate) mod api;
+pub mod model;
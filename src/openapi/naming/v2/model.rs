@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::option_utils::OptionUtils;
+use crate::naming::model::{Instance, InstanceShortKey, ServiceKey};
+use crate::naming::service::ServiceInfoDto;
+use crate::naming::NamingUtils;
+use crate::utils::get_bool_from_string;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceListV2Params {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+    pub cluster_name: Option<String>,
+    pub healthy_only: Option<String>,
+}
+
+impl InstanceListV2Params {
+    pub(crate) fn to_service_key(&self) -> Result<ServiceKey, String> {
+        to_service_key(&self.namespace_id, &self.group_name, &self.service_name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHealthV2Params {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+    pub cluster_name: Option<String>,
+    pub ip: Option<String>,
+    pub port: Option<u32>,
+    pub healthy: Option<String>,
+}
+
+impl InstanceHealthV2Params {
+    pub(crate) fn merge(self, o: Self) -> Self {
+        Self {
+            namespace_id: OptionUtils::select(self.namespace_id, o.namespace_id),
+            group_name: OptionUtils::select(self.group_name, o.group_name),
+            service_name: OptionUtils::select(self.service_name, o.service_name),
+            cluster_name: OptionUtils::select(self.cluster_name, o.cluster_name),
+            ip: OptionUtils::select(self.ip, o.ip),
+            port: OptionUtils::select(self.port, o.port),
+            healthy: OptionUtils::select(self.healthy, o.healthy),
+        }
+    }
+
+    pub(crate) fn to_query_instance(&self) -> Result<Instance, String> {
+        let key = to_service_key(&self.namespace_id, &self.group_name, &self.service_name)?;
+        let ip = match &self.ip {
+            Some(v) if !v.is_empty() => v.to_owned(),
+            _ => return Err("Required parameter 'ip' type String is not present".to_owned()),
+        };
+        let port = match self.port {
+            Some(v) if v > 0 => v,
+            _ => return Err("Required parameter 'port' type Integer is not present".to_owned()),
+        };
+        let mut instance = Instance::new(ip, port);
+        instance.namespace_id = key.namespace_id;
+        instance.group_name = key.group_name;
+        instance.service_name = key.service_name;
+        instance.cluster_name =
+            NamingUtils::default_cluster(self.cluster_name.clone().unwrap_or_default());
+        instance.generate_key();
+        Ok(instance)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientV2Params {
+    pub client_id: Option<String>,
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+    pub ip: Option<String>,
+    pub port: Option<u32>,
+}
+
+impl ClientV2Params {
+    pub(crate) fn get_client_id(&self) -> Result<Arc<String>, String> {
+        match &self.client_id {
+            Some(v) if !v.is_empty() => Ok(Arc::new(v.to_owned())),
+            _ => Err("Required parameter 'clientId' type String is not present".to_owned()),
+        }
+    }
+
+    pub(crate) fn to_service_key(&self) -> Result<ServiceKey, String> {
+        to_service_key(&self.namespace_id, &self.group_name, &self.service_name)
+    }
+
+    pub(crate) fn match_short_key(&self, key: &InstanceShortKey) -> bool {
+        if let Some(ip) = &self.ip {
+            if !ip.is_empty() && ip.as_str() != key.ip.as_str() {
+                return false;
+            }
+        }
+        if let Some(port) = &self.port {
+            if *port != key.port {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn to_service_key(
+    namespace_id: &Option<String>,
+    group_name: &Option<String>,
+    service_name: &Option<String>,
+) -> Result<ServiceKey, String> {
+    let service_name = match service_name {
+        Some(v) if !v.is_empty() => v,
+        _ => return Err("Required parameter 'serviceName' type String is not present".to_owned()),
+    };
+    let namespace_id = NamingUtils::default_namespace(namespace_id.clone().unwrap_or_default());
+    let group_name = NamingUtils::default_group(group_name.clone().unwrap_or_default());
+    Ok(ServiceKey::new(&namespace_id, &group_name, service_name))
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDetailV2 {
+    pub namespace_id: Arc<String>,
+    pub group_name: Arc<String>,
+    pub service_name: Arc<String>,
+    pub protect_threshold: f32,
+    pub metadata: Option<Arc<HashMap<String, String>>>,
+    pub instance_size: i64,
+    pub healthy_instance_size: i64,
+    pub cluster_count: i64,
+}
+
+impl ServiceDetailV2 {
+    pub fn from_dto(namespace_id: Arc<String>, dto: ServiceInfoDto) -> Self {
+        Self {
+            namespace_id,
+            group_name: dto.group_name,
+            service_name: dto.service_name,
+            protect_threshold: dto.protect_threshold.unwrap_or_default(),
+            metadata: dto.metadata,
+            instance_size: dto.instance_size,
+            healthy_instance_size: dto.healthy_instance_size,
+            cluster_count: dto.cluster_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceListV2 {
+    pub count: usize,
+    pub services: Vec<Arc<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientInfoV2 {
+    pub client_id: Arc<String>,
+    pub ephemeral: bool,
+    pub publish_instance_count: usize,
+    pub subscribe_service_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientServiceV2 {
+    pub namespace: Arc<String>,
+    pub group: Arc<String>,
+    pub service_name: Arc<String>,
+    pub registered_instance: Option<ClientInstanceV2>,
+}
+
+impl ClientServiceV2 {
+    pub fn from_instance(instance: &Instance) -> Self {
+        Self {
+            namespace: instance.namespace_id.clone(),
+            group: instance.group_name.clone(),
+            service_name: instance.service_name.clone(),
+            registered_instance: Some(ClientInstanceV2::from_instance(instance)),
+        }
+    }
+
+    pub fn from_service_key(key: ServiceKey) -> Self {
+        Self {
+            namespace: key.namespace_id,
+            group: key.group_name,
+            service_name: key.service_name,
+            registered_instance: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientInstanceV2 {
+    pub client_id: Option<Arc<String>>,
+    pub ip: Arc<String>,
+    pub port: u32,
+    pub cluster: String,
+}
+
+impl ClientInstanceV2 {
+    pub fn from_instance(instance: &Instance) -> Self {
+        Self {
+            client_id: if instance.client_id.is_empty() {
+                None
+            } else {
+                Some(instance.client_id.clone())
+            },
+            ip: instance.ip.clone(),
+            port: instance.port,
+            cluster: instance.cluster_name.clone(),
+        }
+    }
+}
+
+pub(crate) fn is_healthy(healthy: &Option<String>) -> Option<bool> {
+    healthy
+        .as_ref()
+        .map(|_| get_bool_from_string(healthy, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_params_to_instance() {
+        let param = InstanceHealthV2Params {
+            service_name: Some("foo".to_owned()),
+            ip: Some("127.0.0.1".to_owned()),
+            port: Some(8080),
+            healthy: Some("false".to_owned()),
+            ..Default::default()
+        };
+        let instance = param.to_query_instance().unwrap();
+        assert_eq!(instance.group_name.as_str(), "DEFAULT_GROUP");
+        assert_eq!(instance.cluster_name.as_str(), "DEFAULT");
+        assert_eq!(is_healthy(&param.healthy), Some(false));
+        let param = InstanceHealthV2Params::default();
+        assert!(param.to_query_instance().is_err());
+    }
+}
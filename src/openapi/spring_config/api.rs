@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use actix_web::http::header;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::TokenSession;
+use crate::common::web_utils::{can_decrypt_config, get_client_ip};
+use crate::config::config_crypto::ConfigCryptoManager;
+use crate::config::config_properties::ConfigPropertiesParser;
+use crate::config::config_type::MEDIA_TYPE_TEXT_PLAIN;
+use crate::config::core::{ConfigCmd, ConfigResult};
+
+use super::model::{
+    SpringConfigMapping, SpringConfigUtils, SpringEnvironment, SpringFileType, SpringPropertySource,
+};
+
+pub async fn get_environment(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let (application, profile) = path.into_inner();
+    build_environment_response(&req, &app, application, profile, None).await
+}
+
+pub async fn get_environment_with_label(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let (application, profile, label) = path.into_inner();
+    build_environment_response(&req, &app, application, profile, Some(label)).await
+}
+
+pub async fn get_file(
+    req: HttpRequest,
+    name: web::Path<String>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    build_file_response(&req, &app, &name).await
+}
+
+///
+/// label对应git分支,这里不区分label
+pub async fn get_file_with_label(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let (_label, name) = path.into_inner();
+    build_file_response(&req, &app, &name).await
+}
+
+async fn build_environment_response(
+    req: &HttpRequest,
+    app: &AppShareData,
+    application: String,
+    profile: String,
+    label: Option<String>,
+) -> HttpResponse {
+    match query_property_sources(req, app, &application, &profile).await {
+        Ok(property_sources) => {
+            let environment = SpringEnvironment {
+                name: application,
+                profiles: SpringConfigUtils::split_names(&profile)
+                    .map(|e| e.to_owned())
+                    .collect(),
+                label,
+                version: None,
+                state: None,
+                property_sources,
+            };
+            HttpResponse::Ok()
+                .insert_header(header::ContentType(mime::APPLICATION_JSON))
+                .body(serde_json::to_string(&environment).unwrap_or_default())
+        }
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 合并全部属性源后按文件后缀输出
+async fn build_file_response(req: &HttpRequest, app: &AppShareData, name: &str) -> HttpResponse {
+    let (application, profile, file_type) = match SpringConfigUtils::parse_file_name(name) {
+        Some(v) => v,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let sources = match query_property_sources(req, app, application, profile).await {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+    };
+    let properties = SpringConfigUtils::merge_sources(&sources);
+    let (content_type, body) = match file_type {
+        SpringFileType::Properties => (
+            MEDIA_TYPE_TEXT_PLAIN,
+            ConfigPropertiesParser::to_properties_string(&properties),
+        ),
+        SpringFileType::Yaml => {
+            let value = ConfigPropertiesParser::to_nested_value(&properties);
+            match serde_yaml::to_string(&value) {
+                Ok(v) => (MEDIA_TYPE_TEXT_PLAIN, v),
+                Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+            }
+        }
+        SpringFileType::Json => {
+            let value = ConfigPropertiesParser::to_nested_value(&properties);
+            (mime::APPLICATION_JSON.as_ref(), value.to_string())
+        }
+    };
+    HttpResponse::Ok().content_type(content_type).body(body)
+}
+
+///
+/// 按优先级从高到低查询配置并解析为属性源;无命名空间权限的配置与不支持解析的类型会被跳过
+async fn query_property_sources(
+    req: &HttpRequest,
+    app: &AppShareData,
+    application: &str,
+    profile: &str,
+) -> anyhow::Result<Vec<SpringPropertySource>> {
+    let mapping = SpringConfigMapping {
+        namespace: app.sys_config.spring_config_namespace.clone(),
+        group: app.sys_config.spring_config_group.clone(),
+        profile_to_namespace: app.sys_config.spring_config_profile_to_namespace,
+    };
+    let privilege = req
+        .extensions()
+        .get::<Arc<TokenSession>>()
+        .and_then(|e| e.namespace_privilege.clone());
+    let client_ip = get_client_ip(req);
+    let decrypt = can_decrypt_config(req);
+    let mut sources = vec![];
+    for key in mapping.build_config_keys(application, profile) {
+        if let Some(privilege) = &privilege {
+            if !privilege.check(&key.tenant, Some(&key.group), false) {
+                continue;
+            }
+        }
+        let (value, config_type) = match app
+            .config_addr
+            .send(ConfigCmd::GetByClient(
+                key.clone(),
+                client_ip.clone(),
+                decrypt,
+            ))
+            .await??
+        {
+            ConfigResult::Data {
+                value, config_type, ..
+            } => (value, config_type),
+            _ => continue,
+        };
+        //未授权解密时跳过加密配置
+        if ConfigCryptoManager::is_cipher_key(&key) && ConfigCryptoManager::is_encrypted(&value) {
+            continue;
+        }
+        let config_type = config_type.as_ref().map(|e| e.as_str());
+        let config_type = match ConfigPropertiesParser::get_config_type(config_type, &key.data_id) {
+            Some(v) => v,
+            None => continue,
+        };
+        match ConfigPropertiesParser::parse(&config_type, &value) {
+            Ok(properties) => sources.push(SpringPropertySource {
+                name: SpringConfigUtils::get_source_name(&key),
+                source: properties,
+            }),
+            Err(err) => log::warn!(
+                "spring config parse error,data_id:{},group:{},{}",
+                &key.data_id,
+                &key.group,
+                err
+            ),
+        }
+    }
+    Ok(sources)
+}
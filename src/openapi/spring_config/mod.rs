@@ -0,0 +1,29 @@
+use actix_web::web;
+
+pub(crate) mod api;
+pub mod model;
+
+///
+/// spring cloud config server兼容接口,客户端配置`spring.cloud.config.uri=http://<host>:<port>/spring-config`
+pub fn spring_cloud_config(config: &mut web::ServiceConfig) {
+    //配置文件路径需先于`/{application}/{profile}`注册
+    config.service(
+        web::scope("/spring-config")
+            .service(
+                web::resource(r"/{name:[^/]+\.(?:yml|yaml|properties|json)}")
+                    .route(web::get().to(api::get_file)),
+            )
+            .service(
+                web::resource(r"/{label}/{name:[^/]+\.(?:yml|yaml|properties|json)}")
+                    .route(web::get().to(api::get_file_with_label)),
+            )
+            .service(
+                web::resource("/{application}/{profile}")
+                    .route(web::get().to(api::get_environment)),
+            )
+            .service(
+                web::resource("/{application}/{profile}/{label}")
+                    .route(web::get().to(api::get_environment_with_label)),
+            ),
+    );
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::config::core::ConfigKey;
+use crate::config::{ConfigUtils, DEFAULT_TENANT};
+
+///
+/// 所有应用共享的配置,优先级最低
+const SHARED_APPLICATION: &str = "application";
+const DEFAULT_PROFILE: &str = "default";
+///
+/// 应用名对应的data_id,同一应用多个后缀都存在时依次作为属性源
+const DATA_ID_SUFFIXES: [&str; 6] = [".properties", ".yml", ".yaml", ".json", ".toml", ""];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpringEnvironment {
+    pub name: String,
+    pub profiles: Vec<String>,
+    pub label: Option<String>,
+    pub version: Option<String>,
+    pub state: Option<String>,
+    pub property_sources: Vec<SpringPropertySource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpringPropertySource {
+    pub name: String,
+    pub source: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpringFileType {
+    Yaml,
+    Properties,
+    Json,
+}
+
+///
+/// profile映射到配置分组或命名空间,另一维度使用配置的默认值
+#[derive(Debug, Clone)]
+pub struct SpringConfigMapping {
+    pub namespace: Arc<String>,
+    pub group: Arc<String>,
+    pub profile_to_namespace: bool,
+}
+
+impl SpringConfigMapping {
+    fn profile_scope(&self, profile: &str) -> (String, String) {
+        if self.profile_to_namespace {
+            (profile.to_owned(), self.group.as_ref().to_owned())
+        } else {
+            (self.namespace.as_ref().to_owned(), profile.to_owned())
+        }
+    }
+
+    ///
+    /// 按优先级从高到低返回需要查询的配置:profile对应的配置优先于默认配置,
+    /// 后面的profile与应用名优先级更高,共享的application配置优先级最低
+    pub fn build_config_keys(&self, application: &str, profile: &str) -> Vec<ConfigKey> {
+        let mut applications: Vec<&str> = SpringConfigUtils::split_names(application)
+            .filter(|e| *e != SHARED_APPLICATION)
+            .collect();
+        applications.reverse();
+        applications.push(SHARED_APPLICATION);
+        let mut scopes: Vec<(String, String)> = SpringConfigUtils::split_names(profile)
+            .filter(|e| *e != DEFAULT_PROFILE)
+            .map(|e| self.profile_scope(e))
+            .collect();
+        scopes.reverse();
+        scopes.push((
+            self.namespace.as_ref().to_owned(),
+            self.group.as_ref().to_owned(),
+        ));
+        let mut keys = vec![];
+        let mut key_set = HashSet::new();
+        for (namespace, group) in &scopes {
+            let tenant = ConfigUtils::default_tenant(namespace.to_owned());
+            for application in &applications {
+                for suffix in DATA_ID_SUFFIXES {
+                    let key = ConfigKey::new(&format!("{}{}", application, suffix), group, &tenant);
+                    if key_set.insert(key.clone()) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
+
+pub struct SpringConfigUtils;
+
+impl SpringConfigUtils {
+    pub fn split_names(v: &str) -> impl Iterator<Item = &str> {
+        v.split(',').map(|e| e.trim()).filter(|e| !e.is_empty())
+    }
+
+    pub fn get_source_name(key: &ConfigKey) -> String {
+        let namespace = if key.tenant.is_empty() {
+            DEFAULT_TENANT
+        } else {
+            key.tenant.as_str()
+        };
+        format!("rnacos:{}/{}/{}", namespace, &key.group, &key.data_id)
+    }
+
+    ///
+    /// 解析`{application}-{profile}.{ext}`,应用名可包含`-`,以最后一个`-`分隔profile
+    pub fn parse_file_name(name: &str) -> Option<(&str, &str, SpringFileType)> {
+        let (name, ext) = name.rsplit_once('.')?;
+        let file_type = match ext {
+            "yml" | "yaml" => SpringFileType::Yaml,
+            "properties" => SpringFileType::Properties,
+            "json" => SpringFileType::Json,
+            _ => return None,
+        };
+        match name.rsplit_once('-') {
+            Some((application, profile)) if !application.is_empty() && !profile.is_empty() => {
+                Some((application, profile, file_type))
+            }
+            _ => Some((name, DEFAULT_PROFILE, file_type)),
+        }
+    }
+
+    ///
+    /// 属性源按优先级从高到低排列,合并时同名属性取优先级高的值
+    pub fn merge_sources(sources: &[SpringPropertySource]) -> Map<String, Value> {
+        let mut properties = Map::new();
+        for source in sources.iter().rev() {
+            for (k, v) in &source.source {
+                properties.insert(k.to_owned(), v.clone());
+            }
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spring_config_keys() {
+        let mapping = SpringConfigMapping {
+            namespace: Arc::new("public".to_owned()),
+            group: Arc::new("DEFAULT_GROUP".to_owned()),
+            profile_to_namespace: false,
+        };
+        let keys = mapping.build_config_keys("app", "default,dev");
+        assert_eq!(keys.len(), 4 * DATA_ID_SUFFIXES.len());
+        assert_eq!(keys[0], ConfigKey::new("app.properties", "dev", ""));
+        assert_eq!(
+            keys[DATA_ID_SUFFIXES.len()],
+            ConfigKey::new("application.properties", "dev", "")
+        );
+        assert_eq!(
+            keys[2 * DATA_ID_SUFFIXES.len()],
+            ConfigKey::new("app.properties", "DEFAULT_GROUP", "")
+        );
+
+        let mapping = SpringConfigMapping {
+            profile_to_namespace: true,
+            ..mapping
+        };
+        let keys = mapping.build_config_keys("app", "dev");
+        assert_eq!(keys[1], ConfigKey::new("app.yml", "DEFAULT_GROUP", "dev"));
+
+        assert_eq!(
+            SpringConfigUtils::parse_file_name("my-app-dev.yml"),
+            Some(("my-app", "dev", SpringFileType::Yaml))
+        );
+        assert_eq!(
+            SpringConfigUtils::parse_file_name("app.properties"),
+            Some(("app", "default", SpringFileType::Properties))
+        );
+        assert_eq!(SpringConfigUtils::parse_file_name("app-dev.txt"), None);
+    }
+}
@@ -0,0 +1,81 @@
+use actix_http::StatusCode;
+use actix_web::HttpResponse;
+use serde::{Deserialize, Serialize};
+
+///
+/// nacos v2 open api 错误码
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCode {
+    pub code: i32,
+    pub message: &'static str,
+    pub http_status: u16,
+}
+
+impl ErrorCode {
+    const fn new(code: i32, message: &'static str, http_status: u16) -> Self {
+        Self {
+            code,
+            message,
+            http_status,
+        }
+    }
+
+    pub fn to_response(self, detail: String) -> HttpResponse {
+        let status =
+            StatusCode::from_u16(self.http_status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status).json(V2Result {
+            code: self.code,
+            message: self.message.to_owned(),
+            data: Some(detail),
+        })
+    }
+}
+
+pub const PARAMETER_MISSING: ErrorCode = ErrorCode::new(10000, "parameter missing", 400);
+pub const PARAMETER_VALIDATE_ERROR: ErrorCode =
+    ErrorCode::new(20002, "parameter validate error", 400);
+pub const RESOURCE_NOT_FOUND: ErrorCode = ErrorCode::new(20004, "resource not found", 404);
+pub const RESOURCE_CONFLICT: ErrorCode = ErrorCode::new(20005, "resource conflict", 409);
+pub const INSTANCE_ERROR: ErrorCode = ErrorCode::new(21002, "instance error", 400);
+pub const SERVICE_NOT_EXIST: ErrorCode = ErrorCode::new(21008, "service not exist", 404);
+pub const ACCESS_DENIED: ErrorCode = ErrorCode::new(30002, "access denied", 403);
+pub const SERVER_ERROR: ErrorCode = ErrorCode::new(30000, "server error", 500);
+
+///
+/// nacos v2 open api 统一返回结构
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct V2Result<T> {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<T>,
+}
+
+impl<T> V2Result<T>
+where
+    T: Serialize,
+{
+    pub fn success(data: T) -> Self {
+        Self {
+            code: 0,
+            message: "success".to_owned(),
+            data: Some(data),
+        }
+    }
+
+    pub fn ok_response(data: T) -> HttpResponse {
+        HttpResponse::Ok().json(Self::success(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v2_result_json() {
+        let v = serde_json::to_string(&V2Result::success(true)).unwrap();
+        assert_eq!(v, r#"{"code":0,"message":"success","data":true}"#);
+        let res = RESOURCE_NOT_FOUND.to_response("not exist".to_owned());
+        assert_eq!(res.status().as_u16(), 404);
+    }
+}
@@ -26,6 +26,9 @@ pub async fn handle_route(
             op_user,
             config_type,
             desc,
+            app_name,
+            config_tags,
+            cas_md5,
             extend_info: _,
         } => {
             let config_key: ConfigKey = (&key as &str).into();
@@ -36,6 +39,9 @@ pub async fn handle_route(
                     op_user,
                     config_type,
                     desc,
+                    app_name,
+                    config_tags,
+                    cas_md5,
                 })
                 .await??;
         }
@@ -48,6 +54,28 @@ pub async fn handle_route(
                 .send(ConfigAsyncCmd::Delete(config_key))
                 .await??;
         }
+        RouterRequest::ConfigBetaSet {
+            key,
+            value,
+            beta_ips,
+            op_user,
+        } => {
+            let config_key: ConfigKey = (&key as &str).into();
+            app.config_addr
+                .send(ConfigAsyncCmd::AddBeta {
+                    key: config_key,
+                    value,
+                    beta_ips,
+                    op_user,
+                })
+                .await??;
+        }
+        RouterRequest::ConfigBetaDel { key } => {
+            let config_key: ConfigKey = (&key as &str).into();
+            app.config_addr
+                .send(ConfigAsyncCmd::DeleteBeta(config_key))
+                .await??;
+        }
         RouterRequest::JoinNode {
             node_id,
             node_addr: addr,
@@ -76,6 +104,10 @@ pub async fn handle_route(
             let result = app.cache_manager.send(req).await??;
             return Ok(RouterResponse::CacheManagerResult { result });
         }
+        RouterRequest::ConfigListenerQuery { param } => {
+            let list = app.config_route.query_local_listener(param).await?;
+            return Ok(RouterResponse::ConfigListenerResult { list });
+        }
     };
     Ok(RouterResponse::None)
 }
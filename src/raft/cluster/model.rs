@@ -2,6 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::config_subscribe::{ConfigListenerInfo, ConfigListenerQueryParam};
 use crate::config::config_type::ConfigType;
 use crate::{
     config::core::ConfigKey,
@@ -24,6 +25,10 @@ pub struct SetConfigReq {
     pub op_user: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub app_name: Option<Arc<String>>,
+    pub config_tags: Option<Arc<String>>,
+    /// 期望的当前配置md5,不一致时拒绝写入
+    pub cas_md5: Option<Arc<String>>,
     //pub can_route_to_remote: bool,
     //pub extend_info: Option<HashMap<String,String>>,
 }
@@ -36,6 +41,9 @@ impl SetConfigReq {
             op_user: None,
             config_type: None,
             desc: None,
+            app_name: None,
+            config_tags: None,
+            cas_md5: None,
         }
     }
 
@@ -50,6 +58,9 @@ impl SetConfigReq {
             op_user: Some(op_user),
             config_type: None,
             desc: None,
+            app_name: None,
+            config_tags: None,
+            cas_md5: None,
         }
     }
 
@@ -79,6 +90,25 @@ impl DelConfigReq {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct SetBetaConfigReq {
+    pub config_key: ConfigKey,
+    pub value: Arc<String>,
+    pub beta_ips: Arc<String>,
+    pub op_user: Option<Arc<String>>,
+}
+
+impl SetBetaConfigReq {
+    pub fn new(config_key: ConfigKey, value: Arc<String>, beta_ips: Arc<String>) -> Self {
+        Self {
+            config_key,
+            value,
+            beta_ips,
+            op_user: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RouterRequest {
     ConfigSet {
@@ -87,12 +117,25 @@ pub enum RouterRequest {
         op_user: Option<Arc<String>>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        app_name: Option<Arc<String>>,
+        config_tags: Option<Arc<String>>,
+        #[serde(default)]
+        cas_md5: Option<Arc<String>>,
         extend_info: HashMap<String, String>,
     },
     ConfigDel {
         key: String,
         extend_info: HashMap<String, String>,
     },
+    ConfigBetaSet {
+        key: String,
+        value: Arc<String>,
+        beta_ips: Arc<String>,
+        op_user: Option<Arc<String>>,
+    },
+    ConfigBetaDel {
+        key: String,
+    },
     JoinNode {
         node_id: u64,
         node_addr: Arc<String>,
@@ -106,6 +149,9 @@ pub enum RouterRequest {
     CacheLimiterReq {
         req: CacheLimiterReq,
     },
+    ConfigListenerQuery {
+        param: ConfigListenerQueryParam,
+    },
 }
 
 impl From<SetConfigReq> for RouterRequest {
@@ -116,6 +162,9 @@ impl From<SetConfigReq> for RouterRequest {
             op_user: req.op_user,
             config_type: req.config_type,
             desc: req.desc,
+            app_name: req.app_name,
+            config_tags: req.config_tags,
+            cas_md5: req.cas_md5,
             extend_info: Default::default(),
         }
     }
@@ -130,6 +179,17 @@ impl From<DelConfigReq> for RouterRequest {
     }
 }
 
+impl From<SetBetaConfigReq> for RouterRequest {
+    fn from(req: SetBetaConfigReq) -> Self {
+        Self::ConfigBetaSet {
+            key: req.config_key.build_key(),
+            value: req.value,
+            beta_ips: req.beta_ips,
+            op_user: req.op_user,
+        }
+    }
+}
+
 impl From<CacheLimiterReq> for RouterRequest {
     fn from(req: CacheLimiterReq) -> Self {
         Self::CacheLimiterReq { req }
@@ -141,4 +201,5 @@ pub enum RouterResponse {
     None,
     TableManagerResult { result: TableManagerResult },
     CacheManagerResult { result: CacheManagerResult },
+    ConfigListenerResult { list: Vec<ConfigListenerInfo> },
 }
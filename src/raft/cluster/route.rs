@@ -1,16 +1,28 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use actix::prelude::*;
 
+use crate::config::config_push::ConfigPushStatusInfo;
+use crate::config::config_subscribe::{
+    ConfigListenType, ConfigListenerInfo, ConfigListenerQueryParam,
+};
+use crate::config::core::ConfigKey;
+use crate::config::model::ConfigCasConflictError;
+use crate::grpc::api_model::BaseResponse;
+use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd, BiStreamManageResult};
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
+use crate::grpc::nacos_proto::Payload;
+use crate::naming::cluster::node_manage::NodeManage;
 use crate::raft::filestore::core::FileStore;
 use crate::{
-    config::core::{ConfigActor, ConfigAsyncCmd, ConfigCmd},
+    config::core::{ConfigActor, ConfigAsyncCmd, ConfigCmd, ConfigResult},
     grpc::PayloadUtils,
     raft::{network::factory::RaftClusterRequestSender, NacosRaft},
 };
 
-use super::model::{DelConfigReq, RouteAddr, RouterRequest, RouterResponse, SetConfigReq};
+use super::model::{
+    DelConfigReq, RouteAddr, RouterRequest, RouterResponse, SetBetaConfigReq, SetConfigReq,
+};
 
 #[derive(Clone)]
 pub struct RaftAddrRouter {
@@ -54,6 +66,7 @@ impl RaftAddrRouter {
 #[derive(Clone, Debug)]
 pub struct ConfigRoute {
     config_addr: Addr<ConfigActor>,
+    bi_stream_manage: Addr<BiStreamManage>,
     raft_addr_route: Arc<RaftAddrRouter>,
     cluster_sender: Arc<RaftClusterRequestSender>,
 }
@@ -61,11 +74,13 @@ pub struct ConfigRoute {
 impl ConfigRoute {
     pub fn new(
         config_addr: Addr<ConfigActor>,
+        bi_stream_manage: Addr<BiStreamManage>,
         raft_addr_route: Arc<RaftAddrRouter>,
         cluster_sender: Arc<RaftClusterRequestSender>,
     ) -> Self {
         Self {
             config_addr,
+            bi_stream_manage,
             raft_addr_route,
             cluster_sender,
         }
@@ -75,6 +90,26 @@ impl ConfigRoute {
         anyhow::anyhow!("unknown the raft leader addr!")
     }
 
+    ///
+    /// 解析主节点的响应,主节点处理失败时返回其错误信息
+    fn parse_router_response(payload: Payload) -> anyhow::Result<RouterResponse> {
+        let is_error = payload
+            .metadata
+            .as_ref()
+            .map(|e| e.r#type.as_str() == "ErrorResponse")
+            .unwrap_or(false);
+        let body_vec = payload.body.unwrap_or_default().value;
+        if is_error {
+            let resp: BaseResponse = serde_json::from_slice(&body_vec)?;
+            let message = resp.message.unwrap_or_default();
+            if let Some(err) = ConfigCasConflictError::from_message(&message) {
+                return Err(err.into());
+            }
+            return Err(anyhow::anyhow!(message));
+        }
+        Ok(serde_json::from_slice(&body_vec)?)
+    }
+
     pub async fn set_config(&self, req: SetConfigReq) -> anyhow::Result<()> {
         match self.raft_addr_route.get_route_addr().await? {
             RouteAddr::Local => {
@@ -84,8 +119,11 @@ impl ConfigRoute {
                     op_user: req.op_user,
                     config_type: req.config_type,
                     desc: req.desc,
+                    app_name: req.app_name,
+                    config_tags: req.config_tags,
+                    cas_md5: req.cas_md5,
                 };
-                self.config_addr.send(cmd).await?.ok();
+                self.config_addr.send(cmd).await??;
             }
             RouteAddr::Remote(_, addr) => {
                 let source_req = req.clone();
@@ -93,8 +131,7 @@ impl ConfigRoute {
                 let request = serde_json::to_string(&req).unwrap_or_default();
                 let payload = PayloadUtils::build_payload(RAFT_ROUTE_REQUEST, request);
                 let resp_payload = self.cluster_sender.send_request(addr, payload).await?;
-                let body_vec = resp_payload.body.unwrap_or_default().value;
-                let _: RouterResponse = serde_json::from_slice(&body_vec)?;
+                Self::parse_router_response(resp_payload)?;
                 self.config_addr.do_send(ConfigCmd::SetTmpValue(
                     source_req.config_key,
                     source_req.value,
@@ -127,4 +164,166 @@ impl ConfigRoute {
         }
         Ok(())
     }
+
+    pub async fn set_beta_config(&self, req: SetBetaConfigReq) -> anyhow::Result<()> {
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let cmd = ConfigAsyncCmd::AddBeta {
+                    key: req.config_key,
+                    value: req.value,
+                    beta_ips: req.beta_ips,
+                    op_user: req.op_user,
+                };
+                self.config_addr.send(cmd).await?.ok();
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req: RouterRequest = req.into();
+                self.send_to_remote(addr, req).await?;
+            }
+            RouteAddr::Unknown => {
+                return Err(self.unknown_err());
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn del_beta_config(&self, req: DelConfigReq) -> anyhow::Result<()> {
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let cmd = ConfigAsyncCmd::DeleteBeta(req.config_key);
+                self.config_addr.send(cmd).await?.ok();
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req = RouterRequest::ConfigBetaDel {
+                    key: req.config_key.build_key(),
+                };
+                self.send_to_remote(addr, req).await?;
+            }
+            RouteAddr::Unknown => {
+                return Err(self.unknown_err());
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// 查询配置监听信息;客户端连接分布在各节点,需要汇总本节点与其它节点的结果
+    pub async fn query_listener(
+        &self,
+        param: ConfigListenerQueryParam,
+        node_manage: &NodeManage,
+    ) -> anyhow::Result<Vec<ConfigListenerInfo>> {
+        let mut list = self.query_local_listener(param.clone()).await?;
+        let req = RouterRequest::ConfigListenerQuery { param };
+        let request = serde_json::to_string(&req).unwrap_or_default();
+        for node in node_manage.get_other_valid_nodes().await? {
+            let addr = node.addr;
+            let payload = PayloadUtils::build_payload(RAFT_ROUTE_REQUEST, request.clone());
+            let resp = match self
+                .cluster_sender
+                .send_request(addr.clone(), payload)
+                .await
+            {
+                Ok(v) => Self::parse_router_response(v),
+                Err(err) => Err(err),
+            };
+            match resp {
+                Ok(RouterResponse::ConfigListenerResult { list: node_list }) => {
+                    list.extend(node_list)
+                }
+                Ok(_) => {}
+                Err(err) => log::warn!("query config listener from {} error,{}", &addr, err),
+            }
+        }
+        Ok(list)
+    }
+
+    ///
+    /// 查询本节点的配置监听信息,grpc监听附带最近一次变更推送的状态
+    pub async fn query_local_listener(
+        &self,
+        param: ConfigListenerQueryParam,
+    ) -> anyhow::Result<Vec<ConfigListenerInfo>> {
+        let mut list = match self
+            .config_addr
+            .send(ConfigCmd::QueryListener(Box::new(param)))
+            .await??
+        {
+            ConfigResult::ListenerInfo(list) => list,
+            _ => vec![],
+        };
+        let mut indexes = vec![];
+        let mut keys = vec![];
+        for (i, item) in list.iter().enumerate() {
+            if let (ConfigListenType::Grpc, Some(client_id)) = (item.listen_type, &item.client_id) {
+                let key = ConfigKey {
+                    data_id: item.data_id.clone(),
+                    group: item.group.clone(),
+                    tenant: item.tenant.clone(),
+                };
+                indexes.push(i);
+                keys.push((client_id.clone(), key));
+            }
+        }
+        if keys.is_empty() {
+            return Ok(list);
+        }
+        if let BiStreamManageResult::ConfigPushState(states) = self
+            .bi_stream_manage
+            .send(BiStreamManageCmd::QueryConfigPushState(keys))
+            .await??
+        {
+            for (i, state) in indexes.into_iter().zip(states) {
+                list[i].push_state = state;
+            }
+        }
+        Ok(list)
+    }
+
+    ///
+    /// 查询配置的推送状态,列出各监听客户端上报的md5是否为最新值
+    pub async fn query_push_status(
+        &self,
+        key: ConfigKey,
+        node_manage: &NodeManage,
+    ) -> anyhow::Result<Vec<ConfigPushStatusInfo>> {
+        let param = ConfigListenerQueryParam {
+            key: Some(key.build_key()),
+            ..Default::default()
+        };
+        let listeners = self.query_listener(param, node_manage).await?;
+        //灰度配置按客户端ip区分,相同ip的最新md5只查询一次
+        let mut md5_map: HashMap<Arc<String>, Arc<String>> = HashMap::new();
+        let mut list = Vec::with_capacity(listeners.len());
+        for listener in listeners {
+            let latest_md5 = match md5_map.get(&listener.client_ip) {
+                Some(v) => v.clone(),
+                None => {
+                    let cmd =
+                        ConfigCmd::GetByClient(key.clone(), listener.client_ip.clone(), false);
+                    let md5 = match self.config_addr.send(cmd).await?? {
+                        ConfigResult::Data { md5, .. } => md5,
+                        _ => Arc::new(String::new()),
+                    };
+                    md5_map.insert(listener.client_ip.clone(), md5.clone());
+                    md5
+                }
+            };
+            list.push(ConfigPushStatusInfo {
+                is_latest: listener.md5 == latest_md5,
+                latest_md5,
+                listener,
+            });
+        }
+        Ok(list)
+    }
+
+    async fn send_to_remote(&self, addr: Arc<String>, req: RouterRequest) -> anyhow::Result<()> {
+        let request = serde_json::to_string(&req).unwrap_or_default();
+        let payload = PayloadUtils::build_payload(RAFT_ROUTE_REQUEST, request);
+        let resp_payload = self.cluster_sender.send_request(addr, payload).await?;
+        let body_vec = resp_payload.body.unwrap_or_default().value;
+        let _: RouterResponse = serde_json::from_slice(&body_vec)?;
+        Ok(())
+    }
 }
@@ -10,8 +10,12 @@ use serde::{Deserialize, Serialize};
 
 use actix::prelude::*;
 
-use crate::common::constant::CACHE_TREE_NAME;
+use crate::audit::model::AuditLogRetention;
+use crate::common::constant::{AUDIT_LOG_TREE_NAME, CACHE_TREE_NAME, NAMING_INSTANCE_TREE_NAME};
 use crate::common::sequence_utils::SimpleSequence;
+use crate::common::AppSysConfig;
+use crate::naming::core::{NamingActor, NamingCmd};
+use crate::naming::model::{Instance, InstanceDo};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
 use crate::{
@@ -79,6 +83,8 @@ pub struct TableManager {
     pub table_map: HashMap<Arc<String>, TableInfo>,
     raft: Option<Weak<NacosRaft>>,
     cache_manager: Option<Addr<CacheManager>>,
+    naming_addr: Option<Addr<NamingActor>>,
+    audit_retention: AuditLogRetention,
 }
 
 impl TableManager {
@@ -205,6 +211,47 @@ impl TableManager {
         }
     }
 
+    ///
+    /// 按key前缀分页查询,只遍历前缀范围内的数据
+    pub(crate) fn query_prefix_page_list(
+        &self,
+        name: Arc<String>,
+        prefix: &[u8],
+        offset: Option<i64>,
+        limit: Option<i64>,
+        is_rev: bool,
+    ) -> (usize, Vec<TableKV>) {
+        if let Some(table_info) = self.table_map.get(&name) {
+            let range = || {
+                table_info
+                    .table_data
+                    .range(prefix.to_vec()..)
+                    .take_while(|(k, _)| k.starts_with(prefix))
+            };
+            let total = range().count();
+            let offset = offset.unwrap_or_default() as usize;
+            let limit = limit.map(|v| v as usize).unwrap_or(total);
+            let ret = if is_rev {
+                let list: Vec<_> = range().collect();
+                list.into_iter()
+                    .rev()
+                    .skip(offset)
+                    .take(limit)
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect()
+            } else {
+                range()
+                    .skip(offset)
+                    .take(limit)
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect()
+            };
+            (total, ret)
+        } else {
+            (0, vec![])
+        }
+    }
+
     pub(crate) fn query_list_count(&self, name: &Arc<String>, like_key: &Option<String>) -> usize {
         if let Some(table_info) = self.table_map.get(name) {
             if let Some(like_key) = like_key {
@@ -251,6 +298,38 @@ impl TableManager {
         ret.push((k.to_vec(), v.to_vec()));
     }
 
+    ///
+    /// 持久化实例变更后同步到NamingActor
+    fn notify_naming_update(&self, value: &[u8]) {
+        if let Some(naming_addr) = &self.naming_addr {
+            match InstanceDo::from_bytes(value) {
+                Ok(instance_do) => {
+                    let instance: Instance = instance_do.into();
+                    naming_addr.do_send(NamingCmd::Update(instance, None));
+                }
+                Err(e) => log::error!("decode persistent instance error,{}", e),
+            }
+        }
+    }
+
+    fn notify_naming_remove(&self, value: &[u8]) {
+        if let Some(naming_addr) = &self.naming_addr {
+            if let Ok(instance_do) = InstanceDo::from_bytes(value) {
+                let instance: Instance = instance_do.into();
+                naming_addr.do_send(NamingCmd::Delete(instance));
+            }
+        }
+    }
+
+    ///
+    /// 审计日志按保留规则清理,在raft apply中执行保证各节点一致
+    fn remove_expired_audit_logs(&mut self) {
+        if let Some(table_info) = self.table_map.get_mut(AUDIT_LOG_TREE_NAME.as_ref()) {
+            self.audit_retention
+                .remove_expired(&mut table_info.table_data);
+        }
+    }
+
     fn get_table_names(&self) -> Vec<Arc<String>> {
         self.table_map.values().map(|e| e.name.clone()).collect()
     }
@@ -294,6 +373,13 @@ impl Inject for TableManager {
         let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
         self.raft = raft.map(|e| Arc::downgrade(&e));
         self.cache_manager = factory_data.get_actor();
+        self.naming_addr = factory_data.get_actor();
+        if let Some(sys_config) = factory_data.get_bean::<AppSysConfig>() {
+            self.audit_retention = AuditLogRetention::new(
+                sys_config.audit_log_max_count,
+                sys_config.audit_log_max_days,
+            );
+        }
     }
 }
 
@@ -365,6 +451,13 @@ pub enum TableManagerQueryReq {
         limit: Option<i64>,
         is_rev: bool,
     },
+    QueryPrefixPageList {
+        table_name: Arc<String>,
+        prefix: Vec<u8>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+        is_rev: bool,
+    },
 }
 
 impl From<TableManagerQueryReq> for RouterRequest {
@@ -418,8 +511,14 @@ impl Handler<TableManagerReq> for TableManager {
                         };
                         cache_manager.do_send(req);
                     }
+                } else if table_name.as_str() == NAMING_INSTANCE_TREE_NAME.as_str() {
+                    self.notify_naming_update(&value);
                 }
+                let is_audit_log = table_name.as_str() == AUDIT_LOG_TREE_NAME.as_str();
                 self.insert(table_name, key, value, last_seq_id);
+                if is_audit_log {
+                    self.remove_expired_audit_logs();
+                }
                 Ok(TableManagerResult::None)
             }
             TableManagerReq::Remove { table_name, key } => {
@@ -429,8 +528,14 @@ impl Handler<TableManagerReq> for TableManager {
                         cache_manager.do_send(req);
                     }
                 }
+                let is_naming_instance = table_name.as_str() == NAMING_INSTANCE_TREE_NAME.as_str();
                 match self.remove(table_name, key) {
-                    Some(v) => Ok(TableManagerResult::Value(v.to_vec())),
+                    Some(v) => {
+                        if is_naming_instance {
+                            self.notify_naming_remove(&v);
+                        }
+                        Ok(TableManagerResult::Value(v.to_vec()))
+                    }
                     None => Ok(TableManagerResult::None),
                 }
             }
@@ -516,6 +621,17 @@ impl Handler<TableManagerQueryReq> for TableManager {
                     self.query_page_list(table_name, like_key, offset, limit, is_rev);
                 Ok(TableManagerResult::PageListResult(size, list))
             }
+            TableManagerQueryReq::QueryPrefixPageList {
+                table_name,
+                prefix,
+                offset,
+                limit,
+                is_rev,
+            } => {
+                let (size, list) =
+                    self.query_prefix_page_list(table_name, &prefix, offset, limit, is_rev);
+                Ok(TableManagerResult::PageListResult(size, list))
+            }
         }
     }
 }
@@ -3,10 +3,13 @@ use std::sync::Arc;
 
 use crate::common::byte_utils::bin_to_id;
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG, USER_TREE_NAME,
+    ACCESS_KEY_TREE_NAME, AUDIT_LOG_TREE_NAME, CACHE_TREE_NAME, CONFIG_APPROVAL_TREE_NAME,
+    CONFIG_HISTORY_TREE_NAME, CONFIG_SCHEMA_TREE_NAME, CONFIG_TREE_NAME, NAMING_INSTANCE_TREE_NAME,
+    SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG, USER_TREE_NAME,
 };
+use crate::config::config_history::ConfigHistoryUtils;
 use crate::config::core::{ConfigCmd, ConfigKey};
-use crate::config::model::{ConfigRaftCmd, ConfigValueDO};
+use crate::config::model::{ConfigHistoryItemDO, ConfigRaftCmd, ConfigRaftResult, ConfigValueDO};
 use crate::raft::db::table::{TableManagerInnerReq, TableManagerReq};
 use crate::raft::filestore::raftdata::RaftDataWrap;
 use crate::raft::store::{ClientRequest, ClientResponse};
@@ -61,20 +64,28 @@ impl LogRecordLoader for LogRecordLoaderInstance {
                     value,
                     config_type,
                     desc,
+                    app_name,
+                    config_tags,
                     history_id,
                     history_table_id,
                     op_time,
                     op_user,
+                    md5,
+                    cas_md5,
                 } => {
                     let cmd = ConfigRaftCmd::ConfigAdd {
                         key,
                         value,
                         config_type,
                         desc,
+                        app_name,
+                        config_tags,
                         history_id,
                         history_table_id,
                         op_time,
                         op_user,
+                        md5,
+                        cas_md5,
                     };
                     self.data_wrap.config.do_send(cmd);
                 }
@@ -82,8 +93,30 @@ impl LogRecordLoader for LogRecordLoaderInstance {
                     let cmd = ConfigRaftCmd::ConfigRemove { key };
                     self.data_wrap.config.do_send(cmd);
                 }
+                ClientRequest::ConfigBetaSet {
+                    key,
+                    value,
+                    beta_ips,
+                    op_time,
+                    op_user,
+                    md5,
+                } => {
+                    let cmd = ConfigRaftCmd::ConfigBetaAdd {
+                        key,
+                        value,
+                        beta_ips,
+                        op_time,
+                        op_user,
+                        md5,
+                    };
+                    self.data_wrap.config.do_send(cmd);
+                }
+                ClientRequest::ConfigBetaRemove { key } => {
+                    let cmd = ConfigRaftCmd::ConfigBetaRemove { key };
+                    self.data_wrap.config.do_send(cmd);
+                }
                 ClientRequest::TableManagerReq(req) => {
-                    self.data_wrap.table.do_send(req);
+                    self.data_wrap.do_send_table_req(req);
                 }
             },
             _ => {}
@@ -220,11 +253,46 @@ impl StateApplyManager {
         while let Ok(Some(record)) = reader.read_record().await {
             if record.tree.as_str() == CONFIG_TREE_NAME.as_str() {
                 let config_key = ConfigKey::from(&String::from_utf8(record.key)? as &str);
-                let value_do = ConfigValueDO::from_bytes(&record.value)?;
+                let mut value_do = ConfigValueDO::from_bytes(&record.value)?;
+                //旧版本快照的历史记录内联在配置中,迁移到历史记录表
+                let histories = std::mem::take(&mut value_do.histories);
+                if value_do.last_modified.is_none() {
+                    value_do.last_modified = histories.last().and_then(|e| e.last_time);
+                }
                 data_wrap
                     .config
-                    .send(ConfigCmd::InnerSet(config_key, value_do.into()))
+                    .send(ConfigCmd::InnerSet(config_key.clone(), value_do.into()))
                     .await??;
+                for item in histories {
+                    data_wrap
+                        .config
+                        .send(ConfigCmd::InnerSetHistory(config_key.clone(), item.into()))
+                        .await??;
+                }
+            } else if record.tree.as_str() == CONFIG_HISTORY_TREE_NAME.as_str() {
+                if let Some((config_key, _)) = ConfigHistoryUtils::parse_key(&record.key) {
+                    let item = ConfigHistoryItemDO::from_bytes(&record.value)?;
+                    data_wrap
+                        .config
+                        .send(ConfigCmd::InnerSetHistory(config_key, item.into()))
+                        .await??;
+                }
+            } else if record.tree.as_str() == CONFIG_SCHEMA_TREE_NAME.as_str() {
+                let req = TableManagerReq::Set {
+                    table_name: CONFIG_SCHEMA_TREE_NAME.clone(),
+                    key: record.key,
+                    value: record.value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == CONFIG_APPROVAL_TREE_NAME.as_str() {
+                let req = TableManagerReq::Set {
+                    table_name: CONFIG_APPROVAL_TREE_NAME.clone(),
+                    key: record.key,
+                    value: record.value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
             } else if record.tree.as_str() == SEQUENCE_TREE_NAME.as_str() {
                 let key = String::from_utf8(record.key)?;
                 let last_id = bin_to_id(&record.value);
@@ -244,6 +312,24 @@ impl StateApplyManager {
                     last_seq_id: None,
                 };
                 data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == ACCESS_KEY_TREE_NAME.as_str() {
+                let key = record.key;
+                let value = record.value;
+                let req = TableManagerReq::Set {
+                    table_name: ACCESS_KEY_TREE_NAME.clone(),
+                    key,
+                    value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == AUDIT_LOG_TREE_NAME.as_str() {
+                let req = TableManagerReq::Set {
+                    table_name: AUDIT_LOG_TREE_NAME.clone(),
+                    key: record.key,
+                    value: record.value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
             } else if record.tree.as_str() == CACHE_TREE_NAME.as_str() {
                 let key = record.key;
                 let value = record.value;
@@ -254,6 +340,16 @@ impl StateApplyManager {
                     last_seq_id: None,
                 };
                 data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == NAMING_INSTANCE_TREE_NAME.as_str() {
+                let key = record.key;
+                let value = record.value;
+                let req = TableManagerReq::Set {
+                    table_name: NAMING_INSTANCE_TREE_NAME.clone(),
+                    key,
+                    value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
             }
         }
         Ok(())
@@ -308,10 +404,14 @@ impl StateApplyManager {
                 value,
                 config_type,
                 desc,
+                app_name,
+                config_tags,
                 history_id,
                 history_table_id,
                 op_time,
                 op_user,
+                md5,
+                cas_md5,
             } => {
                 if let Some(raft_data_wrap) = &self.data_wrap {
                     let cmd = ConfigRaftCmd::ConfigAdd {
@@ -319,10 +419,14 @@ impl StateApplyManager {
                         value,
                         config_type,
                         desc,
+                        app_name,
+                        config_tags,
                         history_id,
                         history_table_id,
                         op_time,
                         op_user,
+                        md5,
+                        cas_md5,
                     };
                     raft_data_wrap.config.do_send(cmd);
                 }
@@ -333,9 +437,35 @@ impl StateApplyManager {
                     raft_data_wrap.config.do_send(cmd);
                 }
             }
+            ClientRequest::ConfigBetaSet {
+                key,
+                value,
+                beta_ips,
+                op_time,
+                op_user,
+                md5,
+            } => {
+                if let Some(raft_data_wrap) = &self.data_wrap {
+                    let cmd = ConfigRaftCmd::ConfigBetaAdd {
+                        key,
+                        value,
+                        beta_ips,
+                        op_time,
+                        op_user,
+                        md5,
+                    };
+                    raft_data_wrap.config.do_send(cmd);
+                }
+            }
+            ClientRequest::ConfigBetaRemove { key } => {
+                if let Some(raft_data_wrap) = &self.data_wrap {
+                    let cmd = ConfigRaftCmd::ConfigBetaRemove { key };
+                    raft_data_wrap.config.do_send(cmd);
+                }
+            }
             ClientRequest::TableManagerReq(req) => {
                 if let Some(raft_data_wrap) = &self.data_wrap {
-                    raft_data_wrap.table.do_send(req);
+                    raft_data_wrap.do_send_table_req(req);
                 }
             }
         };
@@ -366,31 +496,65 @@ impl StateApplyManager {
                 value,
                 config_type,
                 desc,
+                app_name,
+                config_tags,
                 history_id,
                 history_table_id,
                 op_time,
                 op_user,
+                md5,
+                cas_md5,
             } => {
                 let cmd = ConfigRaftCmd::ConfigAdd {
                     key,
                     value,
                     config_type,
                     desc,
+                    app_name,
+                    config_tags,
                     history_id,
                     history_table_id,
                     op_time,
                     op_user,
+                    md5,
+                    cas_md5,
                 };
-                raft_data_wrap.config.send(cmd).await??;
-                Ok(ClientResponse::Success)
+                match raft_data_wrap.config.send(cmd).await?? {
+                    ConfigRaftResult::CasConflict => Ok(ClientResponse::ConfigCasConflict),
+                    _ => Ok(ClientResponse::Success),
+                }
             }
             ClientRequest::ConfigRemove { key } => {
                 let cmd = ConfigRaftCmd::ConfigRemove { key };
                 raft_data_wrap.config.send(cmd).await??;
                 Ok(ClientResponse::Success)
             }
+            ClientRequest::ConfigBetaSet {
+                key,
+                value,
+                beta_ips,
+                op_time,
+                op_user,
+                md5,
+            } => {
+                let cmd = ConfigRaftCmd::ConfigBetaAdd {
+                    key,
+                    value,
+                    beta_ips,
+                    op_time,
+                    op_user,
+                    md5,
+                };
+                raft_data_wrap.config.send(cmd).await??;
+                Ok(ClientResponse::Success)
+            }
+            ClientRequest::ConfigBetaRemove { key } => {
+                let cmd = ConfigRaftCmd::ConfigBetaRemove { key };
+                raft_data_wrap.config.send(cmd).await??;
+                Ok(ClientResponse::Success)
+            }
             ClientRequest::TableManagerReq(req) => {
-                raft_data_wrap.table.send(req).await??;
+                raft_data_wrap.send_table_req(req).await?;
                 Ok(ClientResponse::Success)
             }
         };
@@ -1,6 +1,8 @@
+use crate::common::constant::CONFIG_HISTORY_TREE_NAME;
 use crate::config::core::ConfigActor;
+use crate::config::model::{ConfigRaftCmd, ConfigRaftResult};
 use crate::raft::cache::CacheManager;
-use crate::raft::db::table::TableManager;
+use crate::raft::db::table::{TableManager, TableManagerReq};
 use actix::prelude::*;
 
 #[derive(Clone)]
@@ -22,4 +24,36 @@ impl RaftDataWrap {
             //cache,
         }
     }
+
+    ///
+    /// 配置历史记录由ConfigActor写入,同表的raft请求需经ConfigActor转发,避免与配置变更的写入乱序
+    fn is_config_history_req(req: &TableManagerReq) -> bool {
+        match req {
+            TableManagerReq::Set { table_name, .. }
+            | TableManagerReq::Remove { table_name, .. } => {
+                table_name.as_str() == CONFIG_HISTORY_TREE_NAME.as_str()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn do_send_table_req(&self, req: TableManagerReq) {
+        if Self::is_config_history_req(&req) {
+            self.config.do_send(ConfigRaftCmd::HistoryTableReq(req));
+        } else {
+            self.table.do_send(req);
+        }
+    }
+
+    pub async fn send_table_req(&self, req: TableManagerReq) -> anyhow::Result<()> {
+        if Self::is_config_history_req(&req) {
+            let _: ConfigRaftResult = self
+                .config
+                .send(ConfigRaftCmd::HistoryTableReq(req))
+                .await??;
+        } else {
+            self.table.send(req).await??;
+        }
+        Ok(())
+    }
 }
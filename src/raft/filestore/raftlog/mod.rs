@@ -20,6 +20,8 @@ use super::{
     log::{LogRange, LogRecord},
     model::{LogIndexInfo, LogRecordLoader, RaftIndexDto},
 };
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
 use crate::{
     common::protobuf_utils::{
         inner_sizeof_varint, read_varint64_offset, write_varint64, FileMessageReader,
@@ -1409,3 +1411,14 @@ impl Handler<RaftLogManagerAsyncRequest> for RaftLogManager {
         Box::pin(fut)
     }
 }
+
+impl Handler<MetricsQuery> for RaftLogManager {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        Ok(vec![MetricsItem::new(
+            MetricsKey::RaftLogFileCount,
+            MetricsRecord::Gauge(self.logs.len() as f32),
+        )])
+    }
+}
@@ -10,6 +10,9 @@ use tokio::{
 };
 
 use crate::common::protobuf_utils::MessageBufReader;
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::now_millis;
 
 use super::{
     log::{LogSnapshotItem, SnapshotHeader, SnapshotRange},
@@ -249,6 +252,11 @@ pub struct RaftSnapshotManager {
     building: Option<SnapshotRange>,
     index_manager: Option<Addr<RaftIndexManager>>,
     is_init: bool,
+    build_start_time: u64,
+    /// 距上次指标采集新增的镜像构建次数
+    build_count: u64,
+    last_build_rt: u64,
+    last_snapshot_size: u64,
 }
 
 impl RaftSnapshotManager {
@@ -260,6 +268,10 @@ impl RaftSnapshotManager {
             building: None,
             index_manager,
             is_init: false,
+            build_start_time: 0,
+            build_count: 0,
+            last_build_rt: 0,
+            last_snapshot_size: 0,
         }
     }
 
@@ -350,6 +362,15 @@ impl RaftSnapshotManager {
         snapshot_range: SnapshotRange,
     ) -> anyhow::Result<()> {
         self.building.take();
+        if self.build_start_time > 0 {
+            self.build_count += 1;
+            self.last_build_rt = now_millis() - self.build_start_time;
+            self.build_start_time = 0;
+        }
+        let path = Self::get_snapshot_path(&self.base_path, snapshot_range.id);
+        if let Ok(metadata) = std::fs::metadata(path) {
+            self.last_snapshot_size = metadata.len();
+        }
         //1. 删除历史镜像
         let old_snapshot_len = self.snapshots.len();
         let split_index = if old_snapshot_len > 1 {
@@ -473,6 +494,7 @@ impl Handler<RaftSnapshotRequest> for RaftSnapshotManager {
             }
             RaftSnapshotRequest::NewSnapshot(header) => {
                 let next_id = self.get_next_id()?;
+                self.build_start_time = now_millis();
                 let path = Arc::new(Self::get_snapshot_path(&self.base_path, next_id));
                 let writer = self.new_writer(ctx, header, path.clone());
                 Ok(RaftSnapshotResponse::NewSnapshot(writer, next_id, path))
@@ -496,3 +518,24 @@ impl Handler<RaftSnapshotRequest> for RaftSnapshotManager {
         }
     }
 }
+
+impl Handler<MetricsQuery> for RaftSnapshotManager {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        Ok(vec![
+            MetricsItem::new(
+                MetricsKey::RaftSnapshotBuildCount,
+                MetricsRecord::CounterInc(std::mem::take(&mut self.build_count)),
+            ),
+            MetricsItem::new(
+                MetricsKey::RaftSnapshotBuildRt,
+                MetricsRecord::Gauge(self.last_build_rt as f32),
+            ),
+            MetricsItem::new(
+                MetricsKey::RaftSnapshotFileSize,
+                MetricsRecord::Gauge(self.last_snapshot_size as f32),
+            ),
+        ])
+    }
+}
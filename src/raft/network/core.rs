@@ -5,7 +5,8 @@ use async_raft_ext::raft::{
 };
 use async_raft_ext::{NodeId, RaftNetwork};
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 use crate::grpc::nacos_proto::Payload;
 use crate::grpc::PayloadUtils;
@@ -14,16 +15,66 @@ use crate::raft::store::ClientRequest;
 
 use super::factory::RaftClusterRequestSender;
 
+///
+/// 记录leader向各follower同步成功的日志位置,用于计算同步延迟
+#[derive(Debug, Default)]
+pub struct RaftReplicationTracker {
+    matched_index: RwLock<HashMap<NodeId, u64>>,
+}
+
+impl RaftReplicationTracker {
+    ///
+    /// 只有同步成功时才更新follower的同步位置
+    pub fn update_matched(&self, target: NodeId, index: u64, success: bool) {
+        if !success {
+            return;
+        }
+        if let Ok(mut map) = self.matched_index.write() {
+            let value = map.entry(target).or_default();
+            if *value < index {
+                *value = index;
+            }
+        }
+    }
+
+    pub fn get_matched(&self, target: NodeId) -> u64 {
+        self.matched_index
+            .read()
+            .map(|e| e.get(&target).cloned().unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// follower落后leader的日志数量
+    pub fn get_lag(&self, target: NodeId, last_log_index: u64) -> u64 {
+        last_log_index.saturating_sub(self.get_matched(target))
+    }
+
+    ///
+    /// 切换leader后旧的同步位置不再有效
+    pub fn clear(&self) {
+        if let Ok(mut map) = self.matched_index.write() {
+            map.clear();
+        }
+    }
+}
+
 pub struct RaftRouter {
     store: Arc<FileStore>, //get target addr
     cluster_sender: Arc<RaftClusterRequestSender>,
+    replication_tracker: Arc<RaftReplicationTracker>,
 }
 
 impl RaftRouter {
-    pub fn new(store: Arc<FileStore>, cluster_sender: Arc<RaftClusterRequestSender>) -> Self {
+    pub fn new(
+        store: Arc<FileStore>,
+        cluster_sender: Arc<RaftClusterRequestSender>,
+        replication_tracker: Arc<RaftReplicationTracker>,
+    ) -> Self {
         Self {
             store,
             cluster_sender,
+            replication_tracker,
         }
     }
 
@@ -45,6 +96,11 @@ impl RaftNetwork<ClientRequest> for RaftRouter {
         let resp_payload = self.send_request(target, payload).await?;
         let body_vec = resp_payload.body.unwrap_or_default().value;
         let res: AppendEntriesResponse = serde_json::from_slice(&body_vec)?;
+        self.replication_tracker.update_matched(
+            target,
+            req.prev_log_index + req.entries.len() as u64,
+            res.success,
+        );
         Ok(res)
     }
 
@@ -130,3 +186,27 @@ impl RaftNetwork<ClientRequest> for HttpRaftRouter {
     }
 }
  */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replication_tracker_update() {
+        let tracker = RaftReplicationTracker::default();
+        assert_eq!(tracker.get_lag(2, 10), 10);
+        tracker.update_matched(2, 8, true);
+        assert_eq!(tracker.get_matched(2), 8);
+        assert_eq!(tracker.get_lag(2, 10), 2);
+        //同步失败及过期的响应不更新同步位置
+        tracker.update_matched(2, 10, false);
+        tracker.update_matched(2, 5, true);
+        assert_eq!(tracker.get_matched(2), 8);
+        assert_eq!(tracker.get_lag(2, 10), 2);
+        tracker.update_matched(2, 10, true);
+        assert_eq!(tracker.get_lag(2, 10), 0);
+        assert_eq!(tracker.get_lag(3, 10), 10);
+        tracker.clear();
+        assert_eq!(tracker.get_matched(2), 0);
+    }
+}
@@ -25,14 +25,30 @@ pub enum ClientRequest {
         value: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        app_name: Option<Arc<String>>,
+        config_tags: Option<Arc<String>>,
         history_id: u64,
         history_table_id: Option<u64>,
         op_time: i64,
         op_user: Option<Arc<String>>,
+        md5: Option<Arc<String>>,
+        #[serde(default)]
+        cas_md5: Option<Arc<String>>,
     },
     ConfigRemove {
         key: String,
     },
+    ConfigBetaSet {
+        key: String,
+        value: Arc<String>,
+        beta_ips: Arc<String>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+        md5: Option<Arc<String>>,
+    },
+    ConfigBetaRemove {
+        key: String,
+    },
     TableManagerReq(TableManagerReq),
 }
 
@@ -42,6 +58,7 @@ impl AppData for ClientRequest {}
 pub enum ClientResponse {
     Success,
     Fail,
+    ConfigCasConflict,
 }
 
 impl Default for ClientResponse {
@@ -1,5 +1,6 @@
 use std::{collections::HashSet, sync::Arc, time::Duration};
 
+use crate::audit::core::AuditLogManager;
 use crate::common::actor_utils::{create_actor_at_thread, create_actor_at_thread2};
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::metrics::core::MetricsManager;
@@ -11,7 +12,10 @@ use crate::raft::filestore::raftlog::RaftLogManager;
 use crate::raft::filestore::raftsnapshot::RaftSnapshotManager;
 use crate::{
     common::{appdata::AppShareData, AppSysConfig},
-    config::core::ConfigActor,
+    config::{
+        config_approval::ConfigApprovalManager, config_crypto::ConfigCryptoManager,
+        config_history::ConfigHistoryRetention, core::ConfigActor,
+    },
     grpc::{bistream_manage::BiStreamManage, PayloadUtils},
     naming::{
         cluster::{
@@ -19,8 +23,12 @@ use crate::{
             node_manage::{InnerNodeManage, NodeManage},
             route::NamingRoute,
         },
+        consul_index::ConsulIndexManage,
         core::NamingActor,
+        eureka_delta::EurekaDeltaManage,
+        health_check::HealthCheckActor,
         naming_delay_nofity::DelayNotifyActor,
+        xds::core::XdsManage,
     },
     raft::{
         cache::{route::CacheRoute, CacheManager},
@@ -32,13 +40,13 @@ use crate::{
         NacosRaft,
         {
             network::{
-                core::RaftRouter,
+                core::{RaftReplicationTracker, RaftRouter},
                 factory::{RaftClusterRequestSender, RaftConnectionFactory},
             },
             store::ClientRequest,
         },
     },
-    user::UserManager,
+    user::{access_key::AccessKeyManager, UserManager},
 };
 use actix::prelude::*;
 use async_raft_ext::{raft::ClientWriteRequest, Config, Raft, RaftStorage};
@@ -62,6 +70,14 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     let base_path = Arc::new(sys_config.config_db_dir.clone());
     let factory = BeanFactory::new();
     factory.register(BeanDefinition::from_obj(sys_config.clone()));
+    let config_crypto = ConfigCryptoManager::new_by_config(&sys_config.config_cipher_keys)?;
+    factory.register(BeanDefinition::from_obj(Arc::new(config_crypto)));
+    let history_retention = ConfigHistoryRetention::new_by_config(
+        sys_config.config_history_max_count,
+        sys_config.config_history_max_days,
+        &sys_config.config_history_namespace_retention,
+    )?;
+    factory.register(BeanDefinition::from_obj(Arc::new(history_retention)));
 
     let index_manager = RaftIndexManager::new(base_path.clone());
     let (index_manager, config_addr) = create_actor_at_thread2(index_manager, ConfigActor::new());
@@ -75,6 +91,15 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         DelayNotifyActor::new().start(),
     ));
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        XdsManage::new().start(),
+    ));
+    factory.register(BeanDefinition::actor_from_obj(
+        ConsulIndexManage::new().start(),
+    ));
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        EurekaDeltaManage::new().start(),
+    ));
 
     //raft
     let conn_factory = RaftConnectionFactory::new(60).start();
@@ -112,7 +137,15 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
         apply_manager,
     ));
     factory.register(BeanDefinition::from_obj(store.clone()));
-    let raft = build_raft(&sys_config, store.clone(), cluster_sender.clone()).await?;
+    let replication_tracker = Arc::new(RaftReplicationTracker::default());
+    factory.register(BeanDefinition::from_obj(replication_tracker.clone()));
+    let raft = build_raft(
+        &sys_config,
+        store.clone(),
+        cluster_sender.clone(),
+        replication_tracker,
+    )
+    .await?;
     factory.register(BeanDefinition::from_obj(raft.clone()));
     let table_manage = TableManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
@@ -130,9 +163,15 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
         raft_addr_router.clone(),
         cluster_sender.clone(),
     ));
-    factory.register(BeanDefinition::from_obj(table_route));
+    factory.register(BeanDefinition::from_obj(table_route.clone()));
+    let bistream_manage_addr = BiStreamManage::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        bistream_manage_addr.clone(),
+    ));
+
     let config_route = Arc::new(ConfigRoute::new(
         config_addr.clone(),
+        bistream_manage_addr.clone(),
         raft_addr_router.clone(),
         cluster_sender.clone(),
     ));
@@ -149,20 +188,28 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
         naming_addr.clone(),
         naming_node_manage.clone(),
         cluster_sender.clone(),
+        table_route,
     ));
     factory.register(BeanDefinition::from_obj(naming_route.clone()));
     let naming_cluster_delay_notify_addr = ClusterInstanceDelayNotifyActor::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         naming_cluster_delay_notify_addr.clone(),
     ));
-
-    let bistream_manage_addr = BiStreamManage::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
-        bistream_manage_addr.clone(),
+        HealthCheckActor::new().start(),
     ));
 
     let user_manager = UserManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(user_manager));
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        AccessKeyManager::new().start(),
+    ));
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        ConfigApprovalManager::new().start(),
+    ));
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        AuditLogManager::new().start(),
+    ));
     let cache_manager = CacheManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         cache_manager.clone(),
@@ -197,6 +244,9 @@ pub fn build_share_data(factory_data: FactoryData) -> anyhow::Result<Arc<AppShar
         config_addr: factory_data.get_actor().unwrap(),
         naming_addr: factory_data.get_actor().unwrap(),
         bi_stream_manage: factory_data.get_actor().unwrap(),
+        xds_manage: factory_data.get_actor().unwrap(),
+        consul_index_manage: factory_data.get_actor().unwrap(),
+        eureka_delta_manage: factory_data.get_actor().unwrap(),
         raft: factory_data.get_bean().unwrap(),
         raft_store: factory_data.get_bean().unwrap(),
         sys_config,
@@ -209,6 +259,9 @@ pub fn build_share_data(factory_data: FactoryData) -> anyhow::Result<Arc<AppShar
         raft_table_route: factory_data.get_bean().unwrap(),
         raft_cache_route: factory_data.get_bean().unwrap(),
         user_manager: factory_data.get_actor().unwrap(),
+        access_key_manager: factory_data.get_actor().unwrap(),
+        config_approval_manager: factory_data.get_actor().unwrap(),
+        audit_log_manager: factory_data.get_actor().unwrap(),
         cache_manager: factory_data.get_actor().unwrap(),
         metrics_manager: factory_data.get_actor().unwrap(),
         factory_data,
@@ -221,6 +274,7 @@ async fn build_raft(
     sys_config: &Arc<AppSysConfig>,
     store: Arc<FileStore>,
     cluster_sender: Arc<RaftClusterRequestSender>,
+    replication_tracker: Arc<RaftReplicationTracker>,
 ) -> anyhow::Result<Arc<NacosRaft>> {
     match store.get_last_log_index().await {
         Ok(last_log) => log::info!(
@@ -241,7 +295,11 @@ async fn build_raft(
         .validate()
         .unwrap();
     let config = Arc::new(config);
-    let network = Arc::new(RaftRouter::new(store.clone(), cluster_sender.clone()));
+    let network = Arc::new(RaftRouter::new(
+        store.clone(),
+        cluster_sender.clone(),
+        replication_tracker,
+    ));
     let raft = Arc::new(Raft::new(
         sys_config.raft_node_id.to_owned(),
         config,
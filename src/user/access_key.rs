@@ -0,0 +1,442 @@
+use std::sync::Arc;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use crypto::{hmac::Hmac, mac::Mac, sha1::Sha1, util::fixed_time_eq};
+use serde::{Deserialize, Serialize};
+
+use crate::common::constant::{ACCESS_KEY_TREE_NAME, USER_TREE_NAME};
+use crate::common::crypto_utils::encode_base64;
+use crate::common::model::privilege::{NamespaceGroupParam, PrivilegeGroup};
+use crate::common::model::TokenSession;
+use crate::now_millis;
+use crate::raft::db::{
+    route::TableRoute,
+    table::{TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult},
+};
+
+use super::model::{PrivilegeGroupDo, UserDo, UserDto};
+
+/// 签名时间戳允许的误差(毫秒)
+const SIGN_TIMESTAMP_WINDOW: i64 = 5 * 60 * 1000;
+
+///
+/// 访问密钥,绑定到一个服务账号(用户)
+/// expire_time为0时不过期
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct AccessKeyDo {
+    #[prost(string, tag = "1")]
+    pub access_key: String,
+    #[prost(string, tag = "2")]
+    pub secret_key: String,
+    #[prost(string, tag = "3")]
+    pub username: String,
+    #[prost(string, tag = "4")]
+    pub desc: String,
+    #[prost(bool, tag = "5")]
+    pub enable: bool,
+    #[prost(uint32, tag = "6")]
+    pub gmt_create: u32,
+    #[prost(uint32, tag = "7")]
+    pub gmt_modified: u32,
+    #[prost(uint32, tag = "8")]
+    pub expire_time: u32,
+    #[prost(message, optional, tag = "9")]
+    pub namespace_privilege: Option<PrivilegeGroupDo>,
+}
+
+impl AccessKeyDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+
+    pub fn is_valid(&self, now_second: u32) -> bool {
+        self.enable && (self.expire_time == 0 || self.expire_time > now_second)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessKeyDto {
+    pub access_key: Arc<String>,
+    /// 只在创建时返回
+    pub secret_key: Option<String>,
+    pub username: Option<Arc<String>>,
+    pub desc: Option<String>,
+    pub enable: Option<bool>,
+    pub gmt_create: Option<i64>,
+    pub gmt_modified: Option<i64>,
+    pub expire_time: Option<i64>,
+    pub namespace_privilege: Option<PrivilegeGroup>,
+}
+
+impl From<AccessKeyDo> for AccessKeyDto {
+    fn from(value: AccessKeyDo) -> Self {
+        Self {
+            access_key: Arc::new(value.access_key),
+            //不直接返回密钥
+            secret_key: None,
+            username: Some(Arc::new(value.username)),
+            desc: Some(value.desc),
+            enable: Some(value.enable),
+            gmt_create: Some(value.gmt_create as i64 * 1000),
+            gmt_modified: Some(value.gmt_modified as i64 * 1000),
+            expire_time: Some(value.expire_time as i64 * 1000),
+            namespace_privilege: value.namespace_privilege.map(|e| e.into()),
+        }
+    }
+}
+
+///
+/// 请求中携带的签名信息
+/// 兼容nacos客户端: Spas-Signature = base64(hmac_sha1(sk, [resource+"+"]timestamp))
+#[derive(Debug, Clone, Default)]
+pub struct AccessKeySignInfo {
+    pub access_key: Arc<String>,
+    pub timestamp: String,
+    pub signature: String,
+    pub resource: Option<String>,
+}
+
+impl AccessKeySignInfo {
+    ///
+    /// 资源为 tenant+group 或 group
+    pub fn build_resource(param: &NamespaceGroupParam) -> Option<String> {
+        let group = param.get_group().unwrap_or_default();
+        let tenant = param.get_namespace();
+        if !tenant.is_empty() {
+            Some(format!("{}+{}", tenant, group))
+        } else if !group.is_empty() {
+            Some(group.to_owned())
+        } else {
+            None
+        }
+    }
+
+    pub fn check_timestamp(&self, now: i64) -> bool {
+        match self.timestamp.parse::<i64>() {
+            Ok(v) => (now - v).abs() <= SIGN_TIMESTAMP_WINDOW,
+            Err(_) => false,
+        }
+    }
+
+    pub fn check_signature(&self, secret_key: &str) -> bool {
+        if self.signature.is_empty() {
+            return false;
+        }
+        if Self::signature_eq(&sign(secret_key, &self.timestamp), &self.signature) {
+            return true;
+        }
+        if let Some(resource) = &self.resource {
+            let data = format!("{}+{}", resource, &self.timestamp);
+            return Self::signature_eq(&sign(secret_key, &data), &self.signature);
+        }
+        false
+    }
+
+    ///
+    /// 固定耗时比较,避免通过比较耗时推测签名
+    fn signature_eq(expected: &str, signature: &str) -> bool {
+        fixed_time_eq(expected.as_bytes(), signature.as_bytes())
+    }
+}
+
+pub fn sign(secret_key: &str, data: &str) -> String {
+    let mut mac = Hmac::new(Sha1::new(), secret_key.as_bytes());
+    mac.input(data.as_bytes());
+    encode_base64(mac.result().code())
+}
+
+fn new_key(len: usize) -> String {
+    let mut v = uuid::Uuid::new_v4().to_string().replace('-', "")
+        + &uuid::Uuid::new_v4().to_string().replace('-', "");
+    v.truncate(len);
+    v
+}
+
+#[bean(inject)]
+#[derive(Default)]
+pub struct AccessKeyManager {
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+}
+
+impl AccessKeyManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get_access_key(
+        raft_table_route: &Option<Arc<TableRoute>>,
+        access_key: Arc<String>,
+    ) -> anyhow::Result<AccessKeyDo> {
+        if let Some(raft_table_route) = raft_table_route {
+            let query_req = TableManagerQueryReq::GetByArcKey {
+                table_name: ACCESS_KEY_TREE_NAME.clone(),
+                key: access_key.clone(),
+            };
+            match raft_table_route.get_leader_data(query_req).await? {
+                TableManagerResult::Value(v) => AccessKeyDo::from_bytes(&v),
+                _ => Err(anyhow::anyhow!("not found access key {}", &access_key)),
+            }
+        } else {
+            Err(anyhow::anyhow!("raft_table_route is none "))
+        }
+    }
+
+    async fn check_sign(
+        table_manager: Option<Addr<TableManager>>,
+        sign_info: AccessKeySignInfo,
+    ) -> anyhow::Result<Option<Arc<TokenSession>>> {
+        let table_manager = if let Some(table_manager) = table_manager {
+            table_manager
+        } else {
+            return Ok(None);
+        };
+        let now = now_millis() as i64;
+        if sign_info.access_key.is_empty() || !sign_info.check_timestamp(now) {
+            return Ok(None);
+        }
+        let query_req = TableManagerQueryReq::GetByArcKey {
+            table_name: ACCESS_KEY_TREE_NAME.clone(),
+            key: sign_info.access_key.clone(),
+        };
+        let key_do = match table_manager.send(query_req).await?? {
+            TableManagerResult::Value(v) => AccessKeyDo::from_bytes(&v)?,
+            _ => return Ok(None),
+        };
+        if !key_do.is_valid((now / 1000) as u32) || !sign_info.check_signature(&key_do.secret_key) {
+            return Ok(None);
+        }
+        let query_req = TableManagerQueryReq::GetByArcKey {
+            table_name: USER_TREE_NAME.clone(),
+            key: Arc::new(key_do.username.clone()),
+        };
+        let user_do = match table_manager.send(query_req).await?? {
+            TableManagerResult::Value(v) => UserDo::from_bytes(&v)?,
+            _ => return Ok(None),
+        };
+        if !user_do.enable {
+            return Ok(None);
+        }
+        let user: UserDto = user_do.into();
+        //访问密钥上配置的权限优先于用户权限
+        let (namespace_privilege, config_decrypt) = match key_do.namespace_privilege {
+            Some(v) if v.enabled => {
+                let config_decrypt = v.config_decrypt;
+                (Some(Arc::new(v.into())), config_decrypt)
+            }
+            _ => (user.get_session_privilege(), user.has_config_decrypt()),
+        };
+        Ok(Some(Arc::new(TokenSession {
+            username: user.username,
+            roles: user.roles.unwrap_or_default(),
+            extend_infos: user.extend_info.unwrap_or_default(),
+            namespace_privilege,
+            config_decrypt,
+        })))
+    }
+}
+
+impl Inject for AccessKeyManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+    }
+}
+
+impl Actor for AccessKeyManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("AccessKeyManager started")
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<AccessKeyResult>")]
+pub enum AccessKeyReq {
+    Add(AccessKeyDto),
+    Update(AccessKeyDto),
+    Remove(Arc<String>),
+    QueryPageList {
+        like_key: Option<String>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+        is_rev: bool,
+    },
+    CheckSign(AccessKeySignInfo),
+}
+
+pub enum AccessKeyResult {
+    None,
+    Info(AccessKeyDto),
+    PageResult(usize, Vec<AccessKeyDto>),
+    Session(Option<Arc<TokenSession>>),
+}
+
+impl Handler<AccessKeyReq> for AccessKeyManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<AccessKeyResult>>;
+
+    fn handle(&mut self, msg: AccessKeyReq, _ctx: &mut Self::Context) -> Self::Result {
+        let raft_table_route = self.raft_table_route.clone();
+        let table_manager = self.table_manager.clone();
+        let fut = async move {
+            match msg {
+                AccessKeyReq::Add(param) => {
+                    let username = param.username.unwrap_or_default();
+                    if username.is_empty() {
+                        return Err(anyhow::anyhow!("username is empty"));
+                    }
+                    if let Some(raft_table_route) = &raft_table_route {
+                        let query_req = TableManagerQueryReq::GetByArcKey {
+                            table_name: USER_TREE_NAME.clone(),
+                            key: username.clone(),
+                        };
+                        if !matches!(
+                            raft_table_route.get_leader_data(query_req).await?,
+                            TableManagerResult::Value(_)
+                        ) {
+                            return Err(anyhow::anyhow!("not found user {}", &username));
+                        }
+                    }
+                    let now = (now_millis() / 1000) as u32;
+                    let key_do = AccessKeyDo {
+                        access_key: new_key(20),
+                        secret_key: new_key(40),
+                        username: username.as_ref().to_owned(),
+                        desc: param.desc.unwrap_or_default(),
+                        enable: param.enable.unwrap_or(true),
+                        gmt_create: now,
+                        gmt_modified: now,
+                        expire_time: param.expire_time.map(|e| (e / 1000) as u32).unwrap_or(0),
+                        namespace_privilege: param.namespace_privilege.map(|e| e.into()),
+                    };
+                    let req = TableManagerReq::Set {
+                        table_name: ACCESS_KEY_TREE_NAME.clone(),
+                        key: key_do.access_key.as_bytes().to_owned(),
+                        value: key_do.to_bytes(),
+                        last_seq_id: None,
+                    };
+                    if let Some(raft_table_route) = raft_table_route {
+                        raft_table_route.request(req).await?;
+                    }
+                    let secret_key = key_do.secret_key.clone();
+                    let mut dto: AccessKeyDto = key_do.into();
+                    dto.secret_key = Some(secret_key);
+                    Ok(AccessKeyResult::Info(dto))
+                }
+                AccessKeyReq::Update(param) => {
+                    let mut key_do =
+                        Self::get_access_key(&raft_table_route, param.access_key.clone()).await?;
+                    key_do.gmt_modified = (now_millis() / 1000) as u32;
+                    if let Some(desc) = param.desc {
+                        key_do.desc = desc;
+                    }
+                    if let Some(enable) = param.enable {
+                        key_do.enable = enable;
+                    }
+                    if let Some(expire_time) = param.expire_time {
+                        key_do.expire_time = (expire_time / 1000) as u32;
+                    }
+                    if let Some(namespace_privilege) = param.namespace_privilege {
+                        key_do.namespace_privilege = Some(namespace_privilege.into());
+                    }
+                    let req = TableManagerReq::Set {
+                        table_name: ACCESS_KEY_TREE_NAME.clone(),
+                        key: key_do.access_key.as_bytes().to_owned(),
+                        value: key_do.to_bytes(),
+                        last_seq_id: None,
+                    };
+                    if let Some(raft_table_route) = raft_table_route {
+                        raft_table_route.request(req).await?;
+                    }
+                    Ok(AccessKeyResult::Info(key_do.into()))
+                }
+                AccessKeyReq::Remove(access_key) => {
+                    let req = TableManagerReq::Remove {
+                        table_name: ACCESS_KEY_TREE_NAME.clone(),
+                        key: access_key.as_bytes().to_owned(),
+                    };
+                    if let Some(raft_table_route) = raft_table_route {
+                        raft_table_route.request(req).await?;
+                    }
+                    Ok(AccessKeyResult::None)
+                }
+                AccessKeyReq::QueryPageList {
+                    like_key,
+                    offset,
+                    limit,
+                    is_rev,
+                } => {
+                    if let Some(table_manager) = &table_manager {
+                        let query_req = TableManagerQueryReq::QueryPageList {
+                            table_name: ACCESS_KEY_TREE_NAME.clone(),
+                            like_key,
+                            offset,
+                            limit,
+                            is_rev,
+                        };
+                        if let TableManagerResult::PageListResult(size, list) =
+                            table_manager.send(query_req).await??
+                        {
+                            let mut key_list = Vec::with_capacity(list.len());
+                            for (_, v) in list {
+                                key_list.push(AccessKeyDo::from_bytes(&v)?.into());
+                            }
+                            return Ok(AccessKeyResult::PageResult(size, key_list));
+                        }
+                    }
+                    Ok(AccessKeyResult::PageResult(0, vec![]))
+                }
+                AccessKeyReq::CheckSign(sign_info) => Ok(AccessKeyResult::Session(
+                    Self::check_sign(table_manager, sign_info).await?,
+                )),
+            }
+        }
+        .into_actor(self)
+        .map(|res, _act, _ctx| res);
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_key_sign() {
+        let sk = "secret";
+        let info = AccessKeySignInfo {
+            access_key: Arc::new("ak".to_owned()),
+            timestamp: "1700000000000".to_owned(),
+            signature: sign(sk, "dev+DEFAULT_GROUP+1700000000000"),
+            resource: Some("dev+DEFAULT_GROUP".to_owned()),
+        };
+        assert!(info.check_signature(sk));
+        assert!(!info.check_signature("other"));
+        assert!(info.check_timestamp(1700000000000 + 1000));
+        assert!(!info.check_timestamp(1700000000000 + SIGN_TIMESTAMP_WINDOW + 1));
+        let param = NamespaceGroupParam {
+            group: Some("DEFAULT_GROUP".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            AccessKeySignInfo::build_resource(&param),
+            Some("DEFAULT_GROUP".to_owned())
+        );
+    }
+}
@@ -22,6 +22,7 @@ use self::{
     permission::USER_ROLE_MANAGER,
 };
 
+pub mod access_key;
 pub mod api;
 pub mod model;
 pub mod permission;
@@ -207,6 +208,7 @@ impl Handler<UserManagerReq> for UserManager {
                             .collect(),
                         enable: true,
                         extend_info: user.extend_info.unwrap_or_default(),
+                        namespace_privilege: user.namespace_privilege.map(|e| e.into()),
                     };
                     let user_data = user_do.to_bytes();
                     let req = TableManagerReq::Set {
@@ -256,6 +258,9 @@ impl Handler<UserManagerReq> for UserManager {
                             last_user.extend_info = extend_info;
                         }
                     }
+                    if let Some(namespace_privilege) = user.namespace_privilege {
+                        last_user.namespace_privilege = Some(namespace_privilege.into());
+                    }
                     if let Some(roles) = user.roles {
                         if !roles.is_empty() {
                             last_user.roles =
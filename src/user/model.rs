@@ -2,7 +2,8 @@ use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
-use crate::user::permission::UserRoleHelper;
+use crate::common::model::privilege::{NamespacePrivilege, PrivilegeGroup};
+use crate::user::permission::{UserRoleHelper, USER_ROLE_MANAGER};
 
 #[derive(Clone, prost::Message, Serialize, Deserialize)]
 pub struct UserDo {
@@ -23,6 +24,64 @@ pub struct UserDo {
     #[prost(map = "string, string", tag = "8")]
     pub extend_info:
         ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "9")]
+    pub namespace_privilege: Option<PrivilegeGroupDo>,
+}
+
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct NamespacePrivilegeDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, repeated, tag = "2")]
+    pub groups: ::prost::alloc::vec::Vec<String>,
+    #[prost(bool, tag = "3")]
+    pub writable: bool,
+}
+
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct PrivilegeGroupDo {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(message, repeated, tag = "2")]
+    pub items: ::prost::alloc::vec::Vec<NamespacePrivilegeDo>,
+    #[prost(bool, tag = "3")]
+    pub config_decrypt: bool,
+}
+
+impl From<PrivilegeGroup> for PrivilegeGroupDo {
+    fn from(value: PrivilegeGroup) -> Self {
+        Self {
+            enabled: value.enabled,
+            items: value
+                .items
+                .into_iter()
+                .map(|e| NamespacePrivilegeDo {
+                    namespace_id: e.namespace_id.as_ref().to_owned(),
+                    groups: e.groups.iter().map(|g| g.as_ref().to_owned()).collect(),
+                    writable: e.writable,
+                })
+                .collect(),
+            config_decrypt: value.config_decrypt,
+        }
+    }
+}
+
+impl From<PrivilegeGroupDo> for PrivilegeGroup {
+    fn from(value: PrivilegeGroupDo) -> Self {
+        Self {
+            enabled: value.enabled,
+            items: value
+                .items
+                .into_iter()
+                .map(|e| NamespacePrivilege {
+                    namespace_id: Arc::new(e.namespace_id),
+                    groups: e.groups.into_iter().map(Arc::new).collect(),
+                    writable: e.writable,
+                })
+                .collect(),
+            config_decrypt: value.config_decrypt,
+        }
+    }
 }
 
 impl UserDo {
@@ -48,6 +107,37 @@ pub struct UserDto {
     pub enable: Option<bool>,
     pub roles: Option<Vec<Arc<String>>>,
     pub extend_info: Option<HashMap<String, String>>,
+    pub namespace_privilege: Option<PrivilegeGroup>,
+}
+
+impl UserDto {
+    ///
+    /// 会话中使用的命名空间权限,管理员或未启用时不限制
+    pub fn get_session_privilege(&self) -> Option<Arc<PrivilegeGroup>> {
+        if let Some(roles) = &self.roles {
+            if roles.contains(&USER_ROLE_MANAGER) {
+                return None;
+            }
+        }
+        self.namespace_privilege
+            .as_ref()
+            .filter(|e| e.enabled)
+            .map(|e| Arc::new(e.clone()))
+    }
+
+    ///
+    /// 是否可以读取cipher-配置的明文,管理员默认授权
+    pub fn has_config_decrypt(&self) -> bool {
+        if let Some(roles) = &self.roles {
+            if roles.contains(&USER_ROLE_MANAGER) {
+                return true;
+            }
+        }
+        self.namespace_privilege
+            .as_ref()
+            .map(|e| e.config_decrypt)
+            .unwrap_or(false)
+    }
 }
 
 impl From<UserDo> for UserDto {
@@ -67,6 +157,7 @@ impl From<UserDo> for UserDto {
             enable: Some(value.enable),
             roles: Some(roles),
             extend_info: Some(value.extend_info),
+            namespace_privilege: value.namespace_privilege.map(|e| e.into()),
         }
     }
 }
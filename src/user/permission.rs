@@ -205,6 +205,16 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/user/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/user/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/user/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/accesskey/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/accesskey/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/accesskey/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/accesskey/remove",HTTP_METHOD_ALL),
+    ]);
+
+    static ref M_AUDIT_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //path
+        R::Path("/rnacos/api/console/v2/audit/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/audit/export",HTTP_METHOD_GET),
     ]);
 
     static ref M_CONFIG_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -226,6 +236,11 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/download",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/info",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/history",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/schema/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/push/status",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/approval/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/approval/info",HTTP_METHOD_GET),
     ]);
 
     static ref M_CONFIG_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -253,6 +268,16 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/cipher/rotate",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/schema/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/push/status",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/schema/set",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/approval/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/approval/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/approval/submit",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/approval/approve",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/approval/reject",HTTP_METHOD_ALL),
     ]);
 
     static ref M_NAMING_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -336,6 +361,7 @@ lazy_static::lazy_static! {
         &M_CONFIG_MANAGE,
         &M_NAMING_MANAGE,
         &M_USER_MANAGE,
+        &M_AUDIT_MANAGE,
         &M_METRICS_VISITOR,
     ]));
 
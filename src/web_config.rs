@@ -6,7 +6,10 @@ use rnacos_web_dist_wrap::get_embedded_file;
 use crate::common::AppSysConfig;
 use crate::console::api::{console_api_config_v1, console_api_config_v2};
 use crate::openapi::auth::{login_config, mock_token};
+use crate::openapi::consul::consul_config;
+use crate::openapi::eureka::eureka_config;
 use crate::openapi::metrics::metrics_config;
+use crate::openapi::spring_config::spring_cloud_config;
 use crate::openapi::{openapi_config, v1::console as nacos_console};
 use crate::raft::network::raft_config;
 
@@ -101,12 +104,30 @@ pub fn app_config(conf_data: AppSysConfig) -> impl FnOnce(&mut ServiceConfig) {
                 );
             login_config(config);
             metrics_config(config);
+            if conf_data.consul_api_enable {
+                consul_config(config);
+            }
+            if conf_data.eureka_api_enable {
+                eureka_config(config);
+            }
+            if conf_data.spring_config_api_enable {
+                spring_cloud_config(config);
+            }
             raft_config(config);
             nacos_console_api_config(config);
             config.configure(openapi_config(conf_data));
         } else {
             login_config(config);
             metrics_config(config);
+            if conf_data.consul_api_enable {
+                consul_config(config);
+            }
+            if conf_data.eureka_api_enable {
+                eureka_config(config);
+            }
+            if conf_data.spring_config_api_enable {
+                spring_cloud_config(config);
+            }
             raft_config(config);
             nacos_console_api_config(config);
             config.configure(openapi_config(conf_data));